@@ -0,0 +1,50 @@
+// nc-event-cpi - emit_cpi! without `ctx` in scope
+//
+// Anchor's emit_cpi! reads the event authority and its bump from `ctx`, so
+// helpers shared by several instructions of nc_token and
+// multisig_governance take an `EventCpi` built from the #[event_cpi]
+// context instead. Events land in the same self-CPI inner instruction.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+
+pub struct EventCpi<'a, 'info> {
+    program_id: Pubkey,
+    authority: &'a AccountInfo<'info>,
+    bump: u8,
+}
+
+impl<'a, 'info> EventCpi<'a, 'info> {
+    // `authority` and `bump` are the context's event_authority account and
+    // bump; `program_id` the emitting program (crate::ID)
+    pub fn new(program_id: Pubkey, authority: &'a AccountInfo<'info>, bump: u8) -> Self {
+        Self {
+            program_id,
+            authority,
+            bump,
+        }
+    }
+
+    // The self-CPI emit_cpi! makes: event data behind EVENT_IX_TAG, signed
+    // by the event authority PDA
+    pub fn emit<E: anchor_lang::Event>(&self, event: &E) -> Result<()> {
+        // Sized once and moved into the instruction - nothing is freed on
+        // the program heap, and list batches emit one of these per entry
+        let event_data = event.data();
+        let mut data = Vec::with_capacity(anchor_lang::event::EVENT_IX_TAG_LE.len() + event_data.len());
+        data.extend_from_slice(anchor_lang::event::EVENT_IX_TAG_LE);
+        data.extend_from_slice(&event_data);
+        let ix = Instruction {
+            program_id: self.program_id,
+            accounts: vec![AccountMeta::new_readonly(self.authority.key(), true)],
+            data,
+        };
+        invoke_signed(
+            &ix,
+            std::slice::from_ref(self.authority),
+            &[&[b"__event_authority", &[self.bump]]],
+        )?;
+        Ok(())
+    }
+}
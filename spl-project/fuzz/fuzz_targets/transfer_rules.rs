@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nc_harness::fuzz::Scenario;
+
+fuzz_target!(|scenario: Scenario| scenario.run());
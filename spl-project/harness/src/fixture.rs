@@ -0,0 +1,342 @@
+// Deployed and initialized protocol for integration tests
+//
+// Both programs are deployed by `deployer`, who initializes them and stays
+// governance admin. `owners` approve proposals; the mint's mint and freeze
+// authority is the nc_token state PDA.
+
+use anchor_lang::prelude::AccountMeta;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::AnchorDeserialize;
+use anchor_spl::token::spl_token;
+use anchor_spl::token::spl_token::solana_program::program_option::COption;
+use anchor_spl::token::spl_token::solana_program::program_pack::{IsInitialized, Pack};
+use multisig_governance::{Governance, PendingTransaction, ProposalMetadata};
+use nc_sdk::governance::{self as gov, Proposal, ProposalData};
+use nc_sdk::{pda, token, GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+use nc_token::NCTokenState;
+
+use crate::{Account, Failure, Outcome, Svm};
+
+pub const DECIMALS: u8 = 9;
+pub const DEFAULT_COOLDOWN: i64 = 90 * 60;
+// List batch entries per queue / append_list_batch transaction
+pub const BATCH_ENTRIES_PER_TX: usize = 20;
+
+const AIRDROP: u64 = 1_000_000_000_000;
+
+pub struct Protocol {
+    pub svm: Svm,
+    pub deployer: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub mint: Pubkey,
+    pub bridge: Pubkey,
+    pub treasury: Pubkey,
+    pub bond: Pubkey,
+}
+
+impl Protocol {
+    // Programs and mint in place, nothing initialized
+    pub fn deploy() -> Self {
+        let svm = Svm::new();
+        let deployer = Pubkey::new_unique();
+        svm.airdrop(&deployer, AIRDROP);
+        svm.add_upgradeable_program(NC_TOKEN_PROGRAM_ID, nc_token::entry, deployer);
+        svm.add_upgradeable_program(GOVERNANCE_PROGRAM_ID, multisig_governance::entry, deployer);
+
+        let protocol = Self {
+            svm,
+            deployer,
+            owners: Vec::new(),
+            mint: Pubkey::new_unique(),
+            bridge: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            bond: Pubkey::new_unique(),
+        };
+        protocol.create_mint(protocol.mint);
+        protocol
+    }
+
+    // Both programs initialized and linked, with `owners` owners of whom
+    // `required` must approve
+    pub fn new(owners: usize, required: u8) -> Self {
+        let mut protocol = Self::deploy();
+        let deployer = protocol.deployer;
+        protocol.owners = (0..owners).map(|_| protocol.wallet()).collect();
+
+        let setup = [
+            token::initialize(
+                deployer,
+                protocol.bridge,
+                protocol.treasury,
+                protocol.bond,
+                protocol.mint,
+            ),
+            gov::initialize(deployer),
+            gov::set_token(deployer),
+        ];
+        protocol.process(&setup, &[deployer]).expect("protocol setup");
+        // Eight owners fit a transaction
+        for owners in protocol.owners.chunks(8) {
+            let add: Vec<Instruction> = owners.iter().map(|owner| gov::add_owner(deployer, *owner)).collect();
+            protocol.process(&add, &[deployer]).expect("add owners");
+        }
+        protocol
+            .process(&[gov::set_required_approvals(deployer, required)], &[deployer])
+            .expect("set required approvals");
+        protocol
+    }
+
+    pub fn process(&self, instructions: &[Instruction], signers: &[Pubkey]) -> Result<Outcome, Failure> {
+        self.svm.process(instructions, signers)
+    }
+
+    // Funded system account
+    pub fn wallet(&self) -> Pubkey {
+        let wallet = Pubkey::new_unique();
+        self.svm.airdrop(&wallet, AIRDROP);
+        wallet
+    }
+
+    // Mint with the nc_token state PDA as mint and freeze authority - the
+    // NC mint, or a look-alike when `address` is any other key
+    pub fn create_mint(&self, address: Pubkey) {
+        let state = pda::nc_token_state().0;
+        self.pack(
+            address,
+            spl_token::state::Mint {
+                mint_authority: COption::Some(state),
+                supply: 0,
+                decimals: DECIMALS,
+                is_initialized: true,
+                freeze_authority: COption::Some(state),
+            },
+        );
+    }
+
+    // Token account of the NC mint holding `amount` (minted directly)
+    pub fn token_account(&self, owner: &Pubkey, amount: u64) -> Pubkey {
+        self.token_account_of(&self.mint, owner, amount)
+    }
+
+    // Token account of `mint` holding `amount` (minted directly)
+    pub fn token_account_of(&self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.pack(
+            address,
+            spl_token::state::Account {
+                mint: *mint,
+                owner: *owner,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            },
+        );
+        let mut mint_state: spl_token::state::Mint = self.unpack(mint);
+        mint_state.supply += amount;
+        self.pack(*mint, mint_state);
+        address
+    }
+
+    pub fn token(&self, address: &Pubkey) -> spl_token::state::Account {
+        self.unpack(address)
+    }
+
+    pub fn balance(&self, address: &Pubkey) -> u64 {
+        self.token(address).amount
+    }
+
+    pub fn is_frozen(&self, address: &Pubkey) -> bool {
+        self.token(address).is_frozen()
+    }
+
+    pub fn state(&self) -> NCTokenState {
+        self.svm.get(&pda::nc_token_state().0).expect("nc_token state")
+    }
+
+    pub fn governance(&self) -> Governance {
+        self.svm.get(&pda::governance().0).expect("governance")
+    }
+
+    pub fn proposal(&self, tx_id: u64) -> Option<PendingTransaction> {
+        self.svm.get(&pda::proposal(tx_id).0)
+    }
+
+    // Owners of the proposal's approval records, current owners or not
+    pub fn approvers(&self, tx_id: u64) -> Vec<Pubkey> {
+        let proposal = pda::proposal(tx_id).0;
+        self.svm
+            .program_accounts(&GOVERNANCE_PROGRAM_ID)
+            .into_iter()
+            .filter_map(|(_, account)| nc_sdk::accounts::approval_record(&account.data).ok())
+            .filter(|record| record.proposal == proposal)
+            .map(|record| record.owner)
+            .collect()
+    }
+
+    // close_proposal with every approval record, a transaction per batch
+    // (as nc-gov sends them); the outcome of the last
+    pub fn close(&self, closer: &Pubkey, tx_id: u64) -> Result<Outcome, Failure> {
+        let proposal = self.proposal(tx_id).expect("proposal");
+        let mut outcome = None;
+        for ix in gov::close_proposal_batches(*closer, &proposal, &self.approvers(tx_id)) {
+            outcome = Some(self.process(&[ix], &[*closer])?);
+        }
+        Ok(outcome.expect("at least one batch"))
+    }
+
+    pub fn transfer(
+        &self,
+        from_authority: &Pubkey,
+        from_account: &Pubkey,
+        to_account: &Pubkey,
+        amount: u64,
+    ) -> Result<Outcome, Failure> {
+        self.process(
+            &[token::transfer(self.mint, *from_account, *to_account, *from_authority, amount)],
+            &[*from_authority],
+        )
+    }
+
+    // Simulates a bool view and decodes its return data
+    pub fn view(&self, ix: Instruction) -> bool {
+        self.returned(ix)
+    }
+
+    // Simulates an nc_token instruction and decodes its return data
+    pub fn returned<T: AnchorDeserialize>(&self, ix: Instruction) -> T {
+        let outcome = self.svm.simulate(&[ix], &[]).expect("view");
+        let (program_id, data) = outcome.return_data.expect("return data");
+        assert_eq!(program_id, NC_TOKEN_PROGRAM_ID);
+        T::try_from_slice(&data).expect("return data")
+    }
+
+    // Queued by the first owner; returns the transaction id. List batches
+    // go in BATCH_ENTRIES_PER_TX chunks, queued then appended (as nc-gov
+    // sends them)
+    pub fn queue(&self, mut proposal: Proposal) -> u64 {
+        let tx_id = self.governance().next_transaction_id;
+        let mut appends = Vec::new();
+        if let Proposal::ListBatch { updates, .. } = &mut proposal {
+            let rest = updates.split_off(updates.len().min(BATCH_ENTRIES_PER_TX));
+            appends = rest.chunks(BATCH_ENTRIES_PER_TX).map(<[_]>::to_vec).collect();
+        }
+        self.process(
+            &[gov::queue(self.owners[0], tx_id, proposal, metadata("test"))],
+            &[self.owners[0]],
+        )
+        .expect("queue");
+        for chunk in appends {
+            self.process(&[gov::append_list_batch(self.owners[0], tx_id, chunk)], &[self.owners[0]])
+                .expect("append list batch");
+        }
+        tx_id
+    }
+
+    pub fn approve(&self, owner: &Pubkey, tx_id: u64) -> Result<Outcome, Failure> {
+        let remaining = self.execution_accounts(tx_id, Some(owner));
+        self.process(&[gov::approve_transaction(*owner, tx_id, None, remaining)], &[*owner])
+    }
+
+    pub fn execute(&self, owner: &Pubkey, tx_id: u64) -> Result<Outcome, Failure> {
+        let remaining = self.execution_accounts(tx_id, None);
+        self.process(&[gov::execute_transaction(*owner, tx_id, None, remaining)], &[*owner])
+    }
+
+    pub fn crank(&self, tx_id: u64) -> Result<Outcome, Failure> {
+        let cranker = self.wallet();
+        let remaining = self.execution_accounts(tx_id, None);
+        self.process(&[gov::crank_execute(cranker, tx_id, remaining)], &[cranker])
+    }
+
+    // Queues, waits out the cooldown and approves with the first `required`
+    // owners - the last approval executes (the first chunk of a list batch)
+    pub fn pass(&self, proposal: Proposal) -> u64 {
+        let tx_id = self.queue(proposal);
+        self.svm.advance(self.governance().cooldown_period);
+        let required = self.governance().required_approvals as usize;
+        for owner in &self.owners[..required] {
+            self.approve(owner, tx_id).expect("approve");
+        }
+        tx_id
+    }
+
+    // Approval PDAs of the other owners that exist, plus what a freeze needs
+    // (same as nc-gov)
+    pub fn execution_accounts(&self, tx_id: u64, approver: Option<&Pubkey>) -> Vec<AccountMeta> {
+        let owners: Vec<Pubkey> = self
+            .governance()
+            .owners
+            .into_iter()
+            .filter(|owner| Some(owner) != approver)
+            .collect();
+        let mut remaining: Vec<AccountMeta> = gov::approval_accounts(tx_id, &owners)
+            .into_iter()
+            .filter(|meta| self.svm.account(&meta.pubkey).is_some())
+            .collect();
+
+        let Some(proposal) = self.proposal(tx_id) else {
+            return remaining;
+        };
+        let token_accounts = ProposalData::decode(proposal.tx_type, &proposal.data)
+            .map(|data| data.freeze_token_accounts())
+            .unwrap_or_default();
+        if !token_accounts.is_empty() {
+            let mut mints = Vec::new();
+            for token_account in &token_accounts {
+                let mint = self.token(token_account).mint;
+                if !mints.contains(&mint) {
+                    mints.push(mint);
+                }
+                remaining.push(AccountMeta::new(*token_account, false));
+            }
+            remaining.extend(mints.into_iter().map(|mint| AccountMeta::new_readonly(mint, false)));
+            remaining.push(AccountMeta::new_readonly(spl_token::ID, false));
+        }
+        remaining
+    }
+
+    fn pack<T: Pack>(&self, address: Pubkey, value: T) {
+        let mut data = vec![0; T::LEN];
+        T::pack(value, &mut data).expect("pack");
+        self.svm.set_account(
+            address,
+            Account {
+                lamports: self.svm.rent().minimum_balance(T::LEN),
+                data,
+                owner: spl_token::ID,
+                executable: false,
+            },
+        );
+    }
+
+    fn unpack<T: Pack + IsInitialized>(&self, address: &Pubkey) -> T {
+        let account = self.svm.account(address).expect("token program account");
+        T::unpack(&account.data).expect("unpack")
+    }
+}
+
+pub fn metadata(title: &str) -> ProposalMetadata {
+    ProposalMetadata {
+        title: title.to_string(),
+        description_uri: String::new(),
+        description_hash: [0; 32],
+    }
+}
+
+// Asserts the transaction failed with the given anchor error
+#[track_caller]
+pub fn assert_error<E: Into<u32>>(result: Result<Outcome, Failure>, error: E) {
+    let code = error.into();
+    match result {
+        Ok(_) => panic!("expected error {}, transaction succeeded", code),
+        Err(failure) => assert_eq!(
+            failure.error.code(),
+            Some(code),
+            "expected error {}, got {}\n{}",
+            code,
+            failure,
+            failure.logs.join("\n")
+        ),
+    }
+}
@@ -0,0 +1,401 @@
+// Fuzz scenarios for the transfer rules and sell-window math
+//
+// A scenario is a random sequence of governance list changes, pauses, clock
+// advances and transfers among a fixed cast of holders. `run` replays it
+// against both programs and panics on the first broken invariant:
+// - a blacklisted pair, or a blacklisted party and a restricted module,
+//   never transfers
+// - nothing moves over a paused route
+// - every LP sale fits 5% of its window's opening balance, counting the
+//   last 24h of hour buckets (checked against a separate model of the
+//   window, not the program's SellWindow)
+// - no program panics or fails with MathOverflow
+// - the program decides as nc_sdk::rules predicts from the accounts
+
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use arbitrary::Arbitrary;
+use nc_sdk::governance::{self as gov, Proposal};
+use nc_sdk::pda;
+use nc_token::merkle::TransferProofs;
+use nc_token::{NCTokenState, PauseModule, SellWindow};
+
+use crate::fixture::Protocol;
+use crate::{ExecError, Failure};
+
+pub const HOLDERS: usize = 5;
+pub const MAX_STEPS: usize = 64;
+
+const DAY: i64 = 24 * 60 * 60;
+const HOUR: i64 = 60 * 60;
+// Keeps the mint supply of all holders within u64
+const MAX_BALANCE: u64 = u64::MAX / (HOLDERS as u64 + 1);
+
+#[derive(Arbitrary, Debug)]
+pub struct Scenario {
+    pub balances: [u64; HOLDERS],
+    pub steps: Vec<Step>,
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Step {
+    // `party` picks a holder; `account` its token account instead of its
+    // wallet (transfers are checked by wallet on the sending side and by
+    // token account on the receiving side)
+    List {
+        list: List,
+        party: u8,
+        account: bool,
+        value: bool,
+        expires_in_hours: Option<u8>,
+    },
+    ModulePause {
+        module: Module,
+        value: bool,
+    },
+    EmergencyPause {
+        seconds: u32,
+    },
+    Unpause,
+    Advance {
+        hours: u8,
+        minutes: u8,
+    },
+    Transfer {
+        from: u8,
+        to: u8,
+        amount: Amount,
+    },
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum List {
+    Blacklist,
+    Restricted,
+    NoSellLimit,
+    LiquidityPool,
+}
+
+// Bridge / bond / treasury switches never apply - no holder is a module
+// address
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Module {
+    Global,
+    LpTrading,
+    P2p,
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Amount {
+    // Tenths of a percent of the sender's balance (up to 25.5%, so sales
+    // land on both sides of the 5% limit)
+    Share(u8),
+    // The model's remaining allowance plus an offset - right at the limit
+    Edge(i8),
+    Exact(u64),
+}
+
+struct Holder {
+    wallet: Pubkey,
+    account: Pubkey,
+}
+
+// Model of one wallet's sell window
+#[derive(Clone, Default)]
+struct Window {
+    start: i64,
+    baseline: u64,
+    sales: Vec<(i64, u64)>, // (hour start, amount)
+}
+
+impl Scenario {
+    pub fn run(&self) {
+        let protocol = Protocol::new(1, 1);
+        let admin = protocol.deployer;
+        let guardian = protocol.wallet();
+        protocol
+            .process(
+                &[
+                    gov::set_cooldown_period(admin, 1),
+                    gov::set_guardians(admin, vec![guardian], 1),
+                ],
+                &[admin],
+            )
+            .expect("fuzz setup");
+
+        let holders: Vec<Holder> = self
+            .balances
+            .iter()
+            .map(|balance| {
+                let wallet = protocol.wallet();
+                let account = protocol.token_account(&wallet, balance % MAX_BALANCE);
+                Holder { wallet, account }
+            })
+            .collect();
+        // Start with a pool and a module in the cast
+        protocol.pass(Proposal::Pair {
+            pool: holders[0].account,
+            value: true,
+        });
+        protocol.pass(Proposal::Restrict {
+            account: holders[1].wallet,
+            value: true,
+        });
+        protocol.pass(Proposal::Restrict {
+            account: holders[1].account,
+            value: true,
+        });
+
+        let mut windows: HashMap<Pubkey, Window> = HashMap::new();
+        for step in self.steps.iter().take(MAX_STEPS) {
+            match *step {
+                Step::List {
+                    list,
+                    party,
+                    account,
+                    value,
+                    expires_in_hours,
+                } => {
+                    let holder = &holders[party as usize % HOLDERS];
+                    let key = if account {
+                        holder.account
+                    } else {
+                        holder.wallet
+                    };
+                    protocol.pass(match list {
+                        List::Blacklist => Proposal::Blacklist {
+                            account: key,
+                            value,
+                            reason_code: 0,
+                            evidence_hash: [0; 32],
+                            expires_at: expires_in_hours
+                                .map_or(0, |hours| protocol.svm.now() + (hours as i64 + 1) * HOUR),
+                            freeze_token_accounts: Vec::new(),
+                        },
+                        List::Restricted => Proposal::Restrict {
+                            account: key,
+                            value,
+                        },
+                        List::NoSellLimit => Proposal::NoSellLimit {
+                            account: key,
+                            value,
+                        },
+                        List::LiquidityPool => Proposal::Pair { pool: key, value },
+                    });
+                }
+                Step::ModulePause { module, value } => {
+                    let module = match module {
+                        Module::Global => PauseModule::Global,
+                        Module::LpTrading => PauseModule::LpTrading,
+                        Module::P2p => PauseModule::P2p,
+                    };
+                    protocol.pass(Proposal::ModulePause { module, value });
+                }
+                Step::EmergencyPause { seconds } => {
+                    let max = protocol.governance().max_pause_duration;
+                    let ix = gov::set_emergency_pause(
+                        guardian,
+                        &[],
+                        1 + seconds as i64 % max,
+                        "fuzz".to_string(),
+                    );
+                    // Guardians may only lengthen a pause in force, up to
+                    // their cap, and wait out the cooldown between pauses
+                    if let Err(failure) = protocol.process(&[ix], &[guardian]) {
+                        let code = failure.error.code();
+                        assert!(
+                            [
+                                multisig_governance::ErrorCode::PauseAlreadyInForce,
+                                multisig_governance::ErrorCode::PauseLimitExceeded,
+                                multisig_governance::ErrorCode::GuardianPauseCooldown,
+                            ]
+                            .into_iter()
+                            .any(|error| code == Some(error.into())),
+                            "emergency pause: {}",
+                            failure
+                        );
+                    }
+                }
+                Step::Unpause => {
+                    protocol.pass(Proposal::Unpause);
+                }
+                Step::Advance { hours, minutes } => protocol
+                    .svm
+                    .advance((hours % 24) as i64 * HOUR + (minutes % 60) as i64 * 60),
+                Step::Transfer { from, to, amount } => {
+                    let from = &holders[from as usize % HOLDERS];
+                    let to = &holders[to as usize % HOLDERS];
+                    check_transfer(&protocol, &mut windows, from, to, amount);
+                }
+            }
+        }
+    }
+}
+
+fn check_transfer(
+    protocol: &Protocol,
+    windows: &mut HashMap<Pubkey, Window>,
+    from: &Holder,
+    to: &Holder,
+    amount: Amount,
+) {
+    let state: NCTokenState = protocol.state();
+    let now = protocol.svm.now();
+    let balance = protocol.balance(&from.account);
+    let (from_key, to_key) = (from.wallet, to.account);
+
+    let blacklisted = |key: &Pubkey| {
+        state
+            .blacklisted
+            .iter()
+            .any(|e| e.account == *key && (e.details.expires_at == 0 || now < e.details.expires_at))
+    };
+    let from_blacklisted = blacklisted(&from_key);
+    let to_blacklisted = blacklisted(&to_key);
+    let from_restricted = state.restricted_list.contains(&from_key);
+    let to_restricted = state.restricted_list.contains(&to_key);
+    let from_pool = state.is_liquidity_pool.contains(&from_key);
+    let to_pool = state.is_liquidity_pool.contains(&to_key);
+    let paused =
+        state.emergency_paused && (state.pause_expires_at == 0 || now < state.pause_expires_at);
+    let module_paused = |module: PauseModule| state.paused_modules & module.flag() != 0;
+
+    // Sell limit model, applied only if the sale goes through
+    let limited = to_pool && !from_restricted && !state.no_sell_limit.contains(&from_key);
+    let mut window = windows.get(&from_key).cloned().unwrap_or_default();
+    if window.start == 0 || now - window.start >= DAY {
+        window = Window {
+            start: now,
+            baseline: balance,
+            sales: Vec::new(),
+        };
+    }
+    let limit = window.baseline as u128 * 5 / 100;
+    let sold: u128 = window
+        .sales
+        .iter()
+        .filter(|(hour, _)| now - hour < DAY)
+        .map(|(_, sold)| *sold as u128)
+        .sum();
+    let amount = match amount {
+        Amount::Share(per_mille) => (balance as u128 * per_mille as u128 / 1_000) as u64,
+        Amount::Edge(offset) => {
+            (limit.saturating_sub(sold) as i128 + offset as i128).clamp(0, u64::MAX as i128) as u64
+        }
+        Amount::Exact(amount) => amount,
+    };
+    let fits = sold + amount as u128 <= limit;
+    let predicted = nc_sdk::rules::check_transfer(
+        &state,
+        protocol
+            .svm
+            .get::<SellWindow>(&pda::sell_window(&from_key).0)
+            .as_ref(),
+        &from_key,
+        &to_key,
+        &TransferProofs::default(),
+        balance,
+        amount,
+        now,
+    );
+
+    let result = protocol.transfer(&from.wallet, &from.account, &to.account, amount);
+    let context = || {
+        format!(
+            "transfer {} of {} from {} to {} at {}",
+            amount, balance, from_key, to_key, now
+        )
+    };
+    match result {
+        Ok(_) => {
+            assert!(
+                predicted.is_ok(),
+                "allowed, predicted {:?}: {}",
+                predicted,
+                context()
+            );
+            assert!(
+                !(from_blacklisted && to_blacklisted),
+                "blacklisted pair transferred: {}",
+                context()
+            );
+            assert!(
+                !((from_blacklisted && to_restricted) || (to_blacklisted && from_restricted)),
+                "blacklisted party traded with a module: {}",
+                context()
+            );
+            assert!(
+                !(paused
+                    && (from_restricted || to_restricted || from_blacklisted || to_blacklisted)),
+                "transfer during emergency pause: {}",
+                context()
+            );
+            let p2p = !from_pool && !to_pool && !from_restricted && !to_restricted;
+            assert!(
+                !module_paused(PauseModule::Global)
+                    && !((from_pool || to_pool) && module_paused(PauseModule::LpTrading))
+                    && !(p2p && module_paused(PauseModule::P2p)),
+                "transfer over a paused route: {}",
+                context()
+            );
+            if limited {
+                assert!(
+                    fits,
+                    "sale above the sell limit ({} sold of {}): {}",
+                    sold,
+                    limit,
+                    context()
+                );
+                window.sales.push((now / HOUR * HOUR, amount));
+                windows.insert(from_key, window);
+            }
+        }
+        Err(Failure { error, logs, .. }) => {
+            let code = error.code();
+            assert!(
+                !matches!(error, ExecError::Panicked(_)),
+                "{}: {}\n{}",
+                error,
+                context(),
+                logs.join("\n")
+            );
+            assert_ne!(
+                code,
+                Some(nc_token::ErrorCode::MathOverflow.into()),
+                "overflow: {}",
+                context()
+            );
+            match predicted {
+                Ok(_) => assert!(
+                    ![
+                        nc_token::ErrorCode::Paused,
+                        nc_token::ErrorCode::Blacklisted,
+                        nc_token::ErrorCode::SellLimitExceeded
+                    ]
+                    .into_iter()
+                    .any(|rule| code == Some(rule.into())),
+                    "{}, predicted allowed: {}",
+                    error,
+                    context()
+                ),
+                Err(denied) => assert_eq!(
+                    code,
+                    Some(nc_token::ErrorCode::from(denied).into()),
+                    "predicted {}: {}",
+                    denied,
+                    context()
+                ),
+            }
+            if code == Some(nc_token::ErrorCode::SellLimitExceeded.into()) {
+                assert!(
+                    limited && !fits,
+                    "sale within the limit rejected ({} sold of {}): {}",
+                    sold,
+                    limit,
+                    context()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,384 @@
+// nc-harness - in-process SVM for NC Token and MultiSig Governance tests
+//
+// Runs the programs natively (no-entrypoint builds) against the same input
+// layout the BPF loader produces, with CPI, sysvars, return data and logs
+// provided through solana-program's syscall stubs. The clock is set by
+// the test, so cooldowns and sell windows can be warped deterministically.
+//
+// Transactions are held to the runtime's limits: size (as a legacy
+// message), account locks, call depth, instruction trace length, 32 KiB of
+// heap per invocation and the compute budget (SetComputeUnitLimit is
+// honoured).
+//
+// Differences from the real runtime worth knowing:
+// - no fees
+// - compute is metered for CPIs, builtins and the stubbed syscalls only -
+//   program code runs natively for free, so consumption is a lower bound
+// - `msg!` goes to stdout instead of the transaction logs (and costs nothing)
+
+pub mod fixture;
+pub mod fuzz;
+mod runtime;
+mod system;
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use anchor_lang::solana_program::bpf_loader_upgradeable::UpgradeableLoaderState;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::rent::Rent;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::spl_token;
+use nc_sdk::events::{self, Event};
+
+pub use runtime::Entrypoint;
+use runtime::{Bank, Executable};
+
+// Unix time a new Svm starts at
+pub const GENESIS_TIME: i64 = 1_700_000_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExecError {
+    Program(ProgramError),
+    MissingSignature(Pubkey),
+    PrivilegeEscalation(Pubkey),
+    UnknownProgram(Pubkey),
+    MissingAccount(Pubkey),
+    ReadonlyModified(Pubkey),
+    ExternalDataModified(Pubkey),
+    ExternalLamportSpend(Pubkey),
+    OwnerModified(Pubkey),
+    UnbalancedInstruction,
+    CallDepth,
+    TraceLength,
+    HeapExhausted(usize),
+    ComputeBudgetExceeded,
+    TransactionTooLarge(usize),
+    TooManyAccountLocks(usize),
+    DuplicateInstruction,
+    Panicked(String),
+}
+
+impl ExecError {
+    // Custom program error code (anchor errors are 6000+, framework 100+)
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            Self::Program(ProgramError::Custom(code)) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Program(err) => write!(f, "{}", err),
+            Self::MissingSignature(key) => write!(f, "missing signature for {}", key),
+            Self::PrivilegeEscalation(key) => write!(f, "privilege escalation on {}", key),
+            Self::UnknownProgram(key) => write!(f, "unknown program {}", key),
+            Self::MissingAccount(key) => write!(f, "account {} not passed to CPI", key),
+            Self::ReadonlyModified(key) => write!(f, "readonly account {} modified", key),
+            Self::ExternalDataModified(key) => write!(f, "data of external account {} modified", key),
+            Self::ExternalLamportSpend(key) => write!(f, "lamports of external account {} spent", key),
+            Self::OwnerModified(key) => write!(f, "owner of {} modified illegally", key),
+            Self::UnbalancedInstruction => write!(f, "sum of account balances changed"),
+            Self::CallDepth => write!(f, "call depth exceeded"),
+            Self::TraceLength => write!(f, "instruction trace length exceeded"),
+            Self::HeapExhausted(used) => write!(f, "out of memory ({} bytes of heap used)", used),
+            Self::ComputeBudgetExceeded => write!(f, "compute budget exceeded"),
+            Self::TransactionTooLarge(size) => write!(f, "transaction too large ({} bytes)", size),
+            Self::TooManyAccountLocks(count) => write!(f, "too many account locks ({})", count),
+            Self::DuplicateInstruction => write!(f, "duplicate compute budget instruction"),
+            Self::Panicked(message) => write!(f, "program panicked: {}", message),
+        }
+    }
+}
+
+// A CPI made during the transaction, as RPC nodes report it in
+// meta.innerInstructions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InnerInstruction {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+    pub stack_height: usize, // 2 for a CPI made by a top-level instruction
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub logs: Vec<String>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    pub inner_instructions: Vec<InnerInstruction>,
+    pub compute_units_consumed: u64,
+}
+
+impl Outcome {
+    // Events the programs emitted through event CPI, in execution order
+    pub fn events(&self) -> Vec<Event> {
+        let instructions = self
+            .inner_instructions
+            .iter()
+            .map(|ix| (&ix.program_id, &ix.data[..]));
+        events::parse_inner_instructions(instructions)
+            .into_iter()
+            .map(|emitted| emitted.event)
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub instruction: usize, // 0 when the transaction as a whole is rejected
+    pub error: ExecError,
+    pub logs: Vec<String>,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "instruction {}: {}", self.instruction, self.error)
+    }
+}
+
+pub struct Svm {
+    bank: Rc<RefCell<Bank>>,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    // System, Compute Budget and SPL Token programs are built in
+    pub fn new() -> Self {
+        let clock = Clock {
+            slot: 1,
+            unix_timestamp: GENESIS_TIME,
+            ..Clock::default()
+        };
+        let svm = Self {
+            bank: Rc::new(RefCell::new(Bank::new(clock))),
+        };
+        {
+            let mut bank = svm.bank.borrow_mut();
+            bank.programs
+                .insert(anchor_lang::system_program::ID, Executable::System);
+            bank.accounts
+                .insert(anchor_lang::system_program::ID, program_account(solana_sdk_ids::native_loader::ID));
+            bank.programs
+                .insert(solana_sdk_ids::compute_budget::ID, Executable::ComputeBudget);
+            bank.accounts
+                .insert(solana_sdk_ids::compute_budget::ID, program_account(solana_sdk_ids::native_loader::ID));
+        }
+        svm.add_program(spl_token::ID, spl_token_entry);
+        svm
+    }
+
+    pub fn add_program(&self, program_id: Pubkey, entry: Entrypoint) {
+        let mut bank = self.bank.borrow_mut();
+        bank.programs.insert(program_id, Executable::Program(entry));
+        bank.accounts
+            .insert(program_id, program_account(solana_sdk_ids::bpf_loader::ID));
+    }
+
+    // Deployed through the upgradeable loader, with a ProgramData account
+    // naming `upgrade_authority`
+    pub fn add_upgradeable_program(&self, program_id: Pubkey, entry: Entrypoint, upgrade_authority: Pubkey) {
+        let loader = solana_sdk_ids::bpf_loader_upgradeable::ID;
+        let (programdata_address, _) = Pubkey::find_program_address(&[program_id.as_ref()], &loader);
+        let program = UpgradeableLoaderState::Program { programdata_address };
+        let program_data = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(upgrade_authority),
+        };
+        let rent = self.rent();
+        let mut bank = self.bank.borrow_mut();
+        bank.programs.insert(program_id, Executable::Program(entry));
+        let data = bincode::serialize(&program).expect("program state");
+        bank.accounts.insert(
+            program_id,
+            Account {
+                lamports: rent.minimum_balance(data.len()),
+                data,
+                owner: loader,
+                executable: true,
+            },
+        );
+        let data = bincode::serialize(&program_data).expect("program data state");
+        bank.accounts.insert(
+            programdata_address,
+            Account {
+                lamports: rent.minimum_balance(data.len()),
+                data,
+                owner: loader,
+                executable: false,
+            },
+        );
+    }
+
+    pub fn set_account(&self, key: Pubkey, account: Account) {
+        self.bank.borrow_mut().accounts.insert(key, account);
+    }
+
+    // None if the account does not exist
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        self.bank.borrow().accounts.get(key).cloned()
+    }
+
+    // Every account owned by `program` (getProgramAccounts)
+    pub fn program_accounts(&self, program: &Pubkey) -> Vec<(Pubkey, Account)> {
+        self.bank
+            .borrow()
+            .accounts
+            .iter()
+            .filter(|(_, account)| account.owner == *program)
+            .map(|(key, account)| (*key, account.clone()))
+            .collect()
+    }
+
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> Option<T> {
+        let account = self.account(key)?;
+        T::try_deserialize(&mut &account.data[..]).ok()
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&self, key: &Pubkey, lamports: u64) {
+        let mut bank = self.bank.borrow_mut();
+        let account = bank.accounts.entry(*key).or_insert_with(|| Account {
+            owner: anchor_lang::system_program::ID,
+            ..Account::default()
+        });
+        account.lamports += lamports;
+    }
+
+    pub fn rent(&self) -> Rent {
+        self.bank.borrow().rent.clone()
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.bank.borrow().clock.clone()
+    }
+
+    pub fn now(&self) -> i64 {
+        self.bank.borrow().clock.unix_timestamp
+    }
+
+    pub fn warp_to(&self, unix_timestamp: i64) {
+        let mut bank = self.bank.borrow_mut();
+        let elapsed = (unix_timestamp - bank.clock.unix_timestamp).max(0) as u64;
+        bank.clock.unix_timestamp = unix_timestamp;
+        // ~400ms slots
+        bank.clock.slot += elapsed * 5 / 2;
+    }
+
+    pub fn advance(&self, seconds: i64) {
+        let now = self.now();
+        self.warp_to(now + seconds);
+    }
+
+    // Runs the instructions atomically; `signers` sign at the top level
+    pub fn process(&self, instructions: &[Instruction], signers: &[Pubkey]) -> Result<Outcome, Failure> {
+        let snapshot = self.bank.borrow().accounts.clone();
+        let _guard = runtime::enter(&self.bank);
+        let mut result = runtime::begin_transaction(&mut self.bank.borrow_mut(), instructions, signers);
+        if result.is_ok() {
+            for (index, ix) in instructions.iter().enumerate() {
+                if let Err(error) = self.process_instruction(ix, signers) {
+                    result = Err((index, error));
+                    break;
+                }
+            }
+        }
+
+        let mut bank = self.bank.borrow_mut();
+        let logs = std::mem::take(&mut bank.logs);
+        let inner_instructions = std::mem::take(&mut bank.inner_instructions);
+        match result {
+            Ok(()) => Ok(Outcome {
+                logs,
+                return_data: bank.return_data.take(),
+                inner_instructions,
+                compute_units_consumed: bank.compute_used,
+            }),
+            Err((instruction, error)) => {
+                bank.accounts = snapshot;
+                bank.return_data = None;
+                Err(Failure {
+                    instruction,
+                    error,
+                    logs,
+                })
+            }
+        }
+    }
+
+    // Like process, but leaves every account as it was
+    pub fn simulate(&self, instructions: &[Instruction], signers: &[Pubkey]) -> Result<Outcome, Failure> {
+        let snapshot = self.bank.borrow().accounts.clone();
+        let result = self.process(instructions, signers);
+        self.bank.borrow_mut().accounts = snapshot;
+        result
+    }
+
+    fn process_instruction(&self, ix: &Instruction, signers: &[Pubkey]) -> Result<(), ExecError> {
+        if let Some(meta) = ix
+            .accounts
+            .iter()
+            .find(|meta| meta.is_signer && !signers.contains(&meta.pubkey))
+        {
+            return Err(ExecError::MissingSignature(meta.pubkey));
+        }
+        {
+            let mut bank = self.bank.borrow_mut();
+            bank.trace_length += 1;
+            if bank.trace_length > runtime::MAX_INSTRUCTION_TRACE_LENGTH {
+                return Err(ExecError::TraceLength);
+            }
+        }
+        let before = self.bank.borrow().total_lamports();
+        let result = runtime::execute(&self.bank, ix);
+        let mut bank = self.bank.borrow_mut();
+        bank.end_transaction();
+        let cpi_error = bank.take_cpi_error();
+        result?;
+        if let Some(err) = cpi_error {
+            return Err(err);
+        }
+        if bank.total_lamports() != before {
+            return Err(ExecError::UnbalancedInstruction);
+        }
+        Ok(())
+    }
+}
+
+fn program_account(loader: Pubkey) -> Account {
+    Account {
+        lamports: 1,
+        data: Vec::new(),
+        owner: loader,
+        executable: true,
+    }
+}
+
+fn spl_token_entry<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [anchor_lang::solana_program::account_info::AccountInfo<'info>],
+    data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    spl_token::processor::Processor::process(program_id, accounts, data)
+}
@@ -0,0 +1,716 @@
+// Instruction execution
+//
+// Programs run natively against the same serialized input the BPF loader
+// hands them, so AccountInfo realloc / assign behave as on-chain. CPIs
+// (recorded as inner instructions, event CPIs included), sysvars, return
+// data and logs reach the harness through solana-program's syscall stubs,
+// which look up the bank of the transaction running on the current thread.
+//
+// The runtime's limits are enforced too: transaction size and account
+// locks before anything runs, call depth and instruction trace length as
+// instructions are invoked, and each invocation's heap and the
+// transaction's compute budget once the program returns (a native program
+// can't be stopped half-way - it fails as if it had been).
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::rc::Rc;
+use std::sync::Once;
+
+use anchor_lang::solana_program::account_info::AccountInfo;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::entrypoint::{self, ProgramResult, MAX_PERMITTED_DATA_INCREASE};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::message::Message;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::program_stubs::{self, SyscallStubs};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::rent::Rent;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::{system, Account, ExecError, InnerInstruction};
+
+// Native program entrypoint (anchor's `entry`, spl-token's processor)
+pub type Entrypoint =
+    for<'info> fn(&Pubkey, &'info [AccountInfo<'info>], &[u8]) -> ProgramResult;
+
+// Invoke depth limit (top-level instruction + 4 nested CPIs)
+const MAX_DEPTH: usize = 5;
+// Instructions per transaction, top-level and CPIs together
+pub(crate) const MAX_INSTRUCTION_TRACE_LENGTH: usize = 64;
+// Serialized transaction size (IPv6 minimum MTU less IP and UDP headers)
+const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
+// Accounts a transaction may reference, program ids and fee payer included
+const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+// Heap per invocation: solana-program's default allocator is a bump
+// allocator over 32 KiB that never frees (a larger requested heap frame
+// goes unused)
+const HEAP_LENGTH: usize = 32 * 1024;
+
+// Compute units, agave's defaults. A transaction without
+// SetComputeUnitLimit gets 200k per program instruction and 3k per builtin
+// one, at most 1.4M in all.
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+const MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT: u64 = 3_000;
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+const BUILTIN_UNITS: u64 = 150;
+const SYSCALL_BASE_COST: u64 = 100;
+const SYSVAR_BASE_COST: u64 = 100;
+const INVOKE_UNITS: u64 = 1_000;
+const CPI_BYTES_PER_UNIT: u64 = 250;
+
+const NON_DUP_MARKER: u8 = u8::MAX;
+const SUCCESS: u64 = 0;
+const UNSUPPORTED_SYSVAR: u64 = 2 << 32;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Executable {
+    System,
+    ComputeBudget,
+    Program(Entrypoint),
+}
+
+// One program invocation; `pre` is the last verified state of its accounts
+struct Frame {
+    program_id: Pubkey,
+    pre: HashMap<Pubkey, Account>,
+}
+
+pub(crate) struct Bank {
+    pub accounts: HashMap<Pubkey, Account>,
+    pub programs: HashMap<Pubkey, Executable>,
+    pub clock: Clock,
+    pub rent: Rent,
+    pub logs: Vec<String>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    pub inner_instructions: Vec<InnerInstruction>,
+    // Instructions run so far in the transaction (see
+    // MAX_INSTRUCTION_TRACE_LENGTH)
+    pub trace_length: usize,
+    // Compute units the transaction may use and has used so far
+    pub compute_limit: u64,
+    pub compute_used: u64,
+    frames: Vec<Frame>,
+    // First error raised inside a CPI - it aborts the whole transaction
+    cpi_error: Option<ExecError>,
+}
+
+impl Bank {
+    pub fn new(clock: Clock) -> Self {
+        Self {
+            accounts: HashMap::new(),
+            programs: HashMap::new(),
+            clock,
+            rent: Rent::default(),
+            logs: Vec::new(),
+            return_data: None,
+            inner_instructions: Vec::new(),
+            trace_length: 0,
+            compute_limit: 0,
+            compute_used: 0,
+            frames: Vec::new(),
+            cpi_error: None,
+        }
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Account {
+        self.accounts.get(key).cloned().unwrap_or_default()
+    }
+
+    pub fn total_lamports(&self) -> u128 {
+        self.accounts.values().map(|a| a.lamports as u128).sum()
+    }
+
+    // Called once the transaction's AccountInfos are gone
+    pub fn end_transaction(&mut self) {
+        self.frames.clear();
+    }
+
+    pub fn take_cpi_error(&mut self) -> Option<ExecError> {
+        self.cpi_error.take()
+    }
+
+    fn consume(&mut self, units: u64) {
+        self.compute_used = self.compute_used.saturating_add(units);
+    }
+
+    fn out_of_compute(&self) -> bool {
+        self.compute_used > self.compute_limit
+    }
+}
+
+// Checks a transaction as the runtime does before running any of it and
+// resets the per-transaction state. Errors name the instruction at fault,
+// 0 for the transaction as a whole.
+pub(crate) fn begin_transaction(
+    bank: &mut Bank,
+    instructions: &[Instruction],
+    signers: &[Pubkey],
+) -> Result<(), (usize, ExecError)> {
+    bank.logs.clear();
+    bank.inner_instructions.clear();
+    bank.trace_length = 0;
+    bank.compute_used = 0;
+
+    // As a legacy message paid for by the first signer (a placeholder one
+    // for signerless simulations)
+    let payer = signers.first().copied().unwrap_or_default();
+    let message = Message::new(instructions, Some(&payer));
+    if message.account_keys.len() > MAX_TX_ACCOUNT_LOCKS {
+        return Err((0, ExecError::TooManyAccountLocks(message.account_keys.len())));
+    }
+    // Signature count (a one-byte compact-u16), signatures, message
+    let signatures = message.header.num_required_signatures as usize;
+    let size = 1 + 64 * signatures + message.serialize().len();
+    if size > PACKET_DATA_SIZE {
+        return Err((0, ExecError::TransactionTooLarge(size)));
+    }
+
+    let mut requested = None;
+    let mut limit = 0;
+    for (index, ix) in instructions.iter().enumerate() {
+        match bank.programs.get(&ix.program_id) {
+            Some(Executable::ComputeBudget) => {
+                limit += MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT;
+                // ComputeBudgetInstruction, borsh-encoded; only the unit limit
+                // matters here (no fees, and the heap frame goes unused)
+                match ix.data[..] {
+                    [2, a, b, c, d] => {
+                        if requested.replace(u32::from_le_bytes([a, b, c, d]) as u64).is_some() {
+                            return Err((index, ExecError::DuplicateInstruction));
+                        }
+                    }
+                    [1, _, _, _, _] | [3, _, _, _, _, _, _, _, _] | [4, _, _, _, _] => {}
+                    _ => return Err((index, ExecError::Program(ProgramError::InvalidInstructionData))),
+                }
+            }
+            Some(Executable::System) => limit += MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT,
+            _ => limit += DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT,
+        }
+    }
+    bank.compute_limit = requested.unwrap_or(limit).min(MAX_COMPUTE_UNIT_LIMIT);
+    Ok(())
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<RefCell<Bank>>>> = const { RefCell::new(None) };
+}
+
+static STUBS: Once = Once::new();
+
+// Routes syscalls on this thread to `bank` until the guard drops
+pub(crate) fn enter(bank: &Rc<RefCell<Bank>>) -> impl Drop {
+    STUBS.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(Stubs));
+    });
+    CURRENT.with(|current| *current.borrow_mut() = Some(bank.clone()));
+
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            CURRENT.with(|current| *current.borrow_mut() = None);
+        }
+    }
+    Guard
+}
+
+fn current() -> Option<Rc<RefCell<Bank>>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+fn log(bank: &Rc<RefCell<Bank>>, message: String) {
+    bank.borrow_mut().logs.push(message);
+}
+
+// Counts what programs allocate. Their allocator never frees, so a
+// program's heap use is the sum of its allocations - a realloc takes a new
+// block and the old one stays used. Each block carries a header naming the
+// invocation that allocated it, so growing a buffer that isn't the
+// program's (the test output capture behind `msg!`'s println) only counts
+// in the invocation that created it.
+struct HeapMeter;
+
+// Header in front of every block, keeping the block's alignment
+fn header_len(align: usize) -> usize {
+    align.max(16)
+}
+
+#[global_allocator]
+static HEAP_METER: HeapMeter = HeapMeter;
+
+// A program invocation's heap
+#[derive(Clone, Copy)]
+struct Heap {
+    invocation: u64,
+    used: usize,
+}
+
+thread_local! {
+    // Heap of the running program, None while harness code runs
+    static HEAP: Cell<Option<Heap>> = const { Cell::new(None) };
+    static INVOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+fn new_heap() -> Option<Heap> {
+    let invocation = INVOCATIONS.get() + 1;
+    INVOCATIONS.set(invocation);
+    HEAP.replace(Some(Heap { invocation, used: 0 }))
+}
+
+// Charges the running program for a block allocated by `owner` (None for
+// a new one) and returns the invocation that owns the block, 0 for the
+// harness
+fn charge_heap(size: usize, align: usize, owner: Option<u64>) -> u64 {
+    // Nothing runs during thread teardown
+    HEAP.try_with(|heap| match heap.get() {
+        Some(Heap { invocation, used }) if owner.is_none_or(|owner| owner == invocation) => {
+            let used = (used + size).next_multiple_of(align);
+            heap.set(Some(Heap { invocation, used }));
+            invocation
+        }
+        _ => 0,
+    })
+    .unwrap_or(0)
+}
+
+impl HeapMeter {
+    // SAFETY (all three): `layout` is the caller's layout for the block
+    unsafe fn with_header(layout: Layout) -> Layout {
+        Layout::from_size_align_unchecked(layout.size() + header_len(layout.align()), layout.align())
+    }
+
+    unsafe fn block(base: *mut u8, layout: Layout, owner: u64) -> *mut u8 {
+        if base.is_null() {
+            return base;
+        }
+        (base as *mut u64).write(owner);
+        base.add(header_len(layout.align()))
+    }
+
+    unsafe fn base(ptr: *mut u8, layout: Layout) -> *mut u8 {
+        ptr.sub(header_len(layout.align()))
+    }
+}
+
+// SAFETY: blocks are System's, offset past a header that keeps them
+// aligned and is freed with them
+unsafe impl GlobalAlloc for HeapMeter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let owner = charge_heap(layout.size(), layout.align(), None);
+        Self::block(System.alloc(Self::with_header(layout)), layout, owner)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let owner = charge_heap(layout.size(), layout.align(), None);
+        Self::block(System.alloc_zeroed(Self::with_header(layout)), layout, owner)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(Self::base(ptr, layout), Self::with_header(layout))
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let base = Self::base(ptr, layout);
+        let owner = charge_heap(new_size, layout.align(), Some((base as *const u64).read()));
+        let base = System.realloc(base, Self::with_header(layout), new_size + header_len(layout.align()));
+        Self::block(base, layout, owner)
+    }
+}
+
+// Syscalls run on the runtime's memory, not the calling program's heap
+fn unmetered<T>(f: impl FnOnce() -> T) -> T {
+    let heap = HEAP.replace(None);
+    let result = f();
+    HEAP.set(heap);
+    result
+}
+
+fn charge_builtin(bank: &Rc<RefCell<Bank>>) -> Result<(), ExecError> {
+    let mut bank = bank.borrow_mut();
+    bank.consume(BUILTIN_UNITS);
+    if bank.out_of_compute() {
+        return Err(ExecError::ComputeBudgetExceeded);
+    }
+    Ok(())
+}
+
+pub(crate) fn execute(bank: &Rc<RefCell<Bank>>, ix: &Instruction) -> Result<(), ExecError> {
+    let (executable, depth) = {
+        let bank = bank.borrow();
+        let executable = bank.programs.get(&ix.program_id).copied();
+        (executable, bank.frames.len() + 1)
+    };
+    let executable = executable.ok_or(ExecError::UnknownProgram(ix.program_id))?;
+    if depth > MAX_DEPTH {
+        return Err(ExecError::CallDepth);
+    }
+
+    log(bank, format!("Program {} invoke [{}]", ix.program_id, depth));
+    bank.borrow_mut().return_data = None;
+    let result = match executable {
+        Executable::System => charge_builtin(bank)
+            .and_then(|()| system::process(&mut bank.borrow_mut(), ix).map_err(ExecError::Program)),
+        Executable::ComputeBudget => charge_builtin(bank),
+        Executable::Program(entry) => run(bank, ix, entry),
+    };
+    match &result {
+        Ok(()) => log(bank, format!("Program {} success", ix.program_id)),
+        Err(err) => log(bank, format!("Program {} failed: {}", ix.program_id, err)),
+    }
+    result
+}
+
+fn run(bank: &Rc<RefCell<Bank>>, ix: &Instruction, entry: Entrypoint) -> Result<(), ExecError> {
+    let mut flags: HashMap<Pubkey, (bool, bool)> = HashMap::new();
+    for meta in &ix.accounts {
+        let (signer, writable) = flags.entry(meta.pubkey).or_default();
+        *signer |= meta.is_signer;
+        *writable |= meta.is_writable;
+    }
+    let (mut input, pre) = {
+        let bank = bank.borrow();
+        let pre: HashMap<Pubkey, Account> =
+            flags.keys().map(|key| (*key, bank.account(key))).collect();
+        (serialize(ix, &flags, &pre), pre)
+    };
+    bank.borrow_mut().frames.push(Frame {
+        program_id: ix.program_id,
+        pre,
+    });
+
+    let (result, heap_used, post) = {
+        // The program's heap starts empty; deserializing the input is the
+        // program's work on-chain too
+        let outer = new_heap();
+        // SAFETY: `input` is laid out by `serialize` exactly as the loader
+        // does and outlives the AccountInfos borrowed from it
+        let (program_id, infos, data) = unsafe { entrypoint::deserialize(input.as_mut_ptr() as *mut u8) };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| entry(program_id, &infos, data)));
+        let heap_used = HEAP.replace(outer).map_or(0, |heap| heap.used);
+        let mut seen = HashSet::new();
+        let post: Vec<(Pubkey, bool, Account)> = infos
+            .iter()
+            .filter(|info| seen.insert(*info.key))
+            .map(|info| (*info.key, info.is_writable, read_account(info)))
+            .collect();
+        (result, heap_used, post)
+    };
+    let frame = bank.borrow_mut().frames.pop().expect("frame pushed above");
+
+    // Either would have stopped the program where it happened
+    let cpi_error = bank.borrow_mut().take_cpi_error();
+    if heap_used > HEAP_LENGTH {
+        return Err(ExecError::HeapExhausted(heap_used));
+    }
+    if bank.borrow().out_of_compute() {
+        return Err(ExecError::ComputeBudgetExceeded);
+    }
+    match result {
+        Err(panic) => return Err(ExecError::Panicked(panic_message(panic))),
+        Ok(Err(err)) => return Err(cpi_error.unwrap_or(ExecError::Program(err))),
+        Ok(Ok(())) => {
+            if let Some(err) = cpi_error {
+                return Err(err);
+            }
+        }
+    }
+
+    for (key, writable, account) in &post {
+        verify(&ix.program_id, key, *writable, &frame.pre[key], account)?;
+    }
+    let mut bank = bank.borrow_mut();
+    for (key, _, account) in post {
+        bank.accounts.insert(key, account);
+    }
+    Ok(())
+}
+
+// CPI from the program on top of the frame stack
+fn invoke(
+    bank: &Rc<RefCell<Bank>>,
+    ix: &Instruction,
+    infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), ExecError> {
+    let caller = match bank.borrow().frames.last() {
+        Some(frame) => frame.program_id,
+        None => return Err(ExecError::CallDepth),
+    };
+    let mut pda_signers = Vec::with_capacity(signers_seeds.len());
+    for seeds in signers_seeds {
+        let key = Pubkey::create_program_address(seeds, &caller)
+            .map_err(|_| ExecError::PrivilegeEscalation(caller))?;
+        pda_signers.push(key);
+    }
+
+    let mut callee_infos: Vec<&AccountInfo> = Vec::new();
+    for meta in &ix.accounts {
+        let info = infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ExecError::MissingAccount(meta.pubkey))?;
+        if (meta.is_writable && !info.is_writable) ||
+            (meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey))
+        {
+            return Err(ExecError::PrivilegeEscalation(meta.pubkey));
+        }
+        if !callee_infos.iter().any(|i| i.key == info.key) {
+            callee_infos.push(info);
+        }
+    }
+
+    {
+        let mut bank = bank.borrow_mut();
+        let data_len: usize = callee_infos.iter().map(|info| info.data_len()).sum();
+        bank.consume(
+            INVOKE_UNITS +
+                ix.data.len() as u64 / CPI_BYTES_PER_UNIT +
+                data_len as u64 / CPI_BYTES_PER_UNIT,
+        );
+        if bank.out_of_compute() {
+            return Err(ExecError::ComputeBudgetExceeded);
+        }
+        bank.trace_length += 1;
+        if bank.trace_length > MAX_INSTRUCTION_TRACE_LENGTH {
+            return Err(ExecError::TraceLength);
+        }
+        let stack_height = bank.frames.len() + 1;
+        bank.inner_instructions.push(InnerInstruction {
+            program_id: ix.program_id,
+            data: ix.data.clone(),
+            stack_height,
+        });
+    }
+
+    // Commit what the caller changed so far
+    {
+        let mut bank = bank.borrow_mut();
+        for info in &callee_infos {
+            let account = read_account(info);
+            let frame = bank.frames.last_mut().expect("caller frame");
+            let pre = frame.pre.get(info.key).cloned().unwrap_or_default();
+            verify(&caller, info.key, info.is_writable, &pre, &account)?;
+            frame.pre.insert(*info.key, account.clone());
+            bank.accounts.insert(*info.key, account);
+        }
+    }
+
+    execute(bank, ix)?;
+
+    // Hand the callee's results back to the caller
+    for info in callee_infos {
+        let account = bank.borrow().account(info.key);
+        write_account(info, &account)?;
+        let mut bank = bank.borrow_mut();
+        let frame = bank.frames.last_mut().expect("caller frame");
+        frame.pre.insert(*info.key, account);
+    }
+    Ok(())
+}
+
+// Aligned loader input: accounts (duplicates by index), instruction data,
+// program id
+fn serialize(
+    ix: &Instruction,
+    flags: &HashMap<Pubkey, (bool, bool)>,
+    accounts: &HashMap<Pubkey, Account>,
+) -> Vec<u64> {
+    let mut bytes: Vec<u8> = Vec::new();
+    bytes.extend((ix.accounts.len() as u64).to_le_bytes());
+    let mut positions: HashMap<Pubkey, usize> = HashMap::new();
+    for (position, meta) in ix.accounts.iter().enumerate() {
+        if let Some(first) = positions.get(&meta.pubkey) {
+            bytes.push(*first as u8);
+            bytes.extend([0u8; 7]);
+            continue;
+        }
+        positions.insert(meta.pubkey, position);
+        let (signer, writable) = flags[&meta.pubkey];
+        let account = &accounts[&meta.pubkey];
+        bytes.extend([NON_DUP_MARKER, signer as u8, writable as u8, account.executable as u8]);
+        bytes.extend([0u8; 4]); // original data length, filled in by deserialize
+        bytes.extend(meta.pubkey.as_ref());
+        bytes.extend(account.owner.as_ref());
+        bytes.extend(account.lamports.to_le_bytes());
+        bytes.extend((account.data.len() as u64).to_le_bytes());
+        bytes.extend(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        bytes.extend(u64::MAX.to_le_bytes()); // rent epoch
+    }
+    bytes.extend((ix.data.len() as u64).to_le_bytes());
+    bytes.extend(&ix.data);
+    bytes.extend(ix.program_id.as_ref());
+
+    let mut input = vec![0u64; bytes.len().div_ceil(8)];
+    // SAFETY: `input` holds at least bytes.len() bytes
+    unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len()) };
+    input
+}
+
+fn read_account(info: &AccountInfo) -> Account {
+    Account {
+        lamports: info.lamports(),
+        data: info.data.borrow().to_vec(),
+        owner: *info.owner,
+        executable: info.executable,
+    }
+}
+
+fn write_account(info: &AccountInfo, account: &Account) -> Result<(), ExecError> {
+    **info.try_borrow_mut_lamports().map_err(ExecError::Program)? = account.lamports;
+    if *info.owner != account.owner {
+        info.assign(&account.owner);
+    }
+    // As on-chain, a callee may grow an account by at most
+    // MAX_PERMITTED_DATA_INCREASE past what the caller was handed (the
+    // serialized padding) - accounts created by CPI included
+    info.resize(account.data.len()).map_err(ExecError::Program)?;
+    info.try_borrow_mut_data()
+        .map_err(ExecError::Program)?
+        .copy_from_slice(&account.data);
+    Ok(())
+}
+
+// Runtime rules for what a program may change on an account
+fn verify(
+    program_id: &Pubkey,
+    key: &Pubkey,
+    writable: bool,
+    pre: &Account,
+    post: &Account,
+) -> Result<(), ExecError> {
+    if pre == post {
+        return Ok(());
+    }
+    let owned = pre.owner == *program_id;
+    if !writable || pre.executable != post.executable {
+        return Err(ExecError::ReadonlyModified(*key));
+    }
+    if pre.owner != post.owner && (!owned || post.data.iter().any(|b| *b != 0)) {
+        return Err(ExecError::OwnerModified(*key));
+    }
+    if pre.data != post.data && !owned {
+        return Err(ExecError::ExternalDataModified(*key));
+    }
+    if post.lamports < pre.lamports && !owned {
+        return Err(ExecError::ExternalLamportSpend(*key));
+    }
+    Ok(())
+}
+
+fn panic_message(panic: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panic".to_string()
+    }
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        unmetered(|| match current() {
+            Some(bank) => {
+                bank.borrow_mut().consume(SYSCALL_BASE_COST.max(message.len() as u64));
+                log(&bank, format!("Program log: {}", message));
+            }
+            None => println!("{}", message),
+        })
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        unmetered(|| {
+            if let Some(bank) = current() {
+                let bytes: usize = fields.iter().map(|field| field.len()).sum();
+                bank.borrow_mut()
+                    .consume(SYSCALL_BASE_COST * (1 + fields.len() as u64) + bytes as u64);
+                let data: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+                log(&bank, format!("Program data: {}", data.join(" ")));
+            }
+        })
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        unmetered(|| {
+            let bank = current().ok_or(ProgramError::UnsupportedSysvar)?;
+            invoke(&bank, instruction, account_infos, signers_seeds).map_err(|err| {
+                let program_error = match &err {
+                    ExecError::Program(err) => err.clone(),
+                    _ => ProgramError::Custom(u32::MAX),
+                };
+                bank.borrow_mut().cpi_error.get_or_insert(err);
+                program_error
+            })
+        })
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unmetered(|| match current() {
+            // SAFETY: the caller passes a pointer to a Clock
+            Some(bank) => unsafe {
+                let mut bank = bank.borrow_mut();
+                bank.consume(SYSVAR_BASE_COST + size_of::<Clock>() as u64);
+                *(var_addr as *mut Clock) = bank.clock.clone();
+                SUCCESS
+            },
+            None => UNSUPPORTED_SYSVAR,
+        })
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unmetered(|| match current() {
+            // SAFETY: the caller passes a pointer to a Rent
+            Some(bank) => unsafe {
+                let mut bank = bank.borrow_mut();
+                bank.consume(SYSVAR_BASE_COST + size_of::<Rent>() as u64);
+                *(var_addr as *mut Rent) = bank.rent.clone();
+                SUCCESS
+            },
+            None => UNSUPPORTED_SYSVAR,
+        })
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        unmetered(|| {
+            let bank = current()?;
+            let mut bank = bank.borrow_mut();
+            let len = bank.return_data.as_ref().map_or(0, |(_, data)| data.len());
+            bank.consume(SYSCALL_BASE_COST + (len + 32) as u64 / CPI_BYTES_PER_UNIT);
+            bank.return_data.clone()
+        })
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        unmetered(|| {
+            if let Some(bank) = current() {
+                let mut bank = bank.borrow_mut();
+                bank.consume(SYSCALL_BASE_COST + data.len() as u64 / CPI_BYTES_PER_UNIT);
+                let program_id = bank.frames.last().map(|frame| frame.program_id).unwrap_or_default();
+                bank.return_data = (!data.is_empty()).then(|| (program_id, data.to_vec()));
+            }
+        })
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        unmetered(|| {
+            current().map_or(0, |bank| {
+                let mut bank = bank.borrow_mut();
+                bank.consume(SYSCALL_BASE_COST);
+                bank.frames.len() as u64
+            })
+        })
+    }
+}
@@ -0,0 +1,97 @@
+// System program builtin (the instructions anchor's init and transfers use)
+
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::system_program::ID as SYSTEM_PROGRAM_ID;
+use solana_system_interface::error::SystemError;
+use solana_system_interface::instruction::SystemInstruction;
+
+use crate::runtime::Bank;
+
+// Max data a system instruction may allocate
+const MAX_PERMITTED_DATA_LENGTH: u64 = 10 * 1024 * 1024;
+
+pub(crate) fn process(bank: &mut Bank, ix: &Instruction) -> Result<(), ProgramError> {
+    let instruction: SystemInstruction =
+        bincode::deserialize(&ix.data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let key = |index: usize| -> Result<Pubkey, ProgramError> {
+        ix.accounts
+            .get(index)
+            .map(|meta| meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    let require_signer = |index: usize| -> Result<(), ProgramError> {
+        match ix.accounts.get(index) {
+            Some(meta) if meta.is_signer => Ok(()),
+            Some(_) => Err(ProgramError::MissingRequiredSignature),
+            None => Err(ProgramError::NotEnoughAccountKeys),
+        }
+    };
+
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (key(0)?, key(1)?);
+            require_signer(0)?;
+            require_signer(1)?;
+            let account = bank.account(&to);
+            if account.lamports > 0 || !account.data.is_empty() || account.owner != SYSTEM_PROGRAM_ID {
+                return Err(custom(SystemError::AccountAlreadyInUse));
+            }
+            transfer(bank, &from, &to, lamports)?;
+            allocate(bank, &to, space)?;
+            assign(bank, &to, owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            require_signer(0)?;
+            transfer(bank, &key(0)?, &key(1)?, lamports)?;
+        }
+        SystemInstruction::Assign { owner } => {
+            require_signer(0)?;
+            assign(bank, &key(0)?, owner);
+        }
+        SystemInstruction::Allocate { space } => {
+            require_signer(0)?;
+            allocate(bank, &key(0)?, space)?;
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+fn transfer(bank: &mut Bank, from: &Pubkey, to: &Pubkey, lamports: u64) -> Result<(), ProgramError> {
+    let source = bank.account(from);
+    if !source.data.is_empty() || source.owner != SYSTEM_PROGRAM_ID {
+        return Err(custom(SystemError::InvalidAccountDataLength));
+    }
+    if source.lamports < lamports {
+        return Err(custom(SystemError::ResultWithNegativeLamports));
+    }
+    bank.accounts.entry(*from).or_default().lamports -= lamports;
+    bank.accounts.entry(*to).or_default().lamports += lamports;
+    Ok(())
+}
+
+fn allocate(bank: &mut Bank, key: &Pubkey, space: u64) -> Result<(), ProgramError> {
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(custom(SystemError::InvalidAccountDataLength));
+    }
+    let account = bank.accounts.entry(*key).or_default();
+    if !account.data.is_empty() {
+        return Err(custom(SystemError::AccountAlreadyInUse));
+    }
+    account.data = vec![0; space as usize];
+    Ok(())
+}
+
+fn assign(bank: &mut Bank, key: &Pubkey, owner: Pubkey) {
+    bank.accounts.entry(*key).or_default().owner = owner;
+}
+
+fn custom(err: SystemError) -> ProgramError {
+    ProgramError::Custom(err as u32)
+}
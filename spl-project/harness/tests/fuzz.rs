@@ -0,0 +1,44 @@
+// Replays pseudo-random fuzz scenarios on stable (the libFuzzer target in
+// fuzz/ explores the same scenarios with coverage guidance). Set
+// NC_FUZZ_ITERATIONS for a longer run.
+
+use arbitrary::{Arbitrary, Unstructured};
+use nc_harness::fuzz::{Scenario, Step, MAX_STEPS};
+
+const DEFAULT_ITERATIONS: u64 = 32;
+
+// splitmix64
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed;
+    let mut out = Vec::with_capacity(len);
+    while out.len() < len {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        out.extend((z ^ (z >> 31)).to_le_bytes());
+    }
+    out.truncate(len);
+    out
+}
+
+#[test]
+fn transfer_rules_hold_for_random_scenarios() {
+    let iterations = std::env::var("NC_FUZZ_ITERATIONS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS);
+    for seed in 0..iterations {
+        // Steps are drawn one by one - arbitrary's Vec decoding stops on a
+        // coin flip per element, which random bytes hit after a step or two
+        let data = bytes(seed, 4096);
+        let mut u = Unstructured::new(&data);
+        let scenario = Scenario {
+            balances: Arbitrary::arbitrary(&mut u).expect("balances"),
+            steps: (0..MAX_STEPS)
+                .map_while(|_| Step::arbitrary(&mut u).ok())
+                .collect(),
+        };
+        scenario.run();
+    }
+}
@@ -0,0 +1,1052 @@
+// multisig_governance end to end: setup, the cooldown and execution paths,
+// every TransactionType through CPI into nc_token, rejection, re-approval
+// and the guardian pause
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use multisig_governance::{ErrorCode, Governance, PendingTransaction, TransactionStatus};
+use nc_harness::fixture::{assert_error, metadata, Protocol, DEFAULT_COOLDOWN};
+use nc_sdk::events::{Event, GovernanceEvent, NcTokenEvent};
+use nc_sdk::governance::{self as gov, Proposal};
+use nc_sdk::{pda, GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+use nc_token::{AddressList, ListUpdate, PauseModule, ProtocolModule};
+
+const HOUR: i64 = 60 * 60;
+
+fn status(protocol: &Protocol, tx_id: u64) -> TransactionStatus {
+    protocol.proposal(tx_id).expect("proposal").status
+}
+
+fn restrict(account: Pubkey) -> Proposal {
+    Proposal::Restrict {
+        account,
+        value: true,
+    }
+}
+
+fn updates(count: usize) -> Vec<ListUpdate> {
+    (0..count)
+        .map(|_| ListUpdate {
+            account: Pubkey::new_unique(),
+            value: true,
+        })
+        .collect()
+}
+
+#[test]
+fn initialize_requires_upgrade_authority() {
+    let protocol = Protocol::deploy();
+    let attacker = protocol.wallet();
+    assert_error(
+        protocol.process(&[gov::initialize(attacker)], &[attacker]),
+        ErrorCode::NotUpgradeAuthority,
+    );
+
+    protocol
+        .process(&[gov::initialize(protocol.deployer)], &[protocol.deployer])
+        .unwrap();
+    let governance = protocol.governance();
+    assert_eq!(governance.admin, protocol.deployer);
+    assert_eq!(governance.cooldown_period, DEFAULT_COOLDOWN);
+    assert!(!governance.token_set);
+
+    // Nothing can be queued before the token is set
+    let owner = protocol.wallet();
+    protocol
+        .process(&[gov::add_owner(protocol.deployer, owner)], &[protocol.deployer])
+        .unwrap();
+    let ix = gov::queue(owner, 1, restrict(owner), metadata("early"));
+    assert_error(protocol.process(&[ix], &[owner]), ErrorCode::TokenNotSet);
+}
+
+#[test]
+fn set_token_is_pinned_and_one_shot() {
+    let protocol = Protocol::deploy();
+    let deployer = protocol.deployer;
+    protocol.process(&[gov::initialize(deployer)], &[deployer]).unwrap();
+
+    let set_token = |token: Pubkey, state: Pubkey| Instruction {
+        program_id: GOVERNANCE_PROGRAM_ID,
+        accounts: multisig_governance::accounts::AdminOnly {
+            governance: pda::governance().0,
+            authority: deployer,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: multisig_governance::instruction::SetToken { token, state }.data(),
+    };
+    let state = pda::nc_token_state().0;
+    assert_error(
+        protocol.process(&[set_token(Pubkey::new_unique(), state)], &[deployer]),
+        ErrorCode::InvalidTokenProgram,
+    );
+    assert_error(
+        protocol.process(&[set_token(nc_token::ID, Pubkey::new_unique())], &[deployer]),
+        ErrorCode::InvalidTokenState,
+    );
+
+    let attacker = protocol.wallet();
+    assert!(protocol.process(&[gov::set_token(attacker)], &[attacker]).is_err());
+
+    protocol.process(&[gov::set_token(deployer)], &[deployer]).unwrap();
+    assert_error(
+        protocol.process(&[gov::set_token(deployer)], &[deployer]),
+        ErrorCode::TokenAlreadySet,
+    );
+}
+
+#[test]
+fn cooldown_blocks_execution_until_it_ends() {
+    let protocol = Protocol::new(3, 2);
+    let account = Pubkey::new_unique();
+    let tx_id = protocol.queue(restrict(account));
+    let proposal = protocol.proposal(tx_id).unwrap();
+    assert_eq!(proposal.execute_after, protocol.svm.now() + DEFAULT_COOLDOWN);
+
+    // Enough approvals one second early - nothing executes
+    protocol.svm.advance(DEFAULT_COOLDOWN - 1);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::Pending);
+    assert_error(protocol.execute(&protocol.owners[0], tx_id), ErrorCode::CooldownNotExpired);
+    assert_error(protocol.crank(tx_id), ErrorCode::CooldownNotExpired);
+
+    protocol.svm.advance(1);
+    let outcome = protocol.execute(&protocol.owners[2], tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::Executed);
+    assert!(protocol.state().restricted_list.contains(&account));
+    assert!(outcome.events().iter().any(|event| matches!(
+        event,
+        Event::Governance(GovernanceEvent::TransactionExecuted(executed)) if executed.tx_id == tx_id
+    )));
+
+    assert_error(protocol.execute(&protocol.owners[0], tx_id), ErrorCode::TransactionNotPending);
+}
+
+#[test]
+fn last_approval_after_cooldown_auto_executes() {
+    let protocol = Protocol::new(3, 2);
+    let account = Pubkey::new_unique();
+    let tx_id = protocol.queue(restrict(account));
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::AutoExecuted);
+    assert!(protocol.state().restricted_list.contains(&account));
+}
+
+#[test]
+fn crank_executes_for_anyone() {
+    let protocol = Protocol::new(3, 2);
+    let account = Pubkey::new_unique();
+    let tx_id = protocol.queue(restrict(account));
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.crank(tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::CrankExecuted);
+    assert!(protocol.state().restricted_list.contains(&account));
+}
+
+#[test]
+fn approvals_are_counted_once_per_owner() {
+    let protocol = Protocol::new(3, 2);
+    let tx_id = protocol.queue(restrict(Pubkey::new_unique()));
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+
+    let stranger = protocol.wallet();
+    assert_error(protocol.approve(&stranger, tx_id), ErrorCode::NotOwner);
+
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    // The approval PDA already exists
+    assert!(protocol.approve(&protocol.owners[0], tx_id).is_err());
+    assert_error(protocol.execute(&protocol.owners[0], tx_id), ErrorCode::InsufficientApprovals);
+    assert_error(protocol.crank(tx_id), ErrorCode::InsufficientApprovals);
+
+    // Passing the same approval twice doesn't count it twice
+    let mut remaining = protocol.execution_accounts(tx_id, None);
+    remaining.push(remaining[0].clone());
+    let ix = gov::execute_transaction(protocol.owners[0], tx_id, None, remaining);
+    assert_error(protocol.process(&[ix], &[protocol.owners[0]]), ErrorCode::DuplicateApproval);
+
+    // The approver's own approval can't ride along either (it is only
+    // written out once the instruction returns)
+    let owner = protocol.owners[1];
+    let mut remaining = protocol.execution_accounts(tx_id, Some(&owner));
+    let proposal = pda::proposal(tx_id).0;
+    remaining.push(AccountMeta::new_readonly(pda::approval(&proposal, &owner).0, false));
+    let ix = gov::approve_transaction(owner, tx_id, None, remaining);
+    assert_error(
+        protocol.process(&[ix], &[owner]),
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch,
+    );
+
+    protocol.approve(&owner, tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::AutoExecuted);
+}
+
+#[test]
+fn removed_owners_approvals_stop_counting() {
+    let protocol = Protocol::new(3, 2);
+    let tx_id = protocol.queue(restrict(Pubkey::new_unique()));
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    protocol
+        .process(
+            &[gov::remove_owner(protocol.deployer, protocol.owners[1])],
+            &[protocol.deployer],
+        )
+        .unwrap();
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    assert_error(protocol.crank(tx_id), ErrorCode::InsufficientApprovals);
+    protocol.approve(&protocol.owners[2], tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::AutoExecuted);
+}
+
+#[test]
+fn owners_cannot_drop_below_the_threshold() {
+    let protocol = Protocol::new(2, 2);
+    let admin = protocol.deployer;
+    assert_error(
+        protocol.process(&[gov::remove_owner(admin, protocol.owners[1])], &[admin]),
+        ErrorCode::TooFewOwners,
+    );
+
+    protocol
+        .process(&[gov::set_required_approvals(admin, 1)], &[admin])
+        .unwrap();
+    protocol
+        .process(&[gov::remove_owner(admin, protocol.owners[1])], &[admin])
+        .unwrap();
+    assert_eq!(protocol.governance().owners, vec![protocol.owners[0]]);
+}
+
+#[test]
+fn reapproval_after_config_change() {
+    let protocol = Protocol::new(3, 2);
+    let admin = protocol.deployer;
+    protocol
+        .process(&[gov::set_require_reapproval(admin, true)], &[admin])
+        .unwrap();
+    let tx_id = protocol.queue(restrict(Pubkey::new_unique()));
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+
+    // Nothing to refresh yet
+    assert_error(
+        protocol.process(
+            &[gov::reapprove_transaction(protocol.owners[0], tx_id)],
+            &[protocol.owners[0]],
+        ),
+        ErrorCode::AlreadyApproved,
+    );
+
+    // Adding an owner bumps the config nonce, leaving the proposal stale
+    let newcomer = protocol.wallet();
+    protocol.process(&[gov::add_owner(admin, newcomer)], &[admin]).unwrap();
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    assert_error(protocol.crank(tx_id), ErrorCode::StaleProposal);
+
+    // An earlier approver carries it over; the other old approval is void
+    protocol
+        .process(
+            &[gov::reapprove_transaction(protocol.owners[0], tx_id)],
+            &[protocol.owners[0]],
+        )
+        .unwrap();
+    assert_error(protocol.crank(tx_id), ErrorCode::InsufficientApprovals);
+
+    protocol
+        .process(
+            &[gov::reapprove_transaction(protocol.owners[1], tx_id)],
+            &[protocol.owners[1]],
+        )
+        .unwrap();
+    protocol.crank(tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::CrankExecuted);
+}
+
+#[test]
+fn stale_proposals_take_fresh_approvals() {
+    let protocol = Protocol::new(3, 2);
+    let admin = protocol.deployer;
+    protocol
+        .process(&[gov::set_require_reapproval(admin, true)], &[admin])
+        .unwrap();
+
+    // Stale before anyone approved - there's nothing to re-approve, so the
+    // first approval carries it over
+    let tx_id = protocol.queue(restrict(Pubkey::new_unique()));
+    let newcomer = protocol.wallet();
+    protocol.process(&[gov::add_owner(admin, newcomer)], &[admin]).unwrap();
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    assert_error(protocol.crank(tx_id), ErrorCode::StaleProposal);
+    protocol.approve(&newcomer, tx_id).unwrap();
+    let proposal = protocol.proposal(tx_id).unwrap();
+    assert_eq!(proposal.config_nonce, protocol.governance().config_nonce);
+    assert_error(protocol.crank(tx_id), ErrorCode::InsufficientApprovals);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::AutoExecuted);
+
+    // Stale with its only approval from an owner since removed - who can't
+    // re-approve it
+    let tx_id = protocol.queue(restrict(Pubkey::new_unique()));
+    let removed = protocol.owners[2];
+    protocol.approve(&removed, tx_id).unwrap();
+    protocol.process(&[gov::remove_owner(admin, removed)], &[admin]).unwrap();
+    assert_error(
+        protocol.process(&[gov::reapprove_transaction(removed, tx_id)], &[removed]),
+        ErrorCode::NotOwner,
+    );
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::Pending);
+    protocol.approve(&newcomer, tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::AutoExecuted);
+}
+
+#[test]
+fn required_approvals_stay_reachable() {
+    let protocol = Protocol::new(3, 2);
+    let admin = protocol.deployer;
+    for required in [0, 4] {
+        assert_error(
+            protocol.process(&[gov::set_required_approvals(admin, required)], &[admin]),
+            ErrorCode::InvalidApprovalRequirement,
+        );
+    }
+    protocol
+        .process(&[gov::set_required_approvals(admin, 3)], &[admin])
+        .unwrap();
+    assert_eq!(protocol.governance().required_approvals, 3);
+
+    // Raising it again needs another owner first
+    let newcomer = protocol.wallet();
+    protocol.process(&[gov::add_owner(admin, newcomer)], &[admin]).unwrap();
+    protocol
+        .process(&[gov::set_required_approvals(admin, 4)], &[admin])
+        .unwrap();
+    assert_eq!(protocol.governance().required_approvals, 4);
+}
+
+#[test]
+fn rejected_proposals_are_closed_with_a_refund() {
+    let protocol = Protocol::new(3, 2);
+    let initiator = protocol.owners[0];
+    let tx_id = protocol.queue(restrict(Pubkey::new_unique()));
+    let rejector = protocol.owners[1];
+
+    assert_error(
+        protocol.process(
+            &[gov::close_proposal(initiator, &protocol.proposal(tx_id).unwrap(), &[])],
+            &[initiator],
+        ),
+        ErrorCode::TransactionStillPending,
+    );
+    assert_error(
+        protocol.process(&[gov::reject_transaction(rejector, tx_id, String::new())], &[rejector]),
+        ErrorCode::RejectionReasonRequired,
+    );
+    protocol
+        .process(
+            &[gov::reject_transaction(rejector, tx_id, "wrong account".to_string())],
+            &[rejector],
+        )
+        .unwrap();
+    let proposal = protocol.proposal(tx_id).unwrap();
+    assert_eq!(proposal.status, TransactionStatus::Rejected);
+    assert_eq!(proposal.rejector, rejector);
+    assert_eq!(proposal.rejection_reason, "wrong account");
+
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    assert_error(protocol.approve(&protocol.owners[2], tx_id), ErrorCode::TransactionNotPending);
+
+    // Only the initiator or an owner may close
+    let stranger = protocol.wallet();
+    assert_error(
+        protocol.process(&[gov::close_proposal(stranger, &proposal, &[])], &[stranger]),
+        ErrorCode::Unauthorized,
+    );
+    // The rejector paid to store the reason and must be refunded
+    let mut without_rejector = proposal.clone();
+    without_rejector.rejection_rent = 0;
+    let closer = protocol.owners[2];
+    assert_error(
+        protocol.process(&[gov::close_proposal(closer, &without_rejector, &[])], &[closer]),
+        ErrorCode::MissingAccount,
+    );
+
+    assert!(proposal.rejection_rent > 0);
+    let rent = protocol.svm.lamports(&pda::proposal(tx_id).0);
+    let initiator_before = protocol.svm.lamports(&initiator);
+    let rejector_before = protocol.svm.lamports(&rejector);
+    protocol
+        .process(&[gov::close_proposal(closer, &proposal, &[])], &[closer])
+        .unwrap();
+    assert!(protocol.proposal(tx_id).is_none());
+    assert_eq!(
+        protocol.svm.lamports(&rejector),
+        rejector_before + proposal.rejection_rent
+    );
+    assert_eq!(
+        protocol.svm.lamports(&initiator),
+        initiator_before + rent - proposal.rejection_rent
+    );
+}
+
+#[test]
+fn approval_records_are_closed_with_the_proposal() {
+    let protocol = Protocol::new(3, 2);
+    let tx_id = protocol.pass(restrict(Pubkey::new_unique()));
+    let proposal = protocol.proposal(tx_id).unwrap();
+    let approvers = [protocol.owners[0], protocol.owners[1]];
+    let records: Vec<Pubkey> = approvers
+        .iter()
+        .map(|owner| pda::approval(&pda::proposal(tx_id).0, owner).0)
+        .collect();
+    assert_eq!(proposal.approval_count, 2);
+
+    // Records close a batch at a time, each exactly once; the proposal
+    // stays until the last one goes
+    let closer = protocol.owners[2];
+    assert_error(
+        protocol.process(
+            &[gov::close_proposal(closer, &proposal, &[approvers[0], approvers[0]])],
+            &[closer],
+        ),
+        ErrorCode::InvalidApprovalRecord,
+    );
+    let record_rent = protocol.svm.lamports(&records[0]);
+    let before: Vec<u64> = approvers.iter().map(|a| protocol.svm.lamports(a)).collect();
+    protocol
+        .process(
+            &[gov::close_proposal(closer, &proposal, &approvers[..1])],
+            &[closer],
+        )
+        .unwrap();
+    assert_eq!(protocol.proposal(tx_id).unwrap().approval_count, 1);
+    assert_eq!(protocol.svm.lamports(&approvers[0]), before[0] + record_rent);
+    assert_error(
+        protocol.process(
+            &[gov::close_proposal(closer, &proposal, &approvers[..1])],
+            &[closer],
+        ),
+        ErrorCode::InvalidApprovalRecord,
+    );
+
+    // An owner removed after approving is still refunded
+    protocol
+        .process(
+            &[gov::remove_owner(protocol.deployer, approvers[1])],
+            &[protocol.deployer],
+        )
+        .unwrap();
+    let proposal_rent = protocol.svm.lamports(&pda::proposal(tx_id).0);
+    protocol.close(&closer, tx_id).unwrap();
+    assert!(protocol.proposal(tx_id).is_none());
+    for record in &records {
+        assert!(protocol.svm.account(record).is_none_or(|a| a.lamports == 0));
+    }
+    // owners[0] is also the initiator
+    assert_eq!(
+        protocol.svm.lamports(&approvers[0]),
+        before[0] + record_rent + proposal_rent
+    );
+    assert_eq!(protocol.svm.lamports(&approvers[1]), before[1] + record_rent);
+}
+
+#[test]
+fn max_owner_approvals_close_in_batches() {
+    let owners = Governance::MAX_OWNERS;
+    let protocol = Protocol::new(owners, owners as u8);
+    let tx_id = protocol.queue(restrict(Pubkey::new_unique()));
+    // Without execution accounts - none of the approvals executes
+    for owner in &protocol.owners {
+        protocol
+            .process(&[gov::approve_transaction(*owner, tx_id, None, vec![])], &[*owner])
+            .unwrap();
+    }
+    let rejector = protocol.owners[1];
+    protocol
+        .process(&[gov::reject_transaction(rejector, tx_id, "no".to_string())], &[rejector])
+        .unwrap();
+    let proposal = protocol.proposal(tx_id).unwrap();
+    assert_eq!(proposal.approval_count as usize, owners);
+
+    // Each batch fits a transaction and counts its records off
+    let closer = protocol.owners[2];
+    let batches = gov::close_proposal_batches(closer, &proposal, &protocol.approvers(tx_id));
+    assert_eq!(batches.len(), owners.div_ceil(gov::CLOSE_RECORDS_PER_TX));
+    let (last, rest) = batches.split_last().unwrap();
+    for (index, ix) in rest.iter().enumerate() {
+        let outcome = protocol.process(std::slice::from_ref(ix), &[closer]).unwrap();
+        assert!(outcome.events().is_empty());
+        assert_eq!(
+            protocol.proposal(tx_id).unwrap().approval_count as usize,
+            owners - (index + 1) * gov::CLOSE_RECORDS_PER_TX
+        );
+    }
+
+    // The last closes the proposal and refunds the rejector (and its
+    // record, if the batch has it)
+    let before = protocol.svm.lamports(&rejector);
+    let record = pda::approval(&pda::proposal(tx_id).0, &rejector).0;
+    let record_rent = protocol.svm.lamports(&record);
+    let outcome = protocol.process(std::slice::from_ref(last), &[closer]).unwrap();
+    assert!(protocol.proposal(tx_id).is_none());
+    assert!(protocol.approvers(tx_id).is_empty());
+    assert_eq!(
+        protocol.svm.lamports(&rejector),
+        before + record_rent + proposal.rejection_rent
+    );
+    assert!(matches!(
+        &outcome.events()[..],
+        [Event::Governance(GovernanceEvent::ProposalClosed(closed))] if closed.tx_id == tx_id
+    ));
+}
+
+#[test]
+fn pause_proposals() {
+    let protocol = Protocol::new(3, 2);
+    let guardian = protocol.wallet();
+    protocol
+        .process(
+            &[
+                gov::set_guardians(protocol.deployer, vec![guardian], 1),
+                gov::set_emergency_pause(guardian, &[], HOUR, "incident".to_string()),
+            ],
+            &[protocol.deployer, guardian],
+        )
+        .unwrap();
+
+    // Extend without expiry, then lift
+    protocol.pass(Proposal::ExtendPause { expires_at: 0 });
+    let state = protocol.state();
+    assert!(state.emergency_paused);
+    assert_eq!(state.pause_expires_at, 0);
+    protocol.svm.advance(2 * HOUR);
+    assert!(protocol.state().is_paused(protocol.svm.now()));
+
+    protocol.pass(Proposal::Unpause);
+    assert!(!protocol.state().is_paused(protocol.svm.now()));
+
+    protocol.pass(Proposal::ModulePause {
+        module: PauseModule::Bond,
+        value: true,
+    });
+    assert!(protocol.state().is_module_paused(PauseModule::Bond));
+}
+
+#[test]
+fn list_proposals() {
+    let protocol = Protocol::new(3, 2);
+    let account = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+
+    protocol.pass(restrict(account));
+    protocol.pass(Proposal::NoSellLimit {
+        account,
+        value: true,
+    });
+    protocol.pass(Proposal::Pair { pool, value: true });
+    protocol.pass(Proposal::Blacklist {
+        account,
+        value: true,
+        reason_code: 4,
+        evidence_hash: [9; 32],
+        expires_at: 0,
+        freeze_token_accounts: Vec::new(),
+    });
+    let state = protocol.state();
+    assert!(state.restricted_list.contains(&account));
+    assert!(state.no_sell_limit.contains(&account));
+    assert!(state.is_liquidity_pool.contains(&pool));
+    assert!(state.is_blacklisted(&account, protocol.svm.now()));
+
+    protocol.pass(Proposal::Pair { pool, value: false });
+    assert!(!protocol.state().is_liquidity_pool.contains(&pool));
+}
+
+#[test]
+fn blacklist_proposal_freezes_and_thaws_token_accounts() {
+    let protocol = Protocol::new(3, 2);
+    let wallet = protocol.wallet();
+    let accounts = [protocol.token_account(&wallet, 10), protocol.token_account(&wallet, 20)];
+    let blacklist = |value: bool| Proposal::Blacklist {
+        account: wallet,
+        value,
+        reason_code: 1,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+        freeze_token_accounts: accounts.to_vec(),
+    };
+
+    protocol.pass(blacklist(true));
+    assert!(accounts.iter().all(|account| protocol.is_frozen(account)));
+    let other = protocol.token_account(&protocol.wallet(), 0);
+    assert!(protocol.transfer(&wallet, &accounts[0], &other, 1).is_err());
+
+    protocol.pass(blacklist(false));
+    assert!(accounts.iter().all(|account| !protocol.is_frozen(account)));
+    protocol.transfer(&wallet, &accounts[0], &other, 1).unwrap();
+
+    // Only the blacklisted wallet's own accounts can be frozen with it
+    let bystander = protocol.token_account(&protocol.wallet(), 10);
+    let tx_id = protocol.queue(Proposal::Blacklist {
+        account: wallet,
+        value: true,
+        reason_code: 1,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+        freeze_token_accounts: vec![accounts[0], bystander],
+    });
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    assert_error(
+        protocol.approve(&protocol.owners[1], tx_id),
+        ErrorCode::TokenAccountOwnerMismatch,
+    );
+    assert!(!protocol.is_frozen(&bystander));
+}
+
+#[test]
+fn freeze_proposal_needs_its_token_accounts() {
+    let protocol = Protocol::new(3, 2);
+    let account = protocol.token_account(&protocol.wallet(), 10);
+    let tx_id = protocol.queue(Proposal::Freeze {
+        token_account: account,
+        value: true,
+    });
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+
+    // Without the token account, mint and token program it can't execute
+    let owner = protocol.owners[1];
+    let remaining = gov::approval_accounts(tx_id, &protocol.owners[..1]);
+    let ix = gov::approve_transaction(owner, tx_id, None, remaining);
+    assert_error(protocol.process(&[ix], &[owner]), ErrorCode::MissingAccount);
+
+    protocol.approve(&owner, tx_id).unwrap();
+    assert!(protocol.is_frozen(&account));
+
+    // Accounts of any other mint are out of reach, even one nc_token could
+    // otherwise freeze
+    let other_mint = Pubkey::new_unique();
+    protocol.create_mint(other_mint);
+    let foreign = protocol.token_account_of(&other_mint, &protocol.wallet(), 10);
+    let tx_id = protocol.queue(Proposal::Freeze {
+        token_account: foreign,
+        value: true,
+    });
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    assert_error(
+        protocol.approve(&protocol.owners[1], tx_id),
+        nc_token::ErrorCode::InvalidMint,
+    );
+    assert!(!protocol.is_frozen(&foreign));
+
+    protocol.pass(Proposal::Freeze {
+        token_account: account,
+        value: false,
+    });
+    assert!(!protocol.is_frozen(&account));
+}
+
+#[test]
+fn list_batch_executes_in_chunks() {
+    let protocol = Protocol::new(3, 2);
+    let first = updates(20);
+    let second = updates(25);
+    let tx_id = protocol.queue(Proposal::ListBatch {
+        list: AddressList::Restricted,
+        updates: first.clone(),
+        reason_code: 0,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+    });
+    // Appended over two transactions - a list batch instruction only
+    // carries about 20 entries
+    for chunk in second.chunks(15) {
+        protocol
+            .process(
+                &[gov::append_list_batch(protocol.owners[0], tx_id, chunk.to_vec())],
+                &[protocol.owners[0]],
+            )
+            .unwrap();
+    }
+
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    // Locked once approvals start
+    assert_error(
+        protocol.process(
+            &[gov::append_list_batch(protocol.owners[0], tx_id, updates(1))],
+            &[protocol.owners[0]],
+        ),
+        ErrorCode::BatchLocked,
+    );
+
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    let proposal = protocol.proposal(tx_id).unwrap();
+    assert_eq!(proposal.status, TransactionStatus::Pending);
+    assert_eq!(proposal.executed_count, 20);
+    assert_eq!(protocol.state().restricted_list.len(), 20);
+
+    // A started batch can't be rejected any more
+    assert_error(
+        protocol.process(
+            &[gov::reject_transaction(protocol.owners[2], tx_id, "late".to_string())],
+            &[protocol.owners[2]],
+        ),
+        ErrorCode::BatchInProgress,
+    );
+
+    protocol.crank(tx_id).unwrap();
+    assert_eq!(protocol.proposal(tx_id).unwrap().executed_count, 40);
+    protocol.crank(tx_id).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::CrankExecuted);
+
+    let restricted = protocol.state().restricted_list;
+    assert_eq!(restricted.len(), 45);
+    assert!(first.iter().chain(&second).all(|update| restricted.contains(&update.account)));
+}
+
+#[test]
+fn full_list_batches_append_and_execute_within_the_heap() {
+    let protocol = Protocol::new(1, 1);
+    let owner = protocol.owners[0];
+    // Removals, so the batch can outgrow the list caps
+    let removals = |count: usize| -> Vec<ListUpdate> {
+        updates(count)
+            .into_iter()
+            .map(|update| ListUpdate { value: false, ..update })
+            .collect()
+    };
+    let tx_id = protocol.queue(Proposal::ListBatch {
+        list: AddressList::Restricted,
+        updates: removals(20),
+        reason_code: 0,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+    });
+    // Appended up to MAX_DATA - each append reads the batch so far
+    let append = |count| {
+        protocol.process(&[gov::append_list_batch(owner, tx_id, removals(count))], &[owner])
+    };
+    let full = (PendingTransaction::MAX_DATA - protocol.proposal(tx_id).unwrap().data.len())
+        / ListUpdate::LEN;
+    let mut left = full;
+    while left > 0 {
+        append(left.min(15)).unwrap();
+        left -= left.min(15);
+    }
+    assert_error(append(1), ErrorCode::DataTooLarge);
+
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&owner, tx_id).unwrap();
+    while status(&protocol, tx_id) == TransactionStatus::Pending {
+        protocol.crank(tx_id).unwrap();
+    }
+    let proposal = protocol.proposal(tx_id).unwrap();
+    assert_eq!(proposal.executed_count as usize, 20 + full);
+    assert_eq!(proposal.status, TransactionStatus::CrankExecuted);
+}
+
+#[test]
+fn expected_hash_pins_the_proposal_content() {
+    let protocol = Protocol::new(3, 2);
+    let tx_id = protocol.queue(Proposal::ListBatch {
+        list: AddressList::Restricted,
+        updates: updates(3),
+        reason_code: 0,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+    });
+    // Read before the batch grows
+    let stale = protocol.proposal(tx_id).unwrap().content_hash();
+    protocol
+        .process(
+            &[gov::append_list_batch(protocol.owners[0], tx_id, updates(2))],
+            &[protocol.owners[0]],
+        )
+        .unwrap();
+    let current = protocol.proposal(tx_id).unwrap().content_hash();
+    assert_ne!(stale, current);
+
+    let approve = |owner: Pubkey, expected_hash: [u8; 32]| {
+        let remaining = protocol.execution_accounts(tx_id, Some(&owner));
+        let ix = gov::approve_transaction(owner, tx_id, Some(expected_hash), remaining);
+        protocol.process(&[ix], &[owner])
+    };
+    assert_error(approve(protocol.owners[0], stale), ErrorCode::ProposalMismatch);
+    approve(protocol.owners[0], current).unwrap();
+    approve(protocol.owners[1], current).unwrap();
+    // Approvals don't change the hash
+    assert_eq!(protocol.proposal(tx_id).unwrap().content_hash(), current);
+
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    let execute = |expected_hash: [u8; 32]| {
+        let remaining = protocol.execution_accounts(tx_id, None);
+        let ix = gov::execute_transaction(protocol.owners[2], tx_id, Some(expected_hash), remaining);
+        protocol.process(&[ix], &[protocol.owners[2]])
+    };
+    assert_error(execute(stale), ErrorCode::ProposalMismatch);
+    execute(current).unwrap();
+    assert_eq!(status(&protocol, tx_id), TransactionStatus::Executed);
+}
+
+#[test]
+fn module_address_proposal_carries_exemptions() {
+    let protocol = Protocol::new(3, 2);
+    let new_treasury = Pubkey::new_unique();
+    protocol.pass(Proposal::ModuleAddress {
+        module: ProtocolModule::Treasury,
+        new_address: new_treasury,
+    });
+    let state = protocol.state();
+    assert_eq!(state.treasury, new_treasury);
+    assert!(state.no_sell_limit.contains(&new_treasury));
+    assert!(!state.no_sell_limit.contains(&protocol.treasury));
+
+    // An address can't serve two modules
+    let tx_id = protocol.queue(Proposal::ModuleAddress {
+        module: ProtocolModule::Bond,
+        new_address: protocol.bridge,
+    });
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    assert_error(
+        protocol.approve(&protocol.owners[1], tx_id),
+        nc_token::ErrorCode::ModuleAddressInUse,
+    );
+    assert_eq!(protocol.state().bond, protocol.bond);
+}
+
+#[test]
+fn merkle_root_proposals() {
+    let protocol = Protocol::new(3, 2);
+    protocol.pass(Proposal::MerkleRoot {
+        list: AddressList::Blacklist,
+        root: [3; 32],
+    });
+    protocol.pass(Proposal::MerkleRoot {
+        list: AddressList::NoSellLimit,
+        root: [4; 32],
+    });
+    let state = protocol.state();
+    assert_eq!(state.blacklist_root, [3; 32]);
+    assert_eq!(state.no_sell_limit_root, [4; 32]);
+
+    // Only the blacklist and no-sell-limit lists have roots
+    let owner = protocol.owners[0];
+    let tx_id = protocol.governance().next_transaction_id;
+    let proposal = Proposal::MerkleRoot {
+        list: AddressList::Restricted,
+        root: [5; 32],
+    };
+    assert_error(
+        protocol.process(&[gov::queue(owner, tx_id, proposal, metadata("root"))], &[owner]),
+        ErrorCode::InvalidTransactionType,
+    );
+}
+
+#[test]
+fn guardian_pause_needs_quorum_and_expires() {
+    let protocol = Protocol::new(3, 2);
+    let guardians: Vec<Pubkey> = (0..3).map(|_| protocol.wallet()).collect();
+    protocol
+        .process(
+            &[gov::set_guardians(protocol.deployer, guardians.clone(), 2)],
+            &[protocol.deployer],
+        )
+        .unwrap();
+    let pause = |signers: &[Pubkey], duration: i64| {
+        let ix = gov::set_emergency_pause(signers[0], &signers[1..], duration, "exploit".to_string());
+        protocol.process(&[ix], signers)
+    };
+
+    assert_error(pause(&guardians[..1], HOUR), ErrorCode::InsufficientGuardians);
+    let outsider = protocol.wallet();
+    assert_error(pause(&[guardians[0], outsider], HOUR), ErrorCode::NotGuardian);
+    let max = protocol.governance().max_pause_duration;
+    assert_error(pause(&guardians[..2], max + 1), ErrorCode::InvalidPauseDuration);
+
+    let outcome = pause(&guardians[1..], HOUR).unwrap();
+    assert!(outcome.events().iter().any(|event| matches!(
+        event,
+        Event::Governance(GovernanceEvent::EmergencyPause(pause)) if pause.guardians == guardians[1..]
+    )));
+    assert!(protocol.state().is_paused(protocol.svm.now()));
+
+    protocol.svm.advance(HOUR - 1);
+    assert!(protocol.state().is_paused(protocol.svm.now()));
+    protocol.svm.advance(1);
+    assert!(!protocol.state().is_paused(protocol.svm.now()));
+}
+
+#[test]
+fn guardian_pause_only_lengthens_and_is_capped() {
+    let protocol = Protocol::new(3, 2);
+    let guardian = protocol.wallet();
+    protocol
+        .process(
+            &[gov::set_guardians(protocol.deployer, vec![guardian], 1)],
+            &[protocol.deployer],
+        )
+        .unwrap();
+    let pause = |duration: i64| {
+        let ix = gov::set_emergency_pause(guardian, &[], duration, "exploit".to_string());
+        protocol.process(&[ix], &[guardian])
+    };
+    let max = protocol.governance().max_pause_duration;
+    let start = protocol.svm.now();
+
+    pause(2 * HOUR).unwrap();
+    assert_eq!(protocol.state().pause_expires_at, start + 2 * HOUR);
+    // Shorter or equal pauses would cut the one in force short
+    assert_error(pause(HOUR), ErrorCode::PauseAlreadyInForce);
+    assert_error(pause(2 * HOUR), ErrorCode::PauseAlreadyInForce);
+    assert_eq!(protocol.state().pause_expires_at, start + 2 * HOUR);
+
+    // Lengthening stops max_pause_duration after the first pause
+    protocol.svm.advance(HOUR);
+    assert_error(pause(max), ErrorCode::PauseLimitExceeded);
+    pause(max - HOUR).unwrap();
+    assert_eq!(protocol.state().pause_expires_at, start + max);
+    protocol.svm.advance(HOUR);
+    assert_error(pause(max - 2 * HOUR), ErrorCode::PauseAlreadyInForce);
+    assert_error(pause(max - 2 * HOUR + 1), ErrorCode::PauseLimitExceeded);
+
+    // Once it lapses guardians wait out the cooldown before pausing again
+    protocol.svm.warp_to(start + max);
+    assert!(!protocol.state().is_paused(protocol.svm.now()));
+    let cooldown = protocol.governance().guardian_pause_cooldown;
+    assert_error(pause(HOUR), ErrorCode::GuardianPauseCooldown);
+    protocol.svm.warp_to(start + max + cooldown - 1);
+    assert_error(pause(HOUR), ErrorCode::GuardianPauseCooldown);
+    protocol.svm.advance(1);
+    pause(HOUR).unwrap();
+
+    // A pause without expiry is never overridden
+    protocol.pass(Proposal::ExtendPause { expires_at: 0 });
+    assert_error(pause(HOUR), ErrorCode::PauseAlreadyInForce);
+    assert_eq!(protocol.state().pause_expires_at, 0);
+}
+
+#[test]
+fn guardian_pause_cooldown_runs_from_the_end_of_the_window() {
+    let protocol = Protocol::new(3, 2);
+    let guardian = protocol.wallet();
+    protocol
+        .process(
+            &[gov::set_guardians(protocol.deployer, vec![guardian], 1)],
+            &[protocol.deployer],
+        )
+        .unwrap();
+    let pause = |duration: i64| {
+        let ix = gov::set_emergency_pause(guardian, &[], duration, "exploit".to_string());
+        protocol.process(&[ix], &[guardian])
+    };
+    let max = protocol.governance().max_pause_duration;
+
+    // Admin only, never negative
+    assert_error(
+        protocol.process(&[gov::set_guardian_pause_cooldown(guardian, HOUR)], &[guardian]),
+        ErrorCode::Unauthorized,
+    );
+    assert_error(
+        protocol.process(
+            &[gov::set_guardian_pause_cooldown(protocol.deployer, -1)],
+            &[protocol.deployer],
+        ),
+        ErrorCode::InvalidPauseDuration,
+    );
+    protocol
+        .process(
+            &[gov::set_guardian_pause_cooldown(protocol.deployer, 2 * HOUR)],
+            &[protocol.deployer],
+        )
+        .unwrap();
+
+    // A short pause lapses early, but the cooldown still counts from the
+    // end of its max_pause_duration window
+    let start = protocol.svm.now();
+    pause(HOUR).unwrap();
+    protocol.svm.warp_to(start + HOUR);
+    assert!(!protocol.state().is_paused(protocol.svm.now()));
+    assert_error(pause(HOUR), ErrorCode::GuardianPauseCooldown);
+    protocol.svm.warp_to(start + max);
+    assert_error(pause(HOUR), ErrorCode::GuardianPauseCooldown);
+    protocol.svm.warp_to(start + max + 2 * HOUR);
+    pause(HOUR).unwrap();
+
+    // Unpausing through governance doesn't reset it
+    let restart = protocol.svm.now();
+    protocol.pass(Proposal::Unpause);
+    assert!(!protocol.state().is_paused(protocol.svm.now()));
+    assert_error(pause(HOUR), ErrorCode::GuardianPauseCooldown);
+
+    // Without a cooldown guardians may pause again right after the window
+    protocol
+        .process(
+            &[gov::set_guardian_pause_cooldown(protocol.deployer, 0)],
+            &[protocol.deployer],
+        )
+        .unwrap();
+    assert_error(pause(HOUR), ErrorCode::GuardianPauseCooldown);
+    protocol.svm.warp_to(restart + max);
+    pause(HOUR).unwrap();
+}
+
+#[test]
+fn events_are_emitted_through_event_cpi() {
+    let protocol = Protocol::new(3, 2);
+    let account = Pubkey::new_unique();
+    let tx_id = protocol.queue(Proposal::NoSellLimit {
+        account,
+        value: true,
+    });
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    let outcome = protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    assert!(protocol.state().no_sell_limit.contains(&account));
+
+    // nc_token's event first (inside the CPI), then governance's
+    let names: Vec<&str> = outcome.events().iter().map(Event::name).collect();
+    assert_eq!(
+        names,
+        ["TransactionApproved", "NoSellLimitSet", "NoSellLimitSet", "TransactionExecuted"]
+    );
+    assert!(outcome.events().iter().any(|event| matches!(
+        event,
+        Event::NcToken(NcTokenEvent::NoSellLimitSet(set)) if set.account == account && set.value
+    )));
+    // Nothing is left in the logs for RPC nodes to truncate
+    assert!(!outcome.logs.iter().any(|line| line.starts_with("Program data:")));
+    let token_event = outcome
+        .inner_instructions
+        .iter()
+        .find(|ix| ix.program_id == NC_TOKEN_PROGRAM_ID && ix.stack_height == 3)
+        .expect("nc_token event CPI");
+    assert!(token_event.data.starts_with(EVENT_IX_TAG_LE));
+
+    // Only the program itself can sign as its event authority, so a
+    // top-level instruction can't pass off data as an event
+    let forged = Instruction {
+        program_id: NC_TOKEN_PROGRAM_ID,
+        accounts: vec![AccountMeta::new_readonly(
+            pda::event_authority(&NC_TOKEN_PROGRAM_ID).0,
+            false,
+        )],
+        data: token_event.data.clone(),
+    };
+    let signer = protocol.wallet();
+    assert_error(
+        protocol.process(&[forged], &[signer]),
+        anchor_lang::error::ErrorCode::ConstraintSigner,
+    );
+}
@@ -0,0 +1,234 @@
+// Merkle-root lists: the tree builder, membership and non-membership proofs,
+// and transfers under a Merkle blacklist
+
+use anchor_lang::prelude::Pubkey;
+use nc_harness::fixture::{assert_error, Protocol};
+use nc_sdk::governance::Proposal;
+use nc_sdk::token;
+use nc_token::merkle::{
+    leaf_hash, node_hash, MerkleProof, MerkleTree, NonMembershipProof, TransferProofs, EMPTY_ROOT,
+    HIGH_SENTINEL, LOW_SENTINEL, MAX_DEPTH,
+};
+use nc_token::{AddressList, ErrorCode};
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+#[test]
+fn tree_proves_members_and_the_gaps_between_them() {
+    let members = [key(0x40), key(0x10), key(0x80), key(0x10)];
+    let tree = MerkleTree::new(members);
+    let root = tree.root();
+
+    // Sorted and deduplicated; 3 members + 2 sentinels pad to 8 leaves
+    let listed: Vec<Pubkey> = tree.members().copied().collect();
+    assert_eq!(listed, [key(0x10), key(0x40), key(0x80)]);
+    assert_eq!(tree.depth(), 3);
+
+    for (position, member) in listed.iter().enumerate() {
+        let proof = tree.prove(member).unwrap();
+        assert_eq!(proof.index as usize, position + 1);
+        assert_eq!(proof.siblings.len(), tree.depth());
+        assert_eq!(proof.root_for(member), Some(root));
+        assert!(proof.verify(&root, member));
+        assert!(tree.prove_absent(member).is_none());
+    }
+
+    for absent in [key(0x01), key(0x20), key(0x7f), key(0x81), key(0xfe)] {
+        assert!(tree.prove(&absent).is_none());
+        let proof = tree.prove_absent(&absent).unwrap();
+        assert!(proof.verify(&root, &absent));
+        assert_eq!(proof.low_proof.index + 1, proof.high_proof.index);
+    }
+}
+
+#[test]
+fn empty_tree_is_just_the_sentinels() {
+    let tree = MerkleTree::new([]);
+    assert_eq!(tree.members().count(), 0);
+    assert_eq!(tree.depth(), 1);
+    assert_eq!(tree.root(), node_hash(&leaf_hash(&LOW_SENTINEL), &leaf_hash(&HIGH_SENTINEL)));
+
+    // The smallest and largest non-sentinel keys are bounded by the sentinels
+    let lowest = Pubkey::new_from_array({
+        let mut bytes = [0; 32];
+        bytes[31] = 1;
+        bytes
+    });
+    let highest = Pubkey::new_from_array({
+        let mut bytes = [0xff; 32];
+        bytes[31] = 0xfe;
+        bytes
+    });
+    for account in [lowest, highest] {
+        let proof = tree.prove_absent(&account).unwrap();
+        assert_eq!((proof.low, proof.high), (LOW_SENTINEL, HIGH_SENTINEL));
+        assert!(proof.verify(&tree.root(), &account));
+    }
+}
+
+#[test]
+fn sentinels_are_never_members_or_absent() {
+    // Sentinels given as members are dropped
+    let tree = MerkleTree::new([LOW_SENTINEL, key(0x33), HIGH_SENTINEL]);
+    assert_eq!(tree.members().copied().collect::<Vec<_>>(), [key(0x33)]);
+    let root = tree.root();
+
+    for sentinel in [LOW_SENTINEL, HIGH_SENTINEL] {
+        assert!(tree.prove(&sentinel).is_none());
+        assert!(tree.prove_absent(&sentinel).is_none());
+    }
+
+    // A sentinel leaf hashes up to the root, but does not verify as a member
+    let low_leaf = MerkleProof {
+        index: 0,
+        siblings: tree.prove_absent(&key(0x01)).unwrap().low_proof.siblings,
+    };
+    assert_eq!(low_leaf.root_for(&LOW_SENTINEL), Some(root));
+    assert!(!low_leaf.verify(&root, &LOW_SENTINEL));
+
+    // Nor can a sentinel be shown to lie between its neighbours
+    let gap = tree.prove_absent(&key(0x01)).unwrap();
+    assert!(!gap.verify(&root, &LOW_SENTINEL));
+}
+
+#[test]
+fn forged_and_out_of_range_proofs_fail() {
+    let tree = MerkleTree::new([key(0x10), key(0x40), key(0x80)]);
+    let root = tree.root();
+    let member = key(0x40);
+    let proof = tree.prove(&member).unwrap();
+
+    // Merkle mode off, another account, another tree
+    assert!(!proof.verify(&EMPTY_ROOT, &member));
+    assert!(!proof.verify(&root, &key(0x10)));
+    assert!(!proof.verify(&MerkleTree::new([key(0x10), key(0x80)]).root(), &member));
+
+    // Forged sibling
+    let mut forged = proof.clone();
+    forged.siblings[1][0] ^= 1;
+    assert!(!forged.verify(&root, &member));
+
+    // Wrong position - same siblings, the leaf hashed on the other side
+    let mut moved = proof.clone();
+    moved.index ^= 1;
+    assert!(!moved.verify(&root, &member));
+
+    // Index past the tree, and proofs deeper than MAX_DEPTH
+    let mut out_of_range = proof.clone();
+    out_of_range.index += 1 << tree.depth();
+    assert_eq!(out_of_range.root_for(&member), None);
+    let too_deep = MerkleProof {
+        index: 0,
+        siblings: vec![[0; 32]; MAX_DEPTH + 1],
+    };
+    assert_eq!(too_deep.root_for(&member), None);
+
+    // An inner node passed off as the root of a shorter proof
+    let short = MerkleProof {
+        index: proof.index >> 1,
+        siblings: proof.siblings[1..].to_vec(),
+    };
+    assert!(!short.verify(&root, &member));
+}
+
+#[test]
+fn forged_non_membership_proofs_fail() {
+    let tree = MerkleTree::new([key(0x10), key(0x40), key(0x80)]);
+    let root = tree.root();
+    let absent = key(0x20);
+    let proof = tree.prove_absent(&absent).unwrap();
+    assert!(proof.verify(&root, &absent));
+
+    // Members and accounts outside the gap
+    assert!(!proof.verify(&root, &key(0x10)));
+    assert!(!proof.verify(&root, &key(0x40)));
+    assert!(!proof.verify(&root, &key(0x50)));
+    assert!(!proof.verify(&EMPTY_ROOT, &absent));
+
+    // Neighbours that are not adjacent leaves hide the member between them
+    let wide = NonMembershipProof {
+        low: key(0x10),
+        low_proof: tree.prove(&key(0x10)).unwrap(),
+        high: key(0x80),
+        high_proof: tree.prove(&key(0x80)).unwrap(),
+    };
+    assert!(!wide.verify(&root, &key(0x40)));
+    assert!(!wide.verify(&root, &absent));
+
+    // Swapped bounds
+    let swapped = NonMembershipProof {
+        low: proof.high,
+        low_proof: proof.high_proof.clone(),
+        high: proof.low,
+        high_proof: proof.low_proof.clone(),
+    };
+    assert!(!swapped.verify(&root, &absent));
+
+    // A bound that is not the leaf its proof is for
+    let mut relabelled = proof.clone();
+    relabelled.high = key(0x30);
+    assert!(!relabelled.verify(&root, &absent));
+
+    let mut forged = proof.clone();
+    forged.high_proof.siblings[0][31] ^= 1;
+    assert!(!forged.verify(&root, &absent));
+}
+
+#[test]
+fn merkle_blacklist_needs_proofs_between_unproven_parties() {
+    let protocol = Protocol::new(3, 2);
+    let holder = |amount: u64| {
+        let wallet = protocol.wallet();
+        (wallet, protocol.token_account(&wallet, amount))
+    };
+    let listed = holder(1_000);
+    let alice = holder(1_000);
+    let bob = holder(0);
+    let tree = MerkleTree::new([listed.0]);
+    protocol.pass(Proposal::MerkleRoot {
+        list: AddressList::Blacklist,
+        root: tree.root(),
+    });
+    let transfer = |from: (Pubkey, Pubkey), to: (Pubkey, Pubkey), proofs: TransferProofs| {
+        let ix = token::transfer_with_proofs(protocol.mint, from.1, to.1, from.0, 10, proofs);
+        protocol.process(&[ix], &[from.0])
+    };
+    let clear = |wallet: &Pubkey| tree.prove_absent(wallet);
+
+    // Neither side proven
+    assert_error(
+        transfer(alice, bob, TransferProofs::default()),
+        ErrorCode::Blacklisted,
+    );
+    assert_error(
+        transfer(listed, bob, TransferProofs::default()),
+        ErrorCode::Blacklisted,
+    );
+
+    // One side proven clear is enough
+    transfer(
+        alice,
+        bob,
+        TransferProofs {
+            from_not_blacklisted: clear(&alice.0),
+            ..TransferProofs::default()
+        },
+    )
+    .unwrap();
+    // (the recipient by its token account)
+    transfer(
+        listed,
+        bob,
+        TransferProofs {
+            to_not_blacklisted: clear(&bob.1),
+            ..TransferProofs::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(protocol.balance(&bob.1), 20);
+
+    // The listed wallet cannot prove itself clear
+    assert!(clear(&listed.0).is_none());
+}
@@ -0,0 +1,825 @@
+// nc_token end to end: initialize, governance-only setters, the pause and
+// blacklist transfer matrices, sell limits and views
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::solana_program::instruction::Instruction;
+use nc_harness::fixture::{assert_error, metadata, Protocol};
+use nc_sdk::events::{Event, NcTokenEvent};
+use nc_sdk::governance::{self as gov, Proposal};
+use nc_sdk::multisig_governance::TransactionStatus;
+use nc_sdk::{pda, token, NC_TOKEN_PROGRAM_ID};
+use nc_token::merkle::TransferProofs;
+use nc_token::{
+    AddressList, BucketStatus, ErrorCode, ListUpdate, NCTokenState, PauseModule, SellStatus, SellWindow,
+    TokenConfig, TransferDenial, TransferPreview,
+};
+
+const HOUR: i64 = 60 * 60;
+const DAY: i64 = 24 * HOUR;
+
+fn list(protocol: &Protocol, list: AddressList, accounts: &[Pubkey], expires_at: i64) {
+    protocol.pass(Proposal::ListBatch {
+        list,
+        updates: accounts
+            .iter()
+            .map(|account| ListUpdate {
+                account: *account,
+                value: true,
+            })
+            .collect(),
+        reason_code: 1,
+        evidence_hash: [7; 32],
+        expires_at,
+    });
+}
+
+// Guardian pause (guardians are set up on first use)
+fn emergency_pause(protocol: &Protocol, duration: i64) {
+    let guardian = protocol.wallet();
+    protocol
+        .process(
+            &[
+                gov::set_guardians(protocol.deployer, vec![guardian], 1),
+                gov::set_emergency_pause(guardian, &[], duration, "incident".to_string()),
+            ],
+            &[protocol.deployer, guardian],
+        )
+        .expect("emergency pause");
+}
+
+// Wallet with a funded token account
+struct Holder {
+    wallet: Pubkey,
+    account: Pubkey,
+}
+
+fn holder(protocol: &Protocol, amount: u64) -> Holder {
+    let wallet = protocol.wallet();
+    let account = protocol.token_account(&wallet, amount);
+    Holder { wallet, account }
+}
+
+// A protocol module: restricted as a sender (wallet) and as a receiver
+// (token account)
+fn module(protocol: &Protocol, amount: u64) -> Holder {
+    let module = holder(protocol, amount);
+    list(protocol, AddressList::Restricted, &[module.wallet, module.account], 0);
+    module
+}
+
+#[test]
+fn initialize_requires_upgrade_authority() {
+    let protocol = Protocol::deploy();
+    let attacker = protocol.wallet();
+    let ix = token::initialize(
+        attacker,
+        protocol.bridge,
+        protocol.treasury,
+        protocol.bond,
+        protocol.mint,
+    );
+    assert_error(protocol.process(&[ix], &[attacker]), ErrorCode::NotUpgradeAuthority);
+
+    // Governance has to be the multisig PDA
+    let mut ix = token::initialize(
+        protocol.deployer,
+        protocol.bridge,
+        protocol.treasury,
+        protocol.bond,
+        protocol.mint,
+    );
+    ix.data = nc_token::instruction::Initialize {
+        governance: attacker,
+        bridge: protocol.bridge,
+        treasury: protocol.treasury,
+        bond: protocol.bond,
+        mint: protocol.mint,
+    }
+    .data();
+    assert_error(protocol.process(&[ix], &[protocol.deployer]), ErrorCode::InvalidGovernance);
+
+    let ix = token::initialize(
+        protocol.deployer,
+        protocol.bridge,
+        protocol.treasury,
+        protocol.bond,
+        protocol.mint,
+    );
+    protocol.process(&[ix], &[protocol.deployer]).unwrap();
+    let state = protocol.state();
+    assert_eq!(state.governance, pda::governance().0);
+    assert_eq!(state.mint, protocol.mint);
+    assert_eq!(state.no_sell_limit, vec![protocol.bridge, protocol.treasury]);
+
+    // Created by CPI, so within what one instruction may allocate on-chain
+    // (the harness enforces the cap), with rent prepaid for full lists
+    let key = pda::nc_token_state().0;
+    let data_len = protocol.svm.account(&key).unwrap().data.len();
+    assert_eq!(data_len, state.space());
+    assert!(data_len <= MAX_PERMITTED_DATA_INCREASE);
+    assert!(protocol.svm.lamports(&key) >= protocol.svm.rent().minimum_balance(NCTokenState::LEN));
+}
+
+#[test]
+fn lists_fill_to_their_caps_and_transfers_still_fit() {
+    let protocol = Protocol::new(1, 1);
+    let key = pda::nc_token_state().0;
+    let data_len = || protocol.svm.account(&key).unwrap().data.len();
+    let rent = protocol.svm.rent().minimum_balance(NCTokenState::LEN);
+    assert!(protocol.svm.lamports(&key) >= rent);
+    assert_eq!(data_len(), protocol.state().space());
+    let seller = holder(&protocol, 1_000);
+    let buyer = holder(&protocol, 0);
+    let pool = holder(&protocol, 1_000);
+
+    // Fill every list, one chunk per execution call - each call loads the
+    // state as filled so far onto the program heap (the harness holds it to
+    // 32 KiB)
+    let caps = [
+        (AddressList::Blacklist, NCTokenState::MAX_BLACKLIST),
+        (AddressList::Restricted, NCTokenState::MAX_RESTRICTED),
+        (AddressList::NoSellLimit, NCTokenState::MAX_NO_SELL_LIMIT),
+        (AddressList::LiquidityPool, NCTokenState::MAX_LIQUIDITY_POOLS),
+    ];
+    let mut restricted = Vec::new();
+    for (list, cap) in caps {
+        let state = protocol.state();
+        let len = match list {
+            AddressList::Blacklist => state.blacklisted.len(),
+            AddressList::Restricted => state.restricted_list.len(),
+            AddressList::NoSellLimit => state.no_sell_limit.len(),
+            AddressList::LiquidityPool => state.is_liquidity_pool.len(),
+        };
+        let mut accounts: Vec<Pubkey> = (len..cap).map(|_| Pubkey::new_unique()).collect();
+        if let AddressList::LiquidityPool = list {
+            accounts[0] = pool.account;
+        }
+        let tx_id = protocol.pass(Proposal::ListBatch {
+            list,
+            updates: accounts
+                .iter()
+                .map(|account| ListUpdate {
+                    account: *account,
+                    value: true,
+                })
+                .collect(),
+            reason_code: 0,
+            evidence_hash: [0; 32],
+            expires_at: 0,
+        });
+        while protocol.proposal(tx_id).unwrap().status == TransactionStatus::Pending {
+            protocol.crank(tx_id).unwrap();
+        }
+        if let AddressList::Restricted = list {
+            restricted = accounts;
+        }
+    }
+    let state = protocol.state();
+    assert_eq!(state.blacklisted.len(), NCTokenState::MAX_BLACKLIST);
+    assert_eq!(state.restricted_list.len(), NCTokenState::MAX_RESTRICTED);
+    assert_eq!(state.no_sell_limit.len(), NCTokenState::MAX_NO_SELL_LIMIT);
+    assert_eq!(state.is_liquidity_pool.len(), NCTokenState::MAX_LIQUIDITY_POOLS);
+    assert_eq!(data_len(), state.space());
+    assert!(protocol.svm.lamports(&key) >= rent);
+
+    // Transfers read the full state: a plain one, and a sale into the pool
+    // that opens the seller's sell window
+    protocol.transfer(&seller.wallet, &seller.account, &buyer.account, 10).unwrap();
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 10).unwrap();
+    assert_eq!(protocol.balance(&buyer.account), 10);
+    assert_eq!(protocol.balance(&pool.account), 1_010);
+
+    // One more is refused; listing an account already there is not
+    let tx_id = protocol.queue(Proposal::Restrict {
+        account: Pubkey::new_unique(),
+        value: true,
+    });
+    protocol.svm.advance(protocol.governance().cooldown_period);
+    assert_error(protocol.approve(&protocol.owners[0], tx_id), ErrorCode::ListFull);
+    protocol.pass(Proposal::Restrict {
+        account: restricted[0],
+        value: true,
+    });
+    assert_eq!(protocol.state().restricted_list.len(), NCTokenState::MAX_RESTRICTED);
+}
+
+#[test]
+fn setters_reject_everyone_but_governance() {
+    let protocol = Protocol::new(3, 2);
+    let attacker = protocol.wallet();
+    let ix = Instruction {
+        program_id: NC_TOKEN_PROGRAM_ID,
+        accounts: nc_token::accounts::GovernanceOnly {
+            state: pda::nc_token_state().0,
+            governance: attacker,
+            event_authority: pda::event_authority(&NC_TOKEN_PROGRAM_ID).0,
+            program: NC_TOKEN_PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: nc_token::instruction::SetEmergencyPause {
+            value: true,
+            expires_at: 0,
+            reason: "attack".to_string(),
+        }
+        .data(),
+    };
+    assert_error(protocol.process(&[ix], &[attacker]), ErrorCode::Unauthorized);
+    assert!(!protocol.state().emergency_paused);
+}
+
+#[test]
+fn p2p_transfer_moves_tokens() {
+    let protocol = Protocol::new(3, 2);
+    let alice = holder(&protocol, 1_000);
+    let bob = holder(&protocol, 0);
+
+    let outcome = protocol.transfer(&alice.wallet, &alice.account, &bob.account, 400).unwrap();
+    assert_eq!(protocol.balance(&alice.account), 600);
+    assert_eq!(protocol.balance(&bob.account), 400);
+    let transfers: Vec<_> = outcome
+        .events()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::NcToken(NcTokenEvent::TransferEvent(event)) => Some(event),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].from, alice.wallet);
+    assert_eq!(transfers[0].to, bob.account);
+    assert_eq!(transfers[0].amount, 400);
+
+    // The sender must sign
+    let ix = token::transfer(protocol.mint, alice.account, bob.account, alice.wallet, 1);
+    assert!(protocol.process(&[ix], &[]).is_err());
+
+    // ... for their own account (a delegate would sell against its own
+    // window), and only NC tokens move through nc_token
+    assert!(protocol.transfer(&bob.wallet, &alice.account, &bob.account, 1).is_err());
+    let other_mint = Pubkey::new_unique();
+    protocol.create_mint(other_mint);
+    let foreign = protocol.token_account_of(&other_mint, &alice.wallet, 10);
+    let foreign_to = protocol.token_account_of(&other_mint, &bob.wallet, 0);
+    assert_error(
+        protocol.process(
+            &[token::transfer(other_mint, foreign, foreign_to, alice.wallet, 1)],
+            &[alice.wallet],
+        ),
+        ErrorCode::InvalidMint,
+    );
+    assert!(protocol.transfer(&alice.wallet, &foreign, &foreign_to, 1).is_err());
+}
+
+#[test]
+fn blacklist_only_blocks_module_interactions() {
+    let protocol = Protocol::new(3, 2);
+    let module = module(&protocol, 1_000);
+    let listed = holder(&protocol, 1_000);
+    let other = holder(&protocol, 1_000);
+    list(&protocol, AddressList::Blacklist, &[listed.wallet, listed.account], 0);
+
+    // P2P either way still works
+    protocol.transfer(&listed.wallet, &listed.account, &other.account, 10).unwrap();
+    protocol.transfer(&other.wallet, &other.account, &listed.account, 10).unwrap();
+
+    // Blacklisted -> module and module -> blacklisted are blocked
+    assert_error(
+        protocol.transfer(&listed.wallet, &listed.account, &module.account, 10),
+        ErrorCode::Blacklisted,
+    );
+    assert_error(
+        protocol.transfer(&module.wallet, &module.account, &listed.account, 10),
+        ErrorCode::Blacklisted,
+    );
+
+    // Blacklisted on both sides is blocked
+    let second = holder(&protocol, 0);
+    list(&protocol, AddressList::Blacklist, &[second.account], 0);
+    assert_error(
+        protocol.transfer(&listed.wallet, &listed.account, &second.account, 10),
+        ErrorCode::Blacklisted,
+    );
+}
+
+#[test]
+fn senders_are_listed_by_wallet_and_recipients_by_token_account() {
+    let protocol = Protocol::new(3, 2);
+    let listed = holder(&protocol, 1_000);
+    let module = holder(&protocol, 1_000);
+    list(&protocol, AddressList::Blacklist, &[listed.wallet], 0);
+    list(&protocol, AddressList::Restricted, &[module.wallet], 0);
+    let preview = |from: &Holder, to: &Holder| -> TransferPreview {
+        protocol.returned(token::preview_transfer(
+            from.account,
+            to.account,
+            from.wallet,
+            10,
+            TransferProofs::default(),
+        ))
+    };
+
+    // A listed wallet only counts when it sends - payments to its token
+    // account are checked against that account's key
+    assert!(preview(&module, &listed).allowed);
+    protocol.transfer(&module.wallet, &module.account, &listed.account, 10).unwrap();
+    assert!(preview(&listed, &module).allowed);
+    protocol.transfer(&listed.wallet, &listed.account, &module.account, 10).unwrap();
+
+    // The receiving side is the token account
+    list(&protocol, AddressList::Blacklist, &[listed.account], 0);
+    assert_eq!(preview(&module, &listed).blocked_by, Some(TransferDenial::BlacklistedRecipient));
+    assert_error(
+        protocol.transfer(&module.wallet, &module.account, &listed.account, 10),
+        ErrorCode::Blacklisted,
+    );
+    list(&protocol, AddressList::Restricted, &[module.account], 0);
+    assert_eq!(preview(&listed, &module).blocked_by, Some(TransferDenial::BlacklistedSender));
+    assert_error(
+        protocol.transfer(&listed.wallet, &listed.account, &module.account, 10),
+        ErrorCode::Blacklisted,
+    );
+}
+
+#[test]
+fn blacklist_entries_lapse_at_expiry() {
+    let protocol = Protocol::new(3, 2);
+    let module = module(&protocol, 0);
+    let listed = holder(&protocol, 1_000);
+    let expires_at = protocol.svm.now() + 3 * HOUR;
+    list(&protocol, AddressList::Blacklist, &[listed.wallet], expires_at);
+
+    assert!(protocol.view(token::is_blacklisted(listed.wallet)));
+    assert_error(
+        protocol.transfer(&listed.wallet, &listed.account, &module.account, 10),
+        ErrorCode::Blacklisted,
+    );
+
+    protocol.svm.warp_to(expires_at);
+    assert!(!protocol.view(token::is_blacklisted(listed.wallet)));
+    protocol.transfer(&listed.wallet, &listed.account, &module.account, 10).unwrap();
+}
+
+#[test]
+fn emergency_pause_freezes_modules_and_blacklisted() {
+    let protocol = Protocol::new(3, 2);
+    let module = module(&protocol, 1_000);
+    let listed = holder(&protocol, 1_000);
+    let alice = holder(&protocol, 1_000);
+    let bob = holder(&protocol, 0);
+    list(&protocol, AddressList::Blacklist, &[listed.wallet], 0);
+    emergency_pause(&protocol, HOUR);
+
+    // P2P between clean wallets is untouched
+    protocol.transfer(&alice.wallet, &alice.account, &bob.account, 10).unwrap();
+
+    assert_error(
+        protocol.transfer(&alice.wallet, &alice.account, &module.account, 10),
+        ErrorCode::Paused,
+    );
+    assert_error(
+        protocol.transfer(&module.wallet, &module.account, &bob.account, 10),
+        ErrorCode::Paused,
+    );
+    assert_error(
+        protocol.transfer(&listed.wallet, &listed.account, &bob.account, 10),
+        ErrorCode::Paused,
+    );
+
+    // Guardian pauses resume on their own
+    protocol.svm.advance(HOUR);
+    protocol.transfer(&alice.wallet, &alice.account, &module.account, 10).unwrap();
+    protocol.transfer(&listed.wallet, &listed.account, &bob.account, 10).unwrap();
+}
+
+#[test]
+fn pauses_in_force_keep_the_later_expiry() {
+    let protocol = Protocol::new(3, 2);
+    emergency_pause(&protocol, 10 * HOUR);
+    let expires_at = protocol.state().pause_expires_at;
+
+    // An earlier expiry does not cut the pause short
+    protocol.pass(Proposal::ExtendPause {
+        expires_at: protocol.svm.now() + HOUR,
+    });
+    assert_eq!(protocol.state().pause_expires_at, expires_at);
+
+    protocol.pass(Proposal::ExtendPause {
+        expires_at: expires_at + HOUR,
+    });
+    assert_eq!(protocol.state().pause_expires_at, expires_at + HOUR);
+    protocol.pass(Proposal::ExtendPause { expires_at: 0 });
+    assert_eq!(protocol.state().pause_expires_at, 0);
+    protocol.pass(Proposal::ExtendPause {
+        expires_at: protocol.svm.now() + HOUR,
+    });
+    assert_eq!(protocol.state().pause_expires_at, 0);
+
+    // Lifting it still works
+    protocol.pass(Proposal::Unpause);
+    assert!(!protocol.state().is_paused(protocol.svm.now()));
+}
+
+#[test]
+fn module_pauses_block_their_routes() {
+    let protocol = Protocol::new(3, 2);
+    let alice = holder(&protocol, 1_000);
+    let bob = holder(&protocol, 0);
+    let pool = holder(&protocol, 0);
+    list(&protocol, AddressList::LiquidityPool, &[pool.account], 0);
+    protocol.svm.airdrop(&protocol.bridge, 1_000_000_000);
+    let bridge = protocol.token_account(&protocol.bridge, 1_000);
+
+    let pause = |module: PauseModule, value: bool| {
+        protocol.pass(Proposal::ModulePause { module, value });
+    };
+
+    pause(PauseModule::P2p, true);
+    assert_error(protocol.transfer(&alice.wallet, &alice.account, &bob.account, 1), ErrorCode::Paused);
+    protocol.transfer(&alice.wallet, &alice.account, &pool.account, 1).unwrap();
+    protocol.transfer(&protocol.bridge, &bridge, &bob.account, 1).unwrap();
+    pause(PauseModule::P2p, false);
+
+    pause(PauseModule::LpTrading, true);
+    assert_error(protocol.transfer(&alice.wallet, &alice.account, &pool.account, 1), ErrorCode::Paused);
+    protocol.transfer(&alice.wallet, &alice.account, &bob.account, 1).unwrap();
+    pause(PauseModule::LpTrading, false);
+
+    pause(PauseModule::Bridge, true);
+    assert_error(protocol.transfer(&protocol.bridge, &bridge, &bob.account, 1), ErrorCode::Paused);
+    protocol.transfer(&alice.wallet, &alice.account, &bob.account, 1).unwrap();
+    pause(PauseModule::Bridge, false);
+
+    pause(PauseModule::Global, true);
+    assert_error(protocol.transfer(&alice.wallet, &alice.account, &bob.account, 1), ErrorCode::Paused);
+    assert_error(protocol.transfer(&alice.wallet, &alice.account, &pool.account, 1), ErrorCode::Paused);
+    pause(PauseModule::Global, false);
+    protocol.transfer(&alice.wallet, &alice.account, &bob.account, 1).unwrap();
+
+    // The emergency pause has its own switch
+    let ix = gov::queue(
+        protocol.owners[0],
+        protocol.governance().next_transaction_id,
+        Proposal::ModulePause {
+            module: PauseModule::Protocol,
+            value: true,
+        },
+        metadata("pause"),
+    );
+    assert_error(
+        protocol.process(&[ix], &[protocol.owners[0]]),
+        multisig_governance::ErrorCode::InvalidTransactionType,
+    );
+}
+
+#[test]
+fn lp_sales_are_capped_at_five_percent_per_day() {
+    let protocol = Protocol::new(3, 2);
+    let seller = holder(&protocol, 10_000);
+    let pool = holder(&protocol, 0);
+    list(&protocol, AddressList::LiquidityPool, &[pool.account], 0);
+
+    // Hour buckets are stamped with the hour start - sell on the hour so the
+    // window and the first bucket line up
+    protocol.svm.warp_to((protocol.svm.now() / HOUR + 1) * HOUR);
+
+    // Spread over the day, up to 5% of the balance at the window start
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 200).unwrap();
+    protocol.svm.advance(5 * HOUR);
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 300).unwrap();
+    assert_error(
+        protocol.transfer(&seller.wallet, &seller.account, &pool.account, 1),
+        ErrorCode::SellLimitExceeded,
+    );
+
+    // P2P transfers don't count
+    let friend = holder(&protocol, 0);
+    protocol.transfer(&seller.wallet, &seller.account, &friend.account, 1_000).unwrap();
+
+    let window: nc_token::SellWindow = protocol.svm.get(&pda::sell_window(&seller.wallet).0).unwrap();
+    assert_eq!(window.start_balance, 10_000);
+
+    // Still inside the 24h window one second before it ends
+    protocol.svm.advance(DAY - 5 * HOUR - 1);
+    assert_error(
+        protocol.transfer(&seller.wallet, &seller.account, &pool.account, 1),
+        ErrorCode::SellLimitExceeded,
+    );
+
+    // The new window starts from the current balance (8_500)
+    protocol.svm.advance(1);
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 425).unwrap();
+    assert_error(
+        protocol.transfer(&seller.wallet, &seller.account, &pool.account, 1),
+        ErrorCode::SellLimitExceeded,
+    );
+    let window: nc_token::SellWindow = protocol.svm.get(&pda::sell_window(&seller.wallet).0).unwrap();
+    assert_eq!(window.start_balance, 8_500);
+}
+
+#[test]
+fn sell_limit_exemptions() {
+    let protocol = Protocol::new(3, 2);
+    let pool = holder(&protocol, 0);
+    list(&protocol, AddressList::LiquidityPool, &[pool.account], 0);
+
+    // Treasury is exempt from initialization
+    protocol.svm.airdrop(&protocol.treasury, 1_000_000_000);
+    let treasury = protocol.token_account(&protocol.treasury, 1_000);
+    protocol.transfer(&protocol.treasury, &treasury, &pool.account, 1_000).unwrap();
+
+    // Governance-granted exemption
+    let whale = holder(&protocol, 1_000);
+    protocol.pass(Proposal::NoSellLimit {
+        account: whale.wallet,
+        value: true,
+    });
+    assert!(protocol.view(token::is_sell_limit(whale.wallet)));
+    protocol.transfer(&whale.wallet, &whale.account, &pool.account, 1_000).unwrap();
+
+    // Restricted modules are exempt
+    let module = module(&protocol, 1_000);
+    protocol.transfer(&module.wallet, &module.account, &pool.account, 1_000).unwrap();
+
+    // Revoking the exemption brings the limit back
+    let whale = holder(&protocol, 1_000);
+    protocol.pass(Proposal::NoSellLimit {
+        account: whale.wallet,
+        value: true,
+    });
+    protocol.pass(Proposal::NoSellLimit {
+        account: whale.wallet,
+        value: false,
+    });
+    assert!(!protocol.view(token::is_sell_limit(whale.wallet)));
+    assert_error(
+        protocol.transfer(&whale.wallet, &whale.account, &pool.account, 51),
+        ErrorCode::SellLimitExceeded,
+    );
+}
+
+#[test]
+fn views_report_list_membership() {
+    let protocol = Protocol::new(3, 2);
+    let account = Pubkey::new_unique();
+    assert!(!protocol.view(token::is_blacklisted(account)));
+    assert!(!protocol.view(token::is_restricted(account)));
+    assert!(!protocol.view(token::is_sell_limit(account)));
+    assert!(protocol.view(token::is_sell_limit(protocol.bridge)));
+
+    protocol.pass(Proposal::Blacklist {
+        account,
+        value: true,
+        reason_code: 2,
+        evidence_hash: [1; 32],
+        expires_at: 0,
+        freeze_token_accounts: Vec::new(),
+    });
+    protocol.pass(Proposal::Restrict {
+        account,
+        value: true,
+    });
+    assert!(protocol.view(token::is_blacklisted(account)));
+    assert!(protocol.view(token::is_restricted(account)));
+
+    let details = protocol.state().blacklisted[0].details;
+    assert_eq!(details.reason_code, 2);
+    assert_eq!(details.evidence_hash, [1; 32]);
+    assert_eq!(details.proposed_by, protocol.owners[0]);
+    assert_eq!(details.expires_at, 0);
+}
+
+#[test]
+fn config_pool_and_exemption_views() {
+    let protocol = Protocol::new(3, 2);
+    let config = || -> TokenConfig { protocol.returned(token::get_config()) };
+    assert_eq!(
+        config(),
+        TokenConfig {
+            governance: pda::governance().0,
+            bridge: protocol.bridge,
+            treasury: protocol.treasury,
+            bond: protocol.bond,
+            mint: protocol.mint,
+            emergency_paused: false,
+            pause_expires_at: 0,
+            pause_in_force: false,
+            paused_modules: 0,
+            blacklist_root: [0; 32],
+            no_sell_limit_root: [0; 32],
+            blacklisted_count: 0,
+            restricted_count: 0,
+            no_sell_limit_count: 2,
+            liquidity_pool_count: 0,
+        }
+    );
+
+    let pool = Pubkey::new_unique();
+    assert!(!protocol.view(token::is_liquidity_pool(pool)));
+    list(&protocol, AddressList::LiquidityPool, &[pool], 0);
+    assert!(protocol.view(token::is_liquidity_pool(pool)));
+
+    // A restricted module is exempt without being on the no-sell-limit list
+    let module = module(&protocol, 0);
+    assert!(protocol.view(token::is_sell_limit_exempt(module.wallet)));
+    assert!(!protocol.view(token::is_sell_limit(module.wallet)));
+    assert!(protocol.view(token::is_sell_limit_exempt(protocol.treasury)));
+    assert!(!protocol.view(token::is_sell_limit_exempt(Pubkey::new_unique())));
+
+    protocol.pass(Proposal::ModulePause {
+        module: PauseModule::Bond,
+        value: true,
+    });
+    emergency_pause(&protocol, HOUR);
+    let paused = config();
+    assert!(paused.emergency_paused && paused.pause_in_force);
+    assert_eq!(paused.pause_expires_at, protocol.svm.now() + HOUR);
+    assert_eq!(paused.paused_modules, PauseModule::Bond.flag());
+    assert_eq!((paused.restricted_count, paused.liquidity_pool_count), (2, 1));
+
+    // Lapsed pauses stay set until cleared, but no longer apply
+    protocol.svm.advance(HOUR);
+    let lapsed = config();
+    assert!(lapsed.emergency_paused && !lapsed.pause_in_force);
+}
+
+#[test]
+fn preview_transfer_reports_the_verdict_without_touching_the_window() {
+    let protocol = Protocol::new(3, 2);
+    let seller = holder(&protocol, 10_000);
+    let pool = holder(&protocol, 0);
+    list(&protocol, AddressList::LiquidityPool, &[pool.account], 0);
+    let preview = |to: &Pubkey, amount: u64| -> TransferPreview {
+        protocol.returned(token::preview_transfer(
+            seller.account,
+            *to,
+            seller.wallet,
+            amount,
+            TransferProofs::default(),
+        ))
+    };
+    let sell_window = pda::sell_window(&seller.wallet).0;
+
+    // Before the first sale: the full 5% of a window that would open now
+    let opened = protocol.svm.now();
+    assert_eq!(
+        preview(&pool.account, 500),
+        TransferPreview {
+            allowed: true,
+            blocked_by: None,
+            sell_limited: true,
+            remaining_allowance: 500,
+            resets_at: opened + DAY,
+        }
+    );
+    assert!(protocol.svm.account(&sell_window).is_none());
+
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 300).unwrap();
+    let before = protocol.svm.account(&sell_window).unwrap().data;
+    protocol.svm.advance(HOUR);
+    assert_eq!(
+        preview(&pool.account, 201),
+        TransferPreview {
+            allowed: false,
+            blocked_by: Some(TransferDenial::SellLimitExceeded),
+            sell_limited: true,
+            remaining_allowance: 200,
+            resets_at: opened + DAY,
+        }
+    );
+    assert!(preview(&pool.account, 200).allowed);
+    assert_eq!(protocol.svm.account(&sell_window).unwrap().data, before);
+
+    // P2P transfers aren't limited
+    let friend = holder(&protocol, 0);
+    let p2p = preview(&friend.account, 9_700);
+    assert!(p2p.allowed && !p2p.sell_limited);
+
+    let module = module(&protocol, 0);
+    list(&protocol, AddressList::Blacklist, &[seller.wallet], 0);
+    assert_eq!(
+        preview(&module.account, 1).blocked_by,
+        Some(TransferDenial::BlacklistedSender)
+    );
+    protocol.pass(Proposal::ModulePause {
+        module: PauseModule::LpTrading,
+        value: true,
+    });
+    assert_eq!(
+        preview(&pool.account, 1).blocked_by,
+        Some(TransferDenial::ModulePaused {
+            module: PauseModule::LpTrading
+        })
+    );
+
+    // The accounts are checked as transfer checks them
+    let not_owner = token::preview_transfer(
+        seller.account,
+        friend.account,
+        friend.wallet,
+        1,
+        TransferProofs::default(),
+    );
+    assert!(protocol.svm.simulate(&[not_owner], &[]).is_err());
+    let other_mint = Pubkey::new_unique();
+    protocol.create_mint(other_mint);
+    let foreign = protocol.token_account_of(&other_mint, &friend.wallet, 0);
+    let wrong_mint = token::preview_transfer(
+        seller.account,
+        foreign,
+        seller.wallet,
+        1,
+        TransferProofs::default(),
+    );
+    assert!(protocol.svm.simulate(&[wrong_mint], &[]).is_err());
+}
+
+#[test]
+fn sell_status_reports_the_window_and_bucket_expiry() {
+    let protocol = Protocol::new(3, 2);
+    let seller = holder(&protocol, 10_000);
+    let pool = holder(&protocol, 0);
+    list(&protocol, AddressList::LiquidityPool, &[pool.account], 0);
+    let status = || -> SellStatus { protocol.returned(token::get_sell_status(seller.wallet, seller.account)) };
+
+    // No window yet - the next sale would open one now
+    let now = protocol.svm.now();
+    assert_eq!(
+        status(),
+        SellStatus {
+            window_start: now,
+            baseline: 10_000,
+            sold: 0,
+            remaining: 500,
+            resets_at: now + DAY,
+            buckets: Vec::new(),
+        }
+    );
+
+    // Open the window ten minutes into an hour, then sell again two hours on
+    protocol.svm.warp_to((now / HOUR + 1) * HOUR + 10 * 60);
+    let opened = protocol.svm.now();
+    let hour = opened / HOUR * HOUR;
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 200).unwrap();
+    protocol.svm.advance(2 * HOUR);
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 100).unwrap();
+    let expected = SellStatus {
+        window_start: opened,
+        baseline: 10_000,
+        sold: 300,
+        remaining: 200,
+        resets_at: opened + DAY,
+        buckets: vec![
+            BucketStatus {
+                hour_start: hour,
+                amount: 200,
+                expires_at: hour + DAY,
+            },
+            BucketStatus {
+                hour_start: hour + 2 * HOUR,
+                amount: 100,
+                expires_at: hour + 2 * HOUR + DAY,
+            },
+        ],
+    };
+    assert_eq!(status(), expected);
+    let window: Option<SellWindow> = protocol.svm.get(&pda::sell_window(&seller.wallet).0);
+    assert_eq!(nc_sdk::rules::sell_status(window.as_ref(), 9_700, protocol.svm.now()), expected);
+
+    // The first bucket stops counting before the window lapses, and the
+    // transfer enforces exactly what the status reports
+    protocol.svm.warp_to(hour + DAY);
+    let status_now = status();
+    assert_eq!((status_now.sold, status_now.remaining), (100, 400));
+    assert_eq!(status_now.buckets, expected.buckets[1..]);
+    protocol.transfer(&seller.wallet, &seller.account, &pool.account, 400).unwrap();
+    assert_error(
+        protocol.transfer(&seller.wallet, &seller.account, &pool.account, 1),
+        ErrorCode::SellLimitExceeded,
+    );
+
+    // Lapsed - a new window from the current balance
+    protocol.svm.warp_to(opened + DAY);
+    assert_eq!(
+        status(),
+        SellStatus {
+            window_start: opened + DAY,
+            baseline: 9_300,
+            sold: 0,
+            remaining: 465,
+            resets_at: opened + 2 * DAY,
+            buckets: Vec::new(),
+        }
+    );
+
+    // The balance has to come from the wallet's own NC account
+    let stranger = holder(&protocol, 1_000_000);
+    let borrowed = token::get_sell_status(seller.wallet, stranger.account);
+    assert!(protocol.svm.simulate(&[borrowed], &[]).is_err());
+    let other_mint = Pubkey::new_unique();
+    protocol.create_mint(other_mint);
+    let foreign = protocol.token_account_of(&other_mint, &seller.wallet, 1_000_000);
+    let foreign = token::get_sell_status(seller.wallet, foreign);
+    assert!(protocol.svm.simulate(&[foreign], &[]).is_err());
+}
@@ -0,0 +1,198 @@
+// Runtime limits: heap per invocation, compute budget, call depth,
+// instruction trace length, transaction size and account locks - pushed
+// by a native program that does whatever its instruction says
+
+use anchor_lang::prelude::{AccountInfo, AccountMeta, ProgramError, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::log::sol_log_data;
+use anchor_lang::solana_program::program::invoke;
+use nc_harness::{ExecError, Failure, Outcome, Svm};
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+// Instruction data: the op, then a u32 argument; anything after it is
+// ignored
+const ALLOC: u8 = 0; // allocate `arg` bytes at once
+const PUSH: u8 = 1; // push `arg` bytes onto a Vec, one at a time
+const LOG_DATA: u8 = 2; // `arg` sol_log_data calls of one byte (201 units each)
+const RECURSE: u8 = 3; // CPI into itself `arg` levels deep
+const FAN_OUT: u8 = 4; // `arg` CPIs allocating 24 KiB each
+
+const KIB: u32 = 1024;
+
+fn stress<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> ProgramResult {
+    let (op, arg) = match data {
+        [op, a, b, c, d, ..] => (*op, u32::from_le_bytes([*a, *b, *c, *d])),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    match op {
+        ALLOC => {
+            std::hint::black_box(vec![1u8; arg as usize]);
+        }
+        PUSH => {
+            let mut bytes = Vec::new();
+            for byte in 0..arg {
+                bytes.push(byte as u8);
+            }
+            std::hint::black_box(bytes);
+        }
+        LOG_DATA => {
+            for _ in 0..arg {
+                sol_log_data(&[b"x"]);
+            }
+        }
+        RECURSE if arg > 0 => invoke(&cpi(program_id, RECURSE, arg - 1), accounts)?,
+        RECURSE => {}
+        FAN_OUT => {
+            for _ in 0..arg {
+                invoke(&cpi(program_id, ALLOC, 24 * KIB), accounts)?;
+            }
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+fn cpi(program_id: &Pubkey, op: u8, arg: u32) -> Instruction {
+    let mut data = vec![op];
+    data.extend(arg.to_le_bytes());
+    Instruction::new_with_bytes(*program_id, &data, vec![])
+}
+
+struct Setup {
+    svm: Svm,
+    program: Pubkey,
+    payer: Pubkey,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let svm = Svm::new();
+        let program = Pubkey::new_unique();
+        svm.add_program(program, stress);
+        let payer = Pubkey::new_unique();
+        svm.airdrop(&payer, 1_000_000_000);
+        Self { svm, program, payer }
+    }
+
+    // The program's account rides along so it can CPI into itself
+    fn ix(&self, op: u8, arg: u32) -> Instruction {
+        let mut ix = cpi(&self.program, op, arg);
+        ix.accounts.push(AccountMeta::new_readonly(self.program, false));
+        ix
+    }
+
+    fn run(&self, instructions: &[Instruction]) -> Result<Outcome, Failure> {
+        self.svm.process(instructions, &[self.payer])
+    }
+
+    #[track_caller]
+    fn error(&self, instructions: &[Instruction]) -> ExecError {
+        self.run(instructions).expect_err("transaction succeeded").error
+    }
+}
+
+#[test]
+fn programs_get_32k_of_heap_per_invocation() {
+    let setup = Setup::new();
+    setup.run(&[setup.ix(ALLOC, 31 * KIB)]).unwrap();
+    assert!(matches!(
+        setup.error(&[setup.ix(ALLOC, 32 * KIB)]),
+        ExecError::HeapExhausted(used) if used > 32 * 1024
+    ));
+
+    // Nothing is freed: growing a Vec to 16 KiB takes every smaller block
+    // on the way as well
+    setup.run(&[setup.ix(PUSH, 5_000)]).unwrap();
+    setup.run(&[setup.ix(ALLOC, 20_000)]).unwrap();
+    assert!(matches!(setup.error(&[setup.ix(PUSH, 20_000)]), ExecError::HeapExhausted(_)));
+
+    // Each CPI runs on a heap of its own
+    setup.run(&[setup.ix(FAN_OUT, 4)]).unwrap();
+    // ...and each top-level instruction
+    setup.run(&[setup.ix(ALLOC, 24 * KIB), setup.ix(ALLOC, 24 * KIB)]).unwrap();
+}
+
+#[test]
+fn compute_units_are_metered_per_transaction() {
+    let setup = Setup::new();
+    let limit = |units| ComputeBudgetInstruction::set_compute_unit_limit(units);
+
+    // The compute budget instruction takes 150 units of its own
+    let outcome = setup.run(&[limit(10_000), setup.ix(LOG_DATA, 49)]).unwrap();
+    assert_eq!(outcome.compute_units_consumed, 150 + 49 * 201);
+    assert_eq!(
+        setup.error(&[limit(10_000), setup.ix(LOG_DATA, 50)]),
+        ExecError::ComputeBudgetExceeded
+    );
+    assert_eq!(
+        setup.error(&[limit(10_000), limit(20_000), setup.ix(LOG_DATA, 1)]),
+        ExecError::DuplicateInstruction
+    );
+
+    // 200k per instruction by default, pooled across the transaction
+    setup.run(&[setup.ix(LOG_DATA, 995)]).unwrap();
+    assert_eq!(setup.error(&[setup.ix(LOG_DATA, 996)]), ExecError::ComputeBudgetExceeded);
+    setup.run(&[setup.ix(LOG_DATA, 996), setup.ix(LOG_DATA, 0)]).unwrap();
+
+    // ...and at most 1.4M, asked for or not
+    let eight: Vec<Instruction> = (0..8).map(|_| setup.ix(LOG_DATA, 900)).collect();
+    assert_eq!(setup.error(&eight), ExecError::ComputeBudgetExceeded);
+    assert_eq!(
+        setup.error(&[limit(2_000_000), setup.ix(LOG_DATA, 6_966)]),
+        ExecError::ComputeBudgetExceeded
+    );
+
+    // CPIs cost 1,000 units each, and a callee running out fails the
+    // transaction
+    let outcome = setup.run(&[setup.ix(FAN_OUT, 2)]).unwrap();
+    assert_eq!(outcome.compute_units_consumed, 2_000);
+    assert_eq!(
+        setup.error(&[limit(2_150), setup.ix(FAN_OUT, 3)]),
+        ExecError::ComputeBudgetExceeded
+    );
+}
+
+#[test]
+fn invocations_nest_five_deep() {
+    let setup = Setup::new();
+    setup.run(&[setup.ix(RECURSE, 4)]).unwrap();
+    assert_eq!(setup.error(&[setup.ix(RECURSE, 5)]), ExecError::CallDepth);
+}
+
+#[test]
+fn transactions_run_at_most_64_instructions() {
+    let setup = Setup::new();
+    // The top-level instruction and 63 CPIs
+    let outcome = setup.run(&[setup.ix(FAN_OUT, 63)]).unwrap();
+    assert_eq!(outcome.inner_instructions.len(), 63);
+    assert_eq!(setup.error(&[setup.ix(FAN_OUT, 64)]), ExecError::TraceLength);
+    // Top-level instructions count too
+    let ixs = [setup.ix(FAN_OUT, 62), setup.ix(ALLOC, 0), setup.ix(ALLOC, 0)];
+    assert_eq!(setup.error(&ixs), ExecError::TraceLength);
+}
+
+#[test]
+fn transactions_fit_one_packet() {
+    let setup = Setup::new();
+    // Signature count and signature (65), header (3), two keys (1 + 64),
+    // blockhash (32), instruction count, program index, one account (4),
+    // data length (2) and the data
+    let padded = |len: usize| {
+        let mut ix = setup.ix(ALLOC, 0);
+        ix.data.resize(len, 0);
+        ix
+    };
+    setup.run(&[padded(1_061)]).unwrap();
+    assert_eq!(setup.error(&[padded(1_062)]), ExecError::TransactionTooLarge(1_233));
+}
+
+#[test]
+fn transactions_lock_at_most_64_accounts() {
+    let setup = Setup::new();
+    // Payer, program and 63 more
+    let mut ix = setup.ix(ALLOC, 0);
+    ix.accounts
+        .extend((0..63).map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false)));
+    assert_eq!(setup.error(&[ix]), ExecError::TooManyAccountLocks(65));
+}
@@ -1,497 +1,1254 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, TokenAccount};
+use nc_event_cpi::EventCpi;
+use nc_token::program::NcToken;
+use nc_token::{
+    AddressList, BlacklistDetails, ListUpdate, NCTokenState, PauseModule, ProtocolModule,
+};
 
-declare_id!("MultiSigGov1111111111111111111111111111111");
+declare_id!("MuLtiSigGov11111111111111111111111111111111");
 
-#[program]
-pub mod multisig_governance {
+// anchor 0.31's #[program] expansion calls the deprecated
+// AccountInfo::realloc in the IDL handlers it emits next to the program
+// module, so the program lives in a wrapper the allow is scoped to
+#[allow(deprecated)]
+mod program_module {
     use super::*;
 
-    // Initialize governance
-    pub fn initialize(ctx: Context<InitializeGovernance>) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        governance.cooldown_period = 90 * 60; // 90 minutes in seconds
-        governance.required_approvals = 1;
-        governance.token_set = false;
-        governance.next_transaction_id = 1;
-        governance.bump = ctx.bumps.governance;
-        
-        msg!("MultiSig Governance initialized");
-        Ok(())
-    }
+    #[program]
+    pub mod multisig_governance {
+        use super::*;
 
-    // Set required approvals (ADMIN only)
-    pub fn set_required_approvals(
-        ctx: Context<AdminOnly>,
-        required: u8,
-    ) -> Result<()> {
-        require!(required > 0, ErrorCode::InvalidApprovalRequirement);
-        let governance = &mut ctx.accounts.governance;
-        let old_value = governance.required_approvals;
-        governance.required_approvals = required;
-        emit!(RequiredApprovalsChanged {
-            old_value,
-            new_value: required,
-        });
-        Ok(())
-    }
+        // Initialize governance (upgrade authority only - becomes the admin)
+        pub fn initialize(ctx: Context<InitializeGovernance>) -> Result<()> {
+            let governance = &mut ctx.accounts.governance;
+            governance.cooldown_period = 90 * 60; // 90 minutes in seconds
+            governance.required_approvals = 1;
+            governance.token_set = false;
+            governance.next_transaction_id = 1;
+            governance.admin = ctx.accounts.authority.key();
+            governance.owners = Vec::new();
+            governance.config_nonce = 0;
+            governance.require_reapproval = false;
+            governance.guardians = Vec::new();
+            governance.guardian_quorum = 1;
+            governance.max_pause_duration = 24 * 60 * 60; // 24 hours in seconds
+            governance.guardian_pause_started_at = 0;
+            governance.guardian_pause_cooldown = 7 * 24 * 60 * 60; // 7 days in seconds
+            governance.bump = ctx.bumps.governance;
+            
+            msg!("MultiSig Governance initialized");
+            Ok(())
+        }
 
-    // Set token contract (ADMIN only, once)
-    pub fn set_token(ctx: Context<AdminOnly>, token: Pubkey) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        require!(!governance.token_set, ErrorCode::TokenAlreadySet);
-        require!(token != Pubkey::default(), ErrorCode::ZeroAddress);
-        governance.nc_token = token;
-        governance.token_set = true;
-        emit!(TokenContractUpdated { new_token_contract: token });
-        Ok(())
-    }
+        // Set required approvals (ADMIN only)
+        pub fn set_required_approvals(
+            ctx: Context<AdminOnly>,
+            required: u8,
+        ) -> Result<()> {
+            let governance = &mut ctx.accounts.governance;
+            // Reachable by the current owners
+            require!(
+                required > 0 && required as usize <= governance.owners.len(),
+                ErrorCode::InvalidApprovalRequirement
+            );
+            let old_value = governance.required_approvals;
+            governance.required_approvals = required;
+            governance.config_nonce += 1;
+            emit_cpi!(RequiredApprovalsChanged {
+                old_value,
+                new_value: required,
+                config_nonce: governance.config_nonce,
+            });
+            Ok(())
+        }
 
-    // Set cooldown period (ADMIN only)
-    pub fn set_cooldown_period(
-        ctx: Context<AdminOnly>,
-        period: i64,
-    ) -> Result<()> {
-        require!(period > 0, ErrorCode::InvalidCooldownPeriod);
-        let governance = &mut ctx.accounts.governance;
-        governance.cooldown_period = period;
-        Ok(())
-    }
+        // Add owner (ADMIN only)
+        pub fn add_owner(ctx: Context<AdminOnly>, owner: Pubkey) -> Result<()> {
+            require!(owner != Pubkey::default(), ErrorCode::ZeroAddress);
+            let governance = &mut ctx.accounts.governance;
+            require!(!governance.owners.contains(&owner), ErrorCode::OwnerAlreadyExists);
+            require!(
+                governance.owners.len() < Governance::MAX_OWNERS,
+                ErrorCode::TooManyOwners
+            );
+            governance.owners.push(owner);
+            governance.config_nonce += 1;
+            emit_cpi!(OwnerAdded {
+                owner,
+                config_nonce: governance.config_nonce,
+            });
+            Ok(())
+        }
 
-    // Emergency pause (ADMIN only, immediate - no queue)
-    pub fn set_emergency_pause(ctx: Context<EmergencyPauseContext>) -> Result<()> {
-        let governance = &ctx.accounts.governance;
-        require!(governance.token_set, ErrorCode::TokenNotSet);
-        
-        // Call NC Token via CPI - governance PDA signs
-        let cpi_program = ctx.accounts.nc_token_program.to_account_info();
-        let governance_seeds = &[
-            b"governance",
-            &[governance.bump],
-        ];
-        let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
-            state: ctx.accounts.nc_token_state.to_account_info(),
-            governance: ctx.accounts.governance.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new_with_signer(
-            cpi_program,
-            cpi_accounts,
-            &[governance_seeds],
-        );
-        nc_token::cpi::set_emergency_pause(cpi_ctx, true)?;
-        
-        emit!(EmergencyPause {});
-        Ok(())
-    }
+        // Remove owner (ADMIN only) - their approvals stop counting
+        pub fn remove_owner(ctx: Context<AdminOnly>, owner: Pubkey) -> Result<()> {
+            let governance = &mut ctx.accounts.governance;
+            require!(governance.owners.contains(&owner), ErrorCode::NotOwner);
+            // Leave enough owners to reach the threshold
+            require!(
+                governance.owners.len() > governance.required_approvals as usize,
+                ErrorCode::TooFewOwners
+            );
+            governance.owners.retain(|&x| x != owner);
+            governance.config_nonce += 1;
+            emit_cpi!(OwnerRemoved {
+                owner,
+                config_nonce: governance.config_nonce,
+            });
+            Ok(())
+        }
 
-    // Queue transaction: Unpause
-    pub fn set_unpause(ctx: Context<QueueTransaction>) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        require!(governance.token_set, ErrorCode::TokenNotSet);
-        
-        let tx_id = governance.next_transaction_id;
-        governance.next_transaction_id += 1;
-        
-        let now = Clock::get()?.unix_timestamp;
-        let execute_after = now + governance.cooldown_period;
-        
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.id = tx_id;
-        proposal.tx_type = TransactionType::Unpause;
-        proposal.status = TransactionStatus::Pending;
-        proposal.initiator = ctx.accounts.signer.key();
-        proposal.target = Pubkey::default();
-        proposal.amount = 0;
-        proposal.data = Vec::new();
-        proposal.timestamp = now;
-        proposal.execute_after = execute_after;
-        proposal.approval_count = 0;
-        proposal.approvals = Vec::new();
-        proposal.rejection_reason = String::new();
-        proposal.rejector = Pubkey::default();
-        proposal.exists = true;
-        proposal.bump = ctx.bumps.proposal;
-        
-        emit!(TransactionQueued {
-            tx_id,
-            tx_type: TransactionType::Unpause,
-            initiator: ctx.accounts.signer.key(),
-        });
-        Ok(())
-    }
+        // Require proposals to be re-approved after an owner/threshold change (ADMIN only)
+        pub fn set_require_reapproval(ctx: Context<AdminOnly>, value: bool) -> Result<()> {
+            let governance = &mut ctx.accounts.governance;
+            governance.require_reapproval = value;
+            emit_cpi!(RequireReapprovalSet { value });
+            Ok(())
+        }
 
-    // Queue transaction: Blacklist
-    pub fn set_blacklist(
-        ctx: Context<QueueTransaction>,
-        account: Pubkey,
-        value: bool,
-    ) -> Result<()> {
-        require!(account != Pubkey::default(), ErrorCode::ZeroAddress);
-        let governance = &mut ctx.accounts.governance;
-        require!(governance.token_set, ErrorCode::TokenNotSet);
-        
-        let data = (account, value).try_to_vec()?;
-        let tx_id = governance.next_transaction_id;
-        governance.next_transaction_id += 1;
-        
-        let now = Clock::get()?.unix_timestamp;
-        let execute_after = now + governance.cooldown_period;
-        
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.id = tx_id;
-        proposal.tx_type = TransactionType::Blacklist;
-        proposal.status = TransactionStatus::Pending;
-        proposal.initiator = ctx.accounts.signer.key();
-        proposal.target = account;
-        proposal.amount = 0;
-        proposal.data = data;
-        proposal.timestamp = now;
-        proposal.execute_after = execute_after;
-        proposal.approval_count = 0;
-        proposal.approvals = Vec::new();
-        proposal.rejection_reason = String::new();
-        proposal.rejector = Pubkey::default();
-        proposal.exists = true;
-        proposal.bump = ctx.bumps.proposal;
-        
-        emit!(TransactionQueued {
-            tx_id,
-            tx_type: TransactionType::Blacklist,
-            initiator: ctx.accounts.signer.key(),
-        });
-        Ok(())
-    }
+        // Set token contract and its state PDA (ADMIN only, once). Every CPI
+        // path is pinned to these two accounts.
+        pub fn set_token(ctx: Context<AdminOnly>, token: Pubkey, state: Pubkey) -> Result<()> {
+            let governance = &mut ctx.accounts.governance;
+            require!(!governance.token_set, ErrorCode::TokenAlreadySet);
+            require!(token != Pubkey::default(), ErrorCode::ZeroAddress);
+            require_keys_eq!(token, nc_token::ID, ErrorCode::InvalidTokenProgram);
+            let (expected_state, _) = Pubkey::find_program_address(&[b"nc_token_state"], &token);
+            require_keys_eq!(state, expected_state, ErrorCode::InvalidTokenState);
+            governance.nc_token = token;
+            governance.nc_token_state = state;
+            governance.token_set = true;
+            emit_cpi!(TokenContractUpdated {
+                new_token_contract: token,
+                state,
+            });
+            Ok(())
+        }
 
-    // Queue transaction: No Sell Limit
-    pub fn set_no_sell_limit(
-        ctx: Context<QueueTransaction>,
-        account: Pubkey,
-        value: bool,
-    ) -> Result<()> {
-        require!(account != Pubkey::default(), ErrorCode::ZeroAddress);
-        let governance = &mut ctx.accounts.governance;
-        require!(governance.token_set, ErrorCode::TokenNotSet);
-        
-        let data = (account, value).try_to_vec()?;
-        let tx_id = governance.next_transaction_id;
-        governance.next_transaction_id += 1;
-        
-        let now = Clock::get()?.unix_timestamp;
-        let execute_after = now + governance.cooldown_period;
-        
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.id = tx_id;
-        proposal.tx_type = TransactionType::NoSellLimit;
-        proposal.status = TransactionStatus::Pending;
-        proposal.initiator = ctx.accounts.signer.key();
-        proposal.target = account;
-        proposal.amount = 0;
-        proposal.data = data;
-        proposal.timestamp = now;
-        proposal.execute_after = execute_after;
-        proposal.approval_count = 0;
-        proposal.approvals = Vec::new();
-        proposal.rejection_reason = String::new();
-        proposal.rejector = Pubkey::default();
-        proposal.exists = true;
-        proposal.bump = ctx.bumps.proposal;
-        
-        emit!(TransactionQueued {
-            tx_id,
-            tx_type: TransactionType::NoSellLimit,
-            initiator: ctx.accounts.signer.key(),
-        });
-        Ok(())
-    }
+        // Set cooldown period (ADMIN only)
+        pub fn set_cooldown_period(
+            ctx: Context<AdminOnly>,
+            period: i64,
+        ) -> Result<()> {
+            require!(period > 0, ErrorCode::InvalidCooldownPeriod);
+            let governance = &mut ctx.accounts.governance;
+            governance.cooldown_period = period;
+            Ok(())
+        }
 
-    // Queue transaction: Restricted
-    pub fn set_restricted(
-        ctx: Context<QueueTransaction>,
-        account: Pubkey,
-        value: bool,
-    ) -> Result<()> {
-        require!(account != Pubkey::default(), ErrorCode::ZeroAddress);
-        let governance = &mut ctx.accounts.governance;
-        require!(governance.token_set, ErrorCode::TokenNotSet);
-        
-        let data = (account, value).try_to_vec()?;
-        let tx_id = governance.next_transaction_id;
-        governance.next_transaction_id += 1;
-        
-        let now = Clock::get()?.unix_timestamp;
-        let execute_after = now + governance.cooldown_period;
-        
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.id = tx_id;
-        proposal.tx_type = TransactionType::Restrict;
-        proposal.status = TransactionStatus::Pending;
-        proposal.initiator = ctx.accounts.signer.key();
-        proposal.target = account;
-        proposal.amount = 0;
-        proposal.data = data;
-        proposal.timestamp = now;
-        proposal.execute_after = execute_after;
-        proposal.approval_count = 0;
-        proposal.approvals = Vec::new();
-        proposal.rejection_reason = String::new();
-        proposal.rejector = Pubkey::default();
-        proposal.exists = true;
-        proposal.bump = ctx.bumps.proposal;
-        
-        emit!(TransactionQueued {
-            tx_id,
-            tx_type: TransactionType::Restrict,
-            initiator: ctx.accounts.signer.key(),
-        });
-        Ok(())
-    }
+        // Set guardian committee and pause quorum (ADMIN only)
+        pub fn set_guardians(
+            ctx: Context<AdminOnly>,
+            guardians: Vec<Pubkey>,
+            quorum: u8,
+        ) -> Result<()> {
+            require!(
+                guardians.len() <= Governance::MAX_GUARDIANS,
+                ErrorCode::TooManyGuardians
+            );
+            require!(
+                quorum > 0 && quorum as usize <= guardians.len(),
+                ErrorCode::InvalidGuardianQuorum
+            );
+            for (i, guardian) in guardians.iter().enumerate() {
+                require!(*guardian != Pubkey::default(), ErrorCode::ZeroAddress);
+                require!(!guardians[..i].contains(guardian), ErrorCode::DuplicateGuardian);
+            }
+            let governance = &mut ctx.accounts.governance;
+            governance.guardians = guardians.clone();
+            governance.guardian_quorum = quorum;
+            emit_cpi!(GuardiansChanged { guardians, quorum });
+            Ok(())
+        }
 
-    // Queue transaction: Liquidity Pool
-    pub fn set_liquidity_pool(
-        ctx: Context<QueueTransaction>,
-        pool: Pubkey,
-        value: bool,
-    ) -> Result<()> {
-        require!(pool != Pubkey::default(), ErrorCode::ZeroAddress);
-        let governance = &mut ctx.accounts.governance;
-        require!(governance.token_set, ErrorCode::TokenNotSet);
-        
-        let data = (pool, value).try_to_vec()?;
-        let tx_id = governance.next_transaction_id;
-        governance.next_transaction_id += 1;
-        
-        let now = Clock::get()?.unix_timestamp;
-        let execute_after = now + governance.cooldown_period;
-        
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.id = tx_id;
-        proposal.tx_type = TransactionType::Pair;
-        proposal.status = TransactionStatus::Pending;
-        proposal.initiator = ctx.accounts.signer.key();
-        proposal.target = pool;
-        proposal.amount = 0;
-        proposal.data = data;
-        proposal.timestamp = now;
-        proposal.execute_after = execute_after;
-        proposal.approval_count = 0;
-        proposal.approvals = Vec::new();
-        proposal.rejection_reason = String::new();
-        proposal.rejector = Pubkey::default();
-        proposal.exists = true;
-        proposal.bump = ctx.bumps.proposal;
+        // Set maximum guardian pause duration (ADMIN only)
+        pub fn set_max_pause_duration(
+            ctx: Context<AdminOnly>,
+            duration: i64,
+        ) -> Result<()> {
+            require!(duration > 0, ErrorCode::InvalidPauseDuration);
+            let governance = &mut ctx.accounts.governance;
+            governance.max_pause_duration = duration;
+            Ok(())
+        }
         
-        emit!(TransactionQueued {
-            tx_id,
-            tx_type: TransactionType::Pair,
-            initiator: ctx.accounts.signer.key(),
-        });
-        Ok(())
+        // Set how long guardians wait after a guardian pause window before
+        // pausing again (ADMIN only)
+        pub fn set_guardian_pause_cooldown(
+            ctx: Context<AdminOnly>,
+            cooldown: i64,
+        ) -> Result<()> {
+            require!(cooldown >= 0, ErrorCode::InvalidPauseDuration);
+            let governance = &mut ctx.accounts.governance;
+            governance.guardian_pause_cooldown = cooldown;
+            Ok(())
+        }
+
+        // Emergency pause (guardian quorum, immediate - no queue). Co-signing
+        // guardians are passed as signer remaining accounts; nc_token resumes
+        // on its own after `duration` seconds unless governance extends it.
+        // Guardians may lengthen a pause in force, but back-to-back guardian
+        // pauses end max_pause_duration after the first - past that it takes
+        // an ExtendPause proposal. A new guardian pause waits out
+        // guardian_pause_cooldown after that window.
+        pub fn set_emergency_pause(
+            ctx: Context<EmergencyPauseContext>,
+            duration: i64,
+            reason: String,
+        ) -> Result<()> {
+            let governance = &mut ctx.accounts.governance;
+            require!(governance.token_set, ErrorCode::TokenNotSet);
+            require!(
+                duration > 0 && duration <= governance.max_pause_duration,
+                ErrorCode::InvalidPauseDuration
+            );
+            
+            let mut guardians = vec![ctx.accounts.authority.key()];
+            for info in ctx.remaining_accounts {
+                if info.is_signer && !guardians.contains(info.key) {
+                    guardians.push(info.key());
+                }
+            }
+            require!(
+                guardians.iter().all(|g| governance.guardians.contains(g)),
+                ErrorCode::NotGuardian
+            );
+            require!(
+                guardians.len() >= governance.guardian_quorum as usize,
+                ErrorCode::InsufficientGuardians
+            );
+            let now = Clock::get()?.unix_timestamp;
+            let expires_at = now + duration;
+            let state = NCTokenState::try_deserialize(
+                &mut &ctx.accounts.nc_token_state.try_borrow_data()?[..],
+            )?;
+            if state.is_paused(now) {
+                require!(
+                    state.pause_expires_at != 0 && state.pause_expires_at < expires_at,
+                    ErrorCode::PauseAlreadyInForce
+                );
+                require!(
+                    expires_at <= governance.guardian_pause_started_at + governance.max_pause_duration,
+                    ErrorCode::PauseLimitExceeded
+                );
+            } else {
+                require!(
+                    now >= governance.guardian_pause_started_at
+                        + governance.max_pause_duration
+                        + governance.guardian_pause_cooldown,
+                    ErrorCode::GuardianPauseCooldown
+                );
+                governance.guardian_pause_started_at = now;
+            }
+            
+            // Call NC Token via CPI - governance PDA signs
+            let cpi_program = ctx.accounts.nc_token_program.to_account_info();
+            let governance_seeds: &[&[u8]] = &[
+                b"governance",
+                &[governance.bump],
+            ];
+            let signer_seeds = &[governance_seeds];
+            let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
+                state: ctx.accounts.nc_token_state.to_account_info(),
+                governance: ctx.accounts.governance.to_account_info(),
+                event_authority: ctx.accounts.nc_token_event_authority.to_account_info(),
+                program: ctx.accounts.nc_token_program.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                signer_seeds,
+            );
+            nc_token::cpi::set_emergency_pause(cpi_ctx, true, expires_at, reason.clone())?;
+            
+            emit_cpi!(EmergencyPause {
+                guardians,
+                reason,
+                expires_at,
+            });
+            Ok(())
+        }
+
+        // Queue transaction: Unpause
+        pub fn set_unpause(
+            ctx: Context<QueueTransaction>,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::Unpause,
+                Pubkey::default(),
+                Vec::new(),
+                metadata,
+            )
+        }
+
+        // Queue transaction: Extend Pause (expires_at 0 = until unpaused)
+        pub fn extend_pause(
+            ctx: Context<QueueTransaction>,
+            expires_at: i64,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(expires_at >= 0, ErrorCode::InvalidPauseDuration);
+            let data = expires_at.try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::ExtendPause,
+                Pubkey::default(),
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: Module Pause
+        pub fn set_module_pause(
+            ctx: Context<QueueTransaction>,
+            module: PauseModule,
+            value: bool,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(module != PauseModule::Protocol, ErrorCode::InvalidTransactionType);
+            let data = (module, value).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::ModulePause,
+                Pubkey::default(),
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: Blacklist (expires_at 0 = no expiry). The listed
+        // token accounts are frozen at the SPL level on execution (thawed when
+        // the blacklist is lifted).
+        #[allow(clippy::too_many_arguments)]
+        pub fn set_blacklist(
+            ctx: Context<QueueTransaction>,
+            account: Pubkey,
+            value: bool,
+            reason_code: u16,
+            evidence_hash: [u8; 32],
+            expires_at: i64,
+            freeze_token_accounts: Vec<Pubkey>,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(account != Pubkey::default(), ErrorCode::ZeroAddress);
+            require!(expires_at >= 0, ErrorCode::InvalidExpiry);
+            let details = BlacklistDetails {
+                reason_code,
+                evidence_hash,
+                proposed_by: ctx.accounts.signer.key(),
+                expires_at,
+            };
+            let data = (account, value, details, freeze_token_accounts).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::Blacklist,
+                account,
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: Freeze (SPL freeze/thaw of an NC token account)
+        pub fn set_freeze(
+            ctx: Context<QueueTransaction>,
+            token_account: Pubkey,
+            value: bool,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(token_account != Pubkey::default(), ErrorCode::ZeroAddress);
+            let data = (token_account, value).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::Freeze,
+                token_account,
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: List Batch (blacklist, restricted, no-sell-limit or
+        // LP updates under one proposal; the blacklist details apply to every
+        // entry and are ignored for other lists)
+        pub fn set_list_batch(
+            ctx: Context<QueueTransaction>,
+            list: AddressList,
+            updates: Vec<ListUpdate>,
+            reason_code: u16,
+            evidence_hash: [u8; 32],
+            expires_at: i64,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(!updates.is_empty(), ErrorCode::EmptyBatch);
+            require!(
+                updates.iter().all(|u| u.account != Pubkey::default()),
+                ErrorCode::ZeroAddress
+            );
+            require!(expires_at >= 0, ErrorCode::InvalidExpiry);
+            let batch = ListBatch {
+                list,
+                details: BlacklistDetails {
+                    reason_code,
+                    evidence_hash,
+                    proposed_by: ctx.accounts.signer.key(),
+                    expires_at,
+                },
+                updates,
+            };
+            let data = batch.try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::ListBatch,
+                Pubkey::default(),
+                data,
+                metadata,
+            )
+        }
+
+        // Append entries to a queued list batch that does not fit in one
+        // transaction (initiator only, before any approval; restarts the cooldown)
+        pub fn append_list_batch(
+            ctx: Context<AppendListBatch>,
+            tx_id: u64,
+            updates: Vec<ListUpdate>,
+        ) -> Result<()> {
+            let proposal = &mut ctx.accounts.proposal;
+            require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
+            require!(
+                proposal.tx_type == TransactionType::ListBatch,
+                ErrorCode::InvalidTransactionType
+            );
+            require!(
+                proposal.status == TransactionStatus::Pending,
+                ErrorCode::TransactionNotPending
+            );
+            require!(proposal.approval_count == 0, ErrorCode::BatchLocked);
+            require!(!updates.is_empty(), ErrorCode::EmptyBatch);
+            require!(
+                updates.iter().all(|u| u.account != Pubkey::default()),
+                ErrorCode::ZeroAddress
+            );
+            
+            require!(
+                proposal.data.len() + updates.len() * ListUpdate::LEN <= PendingTransaction::MAX_DATA,
+                ErrorCode::DataTooLarge
+            );
+            let added = updates.len() as u32;
+            let total = ListBatch::append(&mut proposal.data, &updates)?;
+            proposal.execute_after =
+                Clock::get()?.unix_timestamp + ctx.accounts.governance.cooldown_period;
+            
+            resize_proposal(
+                &proposal.to_account_info(),
+                &ctx.accounts.initiator.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                proposal.space(),
+            )?;
+            
+            emit_cpi!(ListBatchAppended {
+                tx_id,
+                added,
+                total: total as u32,
+            });
+            Ok(())
+        }
+
+        // Queue transaction: Module Address (rotates bridge / treasury / bond)
+        pub fn set_module_address(
+            ctx: Context<QueueTransaction>,
+            module: ProtocolModule,
+            new_address: Pubkey,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(new_address != Pubkey::default(), ErrorCode::ZeroAddress);
+            let data = (module, new_address).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::ModuleAddress,
+                new_address,
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: Merkle Root (blacklist or no-sell-limit list;
+        // a zero root switches Merkle mode off)
+        pub fn set_merkle_root(
+            ctx: Context<QueueTransaction>,
+            list: AddressList,
+            root: [u8; 32],
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(
+                matches!(list, AddressList::Blacklist | AddressList::NoSellLimit),
+                ErrorCode::InvalidTransactionType
+            );
+            let data = (list, root).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::MerkleRoot,
+                Pubkey::default(),
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: No Sell Limit
+        pub fn set_no_sell_limit(
+            ctx: Context<QueueTransaction>,
+            account: Pubkey,
+            value: bool,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(account != Pubkey::default(), ErrorCode::ZeroAddress);
+            let data = (account, value).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::NoSellLimit,
+                account,
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: Restricted
+        pub fn set_restricted(
+            ctx: Context<QueueTransaction>,
+            account: Pubkey,
+            value: bool,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(account != Pubkey::default(), ErrorCode::ZeroAddress);
+            let data = (account, value).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::Restrict,
+                account,
+                data,
+                metadata,
+            )
+        }
+
+        // Queue transaction: Liquidity Pool
+        pub fn set_liquidity_pool(
+            ctx: Context<QueueTransaction>,
+            pool: Pubkey,
+            value: bool,
+            metadata: ProposalMetadata,
+        ) -> Result<()> {
+            require!(pool != Pubkey::default(), ErrorCode::ZeroAddress);
+            let data = (pool, value).try_to_vec()?;
+            queue_proposal(
+                ctx.accounts,
+                &ctx.bumps,
+                TransactionType::Pair,
+                pool,
+                data,
+                metadata,
+            )
+        }
+
+        // Approve transaction (creates the approver's approval PDA; pass the
+        // other approval PDAs as remaining accounts to allow auto-execution).
+        // With expected_hash set, fails unless the proposal's content_hash
+        // matches. An approval is always given under the current config, so
+        // it also carries a stale proposal over to it.
+        pub fn approve_transaction<'info>(
+            ctx: Context<'_, '_, '_, 'info, ApproveTransaction<'info>>,
+            tx_id: u64,
+            expected_hash: Option<[u8; 32]>,
+        ) -> Result<()> {
+            let proposal = &mut ctx.accounts.proposal;
+            let governance = &ctx.accounts.governance;
+            
+            require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
+            require_content(proposal, expected_hash)?;
+            require!(proposal.exists, ErrorCode::TransactionNotFound);
+            require!(
+                proposal.status == TransactionStatus::Pending,
+                ErrorCode::TransactionNotPending
+            );
+            
+            let approver = ctx.accounts.approver.key();
+            require!(governance.owners.contains(&approver), ErrorCode::NotOwner);
+            
+            // A second approval by the same owner fails on the existing PDA
+            let now = Clock::get()?.unix_timestamp;
+            let approval = &mut ctx.accounts.approval;
+            approval.proposal = proposal.key();
+            approval.owner = approver;
+            approval.config_nonce = governance.config_nonce;
+            approval.timestamp = now;
+            approval.bump = ctx.bumps.approval;
+            proposal.approval_count += 1;
+            proposal.config_nonce = governance.config_nonce;
+            
+            emit_cpi!(TransactionApproved {
+                tx_id,
+                approver,
+                config_nonce: governance.config_nonce,
+            });
+            
+            // Auto-execute if enough approvals and cooldown expired
+            let approvals = 1 + count_valid_approvals(
+                governance,
+                &proposal.key(),
+                ctx.remaining_accounts,
+                vec![approver],
+            )?;
+            if approvals >= governance.required_approvals &&
+               now >= proposal.execute_after {
+                execute_proposal(
+                    governance,
+                    proposal,
+                    ctx.accounts.nc_token_program.to_account_info(),
+                    ctx.accounts.nc_token_state.to_account_info(),
+                    ctx.accounts.nc_token_event_authority.to_account_info(),
+                    ctx.remaining_accounts,
+                    &EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority),
+                    approver,
+                    ExecutionMode::Auto,
+                )?;
+            }
+            
+            Ok(())
+        }
+
+        // Re-approve transaction (refreshes an approval given under an older
+        // config when re-approval is required; the first re-approval also
+        // carries a proposal queued under an older config over to the current one)
+        pub fn reapprove_transaction(
+            ctx: Context<ReapproveTransaction>,
+            tx_id: u64,
+        ) -> Result<()> {
+            let proposal = &mut ctx.accounts.proposal;
+            let governance = &ctx.accounts.governance;
+            
+            require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
+            require!(
+                proposal.status == TransactionStatus::Pending,
+                ErrorCode::TransactionNotPending
+            );
+            
+            let approver = ctx.accounts.approver.key();
+            require!(governance.owners.contains(&approver), ErrorCode::NotOwner);
+            
+            let approval = &mut ctx.accounts.approval;
+            require!(
+                governance.require_reapproval &&
+                    approval.config_nonce != governance.config_nonce,
+                ErrorCode::AlreadyApproved
+            );
+            approval.config_nonce = governance.config_nonce;
+            approval.timestamp = Clock::get()?.unix_timestamp;
+            proposal.config_nonce = governance.config_nonce;
+            
+            emit_cpi!(TransactionApproved {
+                tx_id,
+                approver,
+                config_nonce: governance.config_nonce,
+            });
+            Ok(())
+        }
+
+        // Reject transaction
+        pub fn reject_transaction(
+            ctx: Context<RejectTransaction>,
+            tx_id: u64,
+            reason: String,
+        ) -> Result<()> {
+            let proposal = &mut ctx.accounts.proposal;
+            
+            require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
+            require!(proposal.exists, ErrorCode::TransactionNotFound);
+            require!(
+                proposal.status == TransactionStatus::Pending,
+                ErrorCode::TransactionNotPending
+            );
+            require!(proposal.executed_count == 0, ErrorCode::BatchInProgress);
+            require!(!reason.is_empty(), ErrorCode::RejectionReasonRequired);
+            require!(
+                reason.len() <= PendingTransaction::MAX_REASON,
+                ErrorCode::RejectionReasonTooLong
+            );
+            require!(
+                ctx.accounts.governance.owners.contains(&ctx.accounts.approver.key()),
+                ErrorCode::NotOwner
+            );
+            
+            proposal.status = TransactionStatus::Rejected;
+            proposal.rejection_reason = reason.clone();
+            proposal.rejector = ctx.accounts.approver.key();
+            
+            // Grow the proposal to hold the reason - the rejector pays the extra
+            // rent and gets it back when the proposal is closed
+            proposal.rejection_rent = resize_proposal(
+                &proposal.to_account_info(),
+                &ctx.accounts.approver.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                proposal.space(),
+            )?;
+            
+            emit_cpi!(TransactionRejected {
+                tx_id,
+                approver: ctx.accounts.approver.key(),
+                reason,
+            });
+            
+            Ok(())
+        }
+
+        // Close a finished proposal (initiator or an owner) with its approval
+        // records (remaining accounts, as record / approver pairs). Records
+        // can be closed a batch per call; each approver gets its record's
+        // rent back. The call that closes the last record closes the
+        // proposal: the rejector gets the rent it paid to store the reason,
+        // the rest goes to the initiator
+        pub fn close_proposal<'info>(
+            ctx: Context<'_, '_, '_, 'info, CloseProposal<'info>>,
+            tx_id: u64,
+        ) -> Result<()> {
+            let proposal = &mut ctx.accounts.proposal;
+            require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
+            require!(
+                proposal.status != TransactionStatus::Pending,
+                ErrorCode::TransactionStillPending
+            );
+            let closer = ctx.accounts.closer.key();
+            require!(
+                closer == proposal.initiator || ctx.accounts.governance.owners.contains(&closer),
+                ErrorCode::Unauthorized
+            );
+            
+            close_approval_records(proposal, ctx.remaining_accounts)?;
+            if proposal.approval_count > 0 {
+                // Open until its last record is closed
+                return Ok(());
+            }
+            
+            if proposal.rejection_rent > 0 {
+                let rejector = ctx.accounts.rejector.as_ref().ok_or(ErrorCode::MissingAccount)?;
+                let info = proposal.to_account_info();
+                **info.try_borrow_mut_lamports()? -= proposal.rejection_rent;
+                **rejector.try_borrow_mut_lamports()? += proposal.rejection_rent;
+            }
+            
+            emit_cpi!(ProposalClosed {
+                tx_id,
+                initiator: proposal.initiator,
+                refunded: proposal.to_account_info().lamports(),
+            });
+            proposal.close(ctx.accounts.initiator.to_account_info())
+        }
+
+        // Execute transaction manually (approval PDAs as remaining accounts;
+        // expected_hash as for approve_transaction)
+        pub fn execute_transaction<'info>(
+            ctx: Context<'_, '_, '_, 'info, CanApprove<'info>>,
+            tx_id: u64,
+            expected_hash: Option<[u8; 32]>,
+        ) -> Result<()> {
+            let proposal = &mut ctx.accounts.proposal;
+            let governance = &ctx.accounts.governance;
+            
+            require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
+            require_content(proposal, expected_hash)?;
+            require!(
+                governance.owners.contains(&ctx.accounts.approver.key()),
+                ErrorCode::NotOwner
+            );
+            require_executable(governance, proposal, ctx.remaining_accounts)?;
+            
+            execute_proposal(
+                governance,
+                proposal,
+                ctx.accounts.nc_token_program.to_account_info(),
+                ctx.accounts.nc_token_state.to_account_info(),
+                ctx.accounts.nc_token_event_authority.to_account_info(),
+                ctx.remaining_accounts,
+                &EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority),
+                ctx.accounts.approver.key(),
+                ExecutionMode::Manual,
+            )
+        }
+
+        // Crank execution (permissionless - anyone, e.g. a keeper bot, can call
+        // once the proposal is approved and past execute_after; approval PDAs
+        // as remaining accounts)
+        pub fn crank_execute<'info>(
+            ctx: Context<'_, '_, '_, 'info, CrankExecute<'info>>,
+            tx_id: u64,
+        ) -> Result<()> {
+            let proposal = &mut ctx.accounts.proposal;
+            let governance = &ctx.accounts.governance;
+            
+            require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
+            require_executable(governance, proposal, ctx.remaining_accounts)?;
+            
+            execute_proposal(
+                governance,
+                proposal,
+                ctx.accounts.nc_token_program.to_account_info(),
+                ctx.accounts.nc_token_state.to_account_info(),
+                ctx.accounts.nc_token_event_authority.to_account_info(),
+                ctx.remaining_accounts,
+                &EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority),
+                ctx.accounts.cranker.key(),
+                ExecutionMode::Crank,
+            )
+        }
     }
+}
 
-    // Approve transaction
-    pub fn approve_transaction(
-        ctx: Context<CanApprove>,
-        tx_id: u64,
-    ) -> Result<()> {
-        let proposal = &mut ctx.accounts.proposal;
-        let governance = &ctx.accounts.governance;
-        
-        require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
-        require!(proposal.exists, ErrorCode::TransactionNotFound);
-        require!(
-            proposal.status == TransactionStatus::Pending,
-            ErrorCode::TransactionNotPending
-        );
+pub use program_module::*;
+
+// Helper: Check a proposal is pending, approved and past its cooldown
+fn require_executable(
+    governance: &Account<Governance>,
+    proposal: &Account<PendingTransaction>,
+    approval_accounts: &[AccountInfo],
+) -> Result<()> {
+    require!(proposal.exists, ErrorCode::TransactionNotFound);
+    require!(
+        proposal.status == TransactionStatus::Pending,
+        ErrorCode::TransactionNotPending
+    );
+    
+    require_current_config(governance, proposal)?;
+    
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= proposal.execute_after,
+        ErrorCode::CooldownNotExpired
+    );
+    require!(
+        count_valid_approvals(
+            governance,
+            &proposal.key(),
+            approval_accounts,
+            Vec::new(),
+        )? >= governance.required_approvals,
+        ErrorCode::InsufficientApprovals
+    );
+    Ok(())
+}
+
+// Helper: When re-approval is required, a proposal queued under an older
+// config can't execute until an approval or re-approval under the current
+// config carries it over
+fn require_current_config(
+    governance: &Governance,
+    proposal: &PendingTransaction,
+) -> Result<()> {
+    require!(
+        !governance.require_reapproval ||
+            proposal.config_nonce == governance.config_nonce,
+        ErrorCode::StaleProposal
+    );
+    Ok(())
+}
+
+// Helper: An approval or execution signed against a copy of the proposal
+// (offline signing) only goes through if the proposal still matches it
+fn require_content(
+    proposal: &PendingTransaction,
+    expected_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    if let Some(expected_hash) = expected_hash {
         require!(
-            !proposal.approvals.contains(&ctx.accounts.approver.key()),
-            ErrorCode::AlreadyApproved
+            proposal.content_hash() == expected_hash,
+            ErrorCode::ProposalMismatch
         );
-        
-        proposal.approvals.push(ctx.accounts.approver.key());
-        proposal.approval_count += 1;
-        
-        emit!(TransactionApproved {
-            tx_id,
-            approver: ctx.accounts.approver.key(),
-        });
-        
-        // Auto-execute if enough approvals and cooldown expired
-        let now = Clock::get()?.unix_timestamp;
-        if proposal.approval_count >= governance.required_approvals &&
-           now >= proposal.execute_after {
-            execute_transaction(ctx, tx_id)?;
+    }
+    Ok(())
+}
+
+// Helper: Count approval PDAs (governance-owned accounts) from current owners (and, when re-approval is
+// required, only those given under the current config). Owners in `counted`
+// were already tallied by the caller.
+fn count_valid_approvals(
+    governance: &Governance,
+    proposal: &Pubkey,
+    approval_accounts: &[AccountInfo],
+    mut counted: Vec<Pubkey>,
+) -> Result<u8> {
+    let mut valid: u8 = 0;
+    for info in approval_accounts {
+        // Remaining accounts may also carry token accounts for freezing
+        if *info.owner != crate::ID {
+            continue;
         }
+        let approval = ApprovalRecord::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(approval.proposal, *proposal, ErrorCode::InvalidApprovalRecord);
+        require!(!counted.contains(&approval.owner), ErrorCode::DuplicateApproval);
+        counted.push(approval.owner);
         
-        Ok(())
+        if governance.owners.contains(&approval.owner) &&
+           (!governance.require_reapproval ||
+            approval.config_nonce == governance.config_nonce) {
+            valid += 1;
+        }
     }
+    Ok(valid)
+}
 
-    // Reject transaction
-    pub fn reject_transaction(
-        ctx: Context<CanApprove>,
-        tx_id: u64,
-        reason: String,
-    ) -> Result<()> {
-        let proposal = &mut ctx.accounts.proposal;
+// Helper: Close approval records of a proposal, given as record / approver
+// pairs, refunding each approver its rent and counting them off the
+// proposal's approval_count. A record given twice fails the close
+fn close_approval_records(
+    proposal: &mut Account<PendingTransaction>,
+    accounts: &[AccountInfo],
+) -> Result<()> {
+    require!(accounts.len().is_multiple_of(2), ErrorCode::MissingAccount);
+    for pair in accounts.chunks(2) {
+        let (record, approver) = (&pair[0], &pair[1]);
+        require_keys_eq!(*record.owner, crate::ID, ErrorCode::InvalidApprovalRecord);
+        let approval = ApprovalRecord::try_deserialize(&mut &record.try_borrow_data()?[..])?;
+        require_keys_eq!(approval.proposal, proposal.key(), ErrorCode::InvalidApprovalRecord);
+        require_keys_eq!(approval.owner, approver.key(), ErrorCode::InvalidApprovalRecord);
         
-        require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
-        require!(proposal.exists, ErrorCode::TransactionNotFound);
-        require!(
-            proposal.status == TransactionStatus::Pending,
-            ErrorCode::TransactionNotPending
-        );
-        require!(reason.len() > 0, ErrorCode::RejectionReasonRequired);
-        
-        proposal.status = TransactionStatus::Rejected;
-        proposal.rejection_reason = reason.clone();
-        proposal.rejector = ctx.accounts.approver.key();
-        
-        emit!(TransactionRejected {
-            tx_id,
-            approver: ctx.accounts.approver.key(),
-            reason,
-        });
-        
-        Ok(())
+        let rent = record.lamports();
+        **record.try_borrow_mut_lamports()? = 0;
+        **approver.try_borrow_mut_lamports()? += rent;
+        record.assign(&system_program::ID);
+        record.resize(0)?;
+        // Every record was counted when it was created
+        proposal.approval_count -= 1;
     }
+    Ok(())
+}
 
-    // Execute transaction manually
-    pub fn execute_transaction(
-        ctx: Context<CanApprove>,
-        tx_id: u64,
-    ) -> Result<()> {
-        let proposal = &ctx.accounts.proposal;
-        let governance = &ctx.accounts.governance;
-        
-        require!(proposal.id == tx_id, ErrorCode::TransactionIdMismatch);
-        require!(proposal.exists, ErrorCode::TransactionNotFound);
-        require!(
-            proposal.status == TransactionStatus::Pending,
-            ErrorCode::TransactionNotPending
-        );
-        
-        let now = Clock::get()?.unix_timestamp;
-        require!(
-            now >= proposal.execute_after,
-            ErrorCode::CooldownNotExpired
-        );
-        require!(
-            proposal.approval_count >= governance.required_approvals,
-            ErrorCode::InsufficientApprovals
-        );
-        
-        execute_transaction(ctx, tx_id)?;
-        Ok(())
+// Helper: Queue transaction (fills in the new proposal PDA, grown to fit data)
+fn queue_proposal(
+    accounts: &mut QueueTransaction,
+    bumps: &QueueTransactionBumps,
+    tx_type: TransactionType,
+    target: Pubkey,
+    data: Vec<u8>,
+    metadata: ProposalMetadata,
+) -> Result<()> {
+    let governance = &mut accounts.governance;
+    require!(governance.token_set, ErrorCode::TokenNotSet);
+    require!(data.len() <= PendingTransaction::MAX_DATA, ErrorCode::DataTooLarge);
+    require!(
+        metadata.title.len() <= PendingTransaction::MAX_TITLE,
+        ErrorCode::TitleTooLong
+    );
+    require!(
+        metadata.description_uri.len() <= PendingTransaction::MAX_DESCRIPTION_URI,
+        ErrorCode::DescriptionUriTooLong
+    );
+    
+    let initiator = accounts.signer.key();
+    let proposal = &mut accounts.proposal;
+    let tx_id = governance.next_transaction_id;
+    governance.next_transaction_id += 1;
+    
+    let now = Clock::get()?.unix_timestamp;
+    let execute_after = now + governance.cooldown_period;
+    
+    proposal.id = tx_id;
+    proposal.tx_type = tx_type;
+    proposal.status = TransactionStatus::Pending;
+    proposal.initiator = initiator;
+    proposal.target = target;
+    proposal.amount = 0;
+    proposal.data = data;
+    proposal.timestamp = now;
+    proposal.execute_after = execute_after;
+    proposal.approval_count = 0;
+    proposal.rejection_reason = String::new();
+    proposal.rejector = Pubkey::default();
+    proposal.rejection_rent = 0;
+    proposal.config_nonce = governance.config_nonce;
+    proposal.title = metadata.title.clone();
+    proposal.description_uri = metadata.description_uri.clone();
+    proposal.description_hash = metadata.description_hash;
+    proposal.executed_count = 0;
+    proposal.exists = true;
+    proposal.bump = bumps.proposal;
+    
+    resize_proposal(
+        &proposal.to_account_info(),
+        &accounts.signer.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        proposal.space(),
+    )?;
+    
+    let events = EventCpi::new(crate::ID, &accounts.event_authority, bumps.event_authority);
+    events.emit(&TransactionQueued {
+        tx_id,
+        tx_type,
+        initiator,
+        config_nonce: governance.config_nonce,
+        title: metadata.title,
+        description_uri: metadata.description_uri,
+        description_hash: metadata.description_hash,
+    })?;
+    Ok(())
+}
+
+// Helper: Realloc a proposal, topping up rent from the payer. Returns the
+// lamports the payer put in
+fn resize_proposal<'info>(
+    proposal: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<u64> {
+    let rent = Rent::get()?.minimum_balance(new_len);
+    let lamports = proposal.lamports();
+    let top_up = rent.saturating_sub(lamports);
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: proposal.clone(),
+                },
+            ),
+            top_up,
+        )?;
     }
+    proposal.resize(new_len)?;
+    Ok(top_up)
+}
 
-    // Helper: Queue transaction (creates proposal PDA)
-    fn queue_transaction(
-        governance: &mut Account<Governance>,
-        tx_type: TransactionType,
-        target: Pubkey,
-        amount: u64,
-        data: Vec<u8>,
-    ) -> Result<u64> {
-        let tx_id = governance.next_transaction_id;
-        governance.next_transaction_id += 1;
-        Ok(tx_id)
+// Helper: Freeze or thaw an NC token account through nc_token. The token
+// account, its mint and the token program are looked up in `accounts`.
+#[allow(clippy::too_many_arguments)]
+fn set_token_account_frozen<'info>(
+    governance: &Account<'info, Governance>,
+    nc_token_program: &AccountInfo<'info>,
+    nc_token_state: &AccountInfo<'info>,
+    nc_token_event_authority: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    events: &EventCpi,
+    token_account: Pubkey,
+    owner: Option<Pubkey>,
+    freeze: bool,
+) -> Result<()> {
+    let find = |key: &Pubkey| {
+        accounts
+            .iter()
+            .find(|a| a.key == key)
+            .cloned()
+            .ok_or_else(|| error!(ErrorCode::MissingAccount))
+    };
+    let token_account_info = find(&token_account)?;
+    let account_state =
+        TokenAccount::try_deserialize(&mut &token_account_info.try_borrow_data()?[..])?;
+    // A blacklist freeze only reaches the blacklisted wallet's own accounts
+    if let Some(owner) = owner {
+        require_keys_eq!(account_state.owner, owner, ErrorCode::TokenAccountOwnerMismatch);
     }
+    // The token program rejects freezing a frozen account (and vice versa)
+    if account_state.is_frozen() == freeze {
+        return Ok(());
+    }
+    
+    let governance_seeds: &[&[u8]] = &[
+        b"governance",
+        &[governance.bump],
+    ];
+    let signer_seeds = &[governance_seeds];
+    let cpi_accounts = nc_token::cpi::accounts::FreezeControl {
+        state: nc_token_state.clone(),
+        governance: governance.to_account_info(),
+        mint: find(&account_state.mint)?,
+        token_account: token_account_info,
+        token_program: find(&token::ID)?,
+        event_authority: nc_token_event_authority.clone(),
+        program: nc_token_program.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        nc_token_program.clone(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    if freeze {
+        nc_token::cpi::freeze_account(cpi_ctx)?;
+    } else {
+        nc_token::cpi::thaw_account(cpi_ctx)?;
+    }
+    events.emit(&TokenAccountFreezeSet { token_account, frozen: freeze })?;
+    Ok(())
+}
 
-    // Helper: Execute transaction
-    fn execute_transaction(ctx: Context<CanApprove>, tx_id: u64) -> Result<()> {
-        let proposal = &ctx.accounts.proposal;
-        let governance = &ctx.accounts.governance;
-        
-        // Call NC Token based on transaction type
-        let cpi_program = ctx.accounts.nc_token_program.to_account_info();
-        
-        // Governance PDA signs for CPI
-        let governance_seeds = &[
-            b"governance",
-            &[governance.bump],
-        ];
-        
-        match proposal.tx_type {
-            TransactionType::Unpause => {
-                let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
-                    state: ctx.accounts.nc_token_state.to_account_info(),
-                    governance: governance.to_account_info(),
-                };
-                let cpi_ctx = CpiContext::new_with_signer(
-                    cpi_program,
-                    cpi_accounts,
-                    &[governance_seeds],
-                );
-                nc_token::cpi::set_emergency_pause(cpi_ctx, false)?;
-                emit!(EmergencyUnpause {});
-            }
-            TransactionType::Blacklist => {
-                let (account, value): (Pubkey, bool) = 
-                    try_from_slice(&proposal.data)?;
-                let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
-                    state: ctx.accounts.nc_token_state.to_account_info(),
-                    governance: governance.to_account_info(),
-                };
-                let cpi_ctx = CpiContext::new_with_signer(
-                    cpi_program,
-                    cpi_accounts,
-                    &[governance_seeds],
-                );
-                nc_token::cpi::set_blacklist(cpi_ctx, account, value)?;
-                emit!(AddressBlacklisted { account, status: value });
-            }
-            TransactionType::NoSellLimit => {
-                let (account, value): (Pubkey, bool) = 
-                    try_from_slice(&proposal.data)?;
-                let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
-                    state: ctx.accounts.nc_token_state.to_account_info(),
-                    governance: governance.to_account_info(),
-                };
-                let cpi_ctx = CpiContext::new_with_signer(
-                    cpi_program,
-                    cpi_accounts,
-                    &[governance_seeds],
-                );
-                nc_token::cpi::set_no_sell_limit(cpi_ctx, account, value)?;
-                emit!(NoSellLimitSet { account, status: value });
-            }
-            TransactionType::Restrict => {
-                let (account, value): (Pubkey, bool) = 
-                    try_from_slice(&proposal.data)?;
-                let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
-                    state: ctx.accounts.nc_token_state.to_account_info(),
-                    governance: governance.to_account_info(),
-                };
-                let cpi_ctx = CpiContext::new_with_signer(
-                    cpi_program,
-                    cpi_accounts,
-                    &[governance_seeds],
-                );
-                nc_token::cpi::set_restricted(cpi_ctx, account, value)?;
-                emit!(AddressRestricted { account, status: value });
+// Helper: Execute transaction (shared by manual, auto and crank execution)
+#[allow(clippy::too_many_arguments)]
+fn execute_proposal<'info>(
+    governance: &Account<'info, Governance>,
+    proposal: &mut Account<'info, PendingTransaction>,
+    nc_token_program: AccountInfo<'info>,
+    nc_token_state: AccountInfo<'info>,
+    nc_token_event_authority: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    events: &EventCpi,
+    executor: Pubkey,
+    mode: ExecutionMode,
+) -> Result<()> {
+    // Governance PDA signs for CPI
+    let governance_seeds: &[&[u8]] = &[
+        b"governance",
+        &[governance.bump],
+    ];
+    let signer_seeds = &[governance_seeds];
+    let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
+        state: nc_token_state.clone(),
+        governance: governance.to_account_info(),
+        event_authority: nc_token_event_authority.clone(),
+        program: nc_token_program.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        nc_token_program.clone(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    
+    // Call NC Token based on transaction type
+    match proposal.tx_type {
+        TransactionType::Unpause => {
+            let reason = proposal.title.clone();
+            nc_token::cpi::set_emergency_pause(cpi_ctx, false, 0, reason.clone())?;
+            events.emit(&EmergencyUnpause { reason })?;
+        }
+        TransactionType::ExtendPause => {
+            let expires_at = i64::try_from_slice(&proposal.data)?;
+            let reason = proposal.title.clone();
+            nc_token::cpi::set_emergency_pause(cpi_ctx, true, expires_at, reason.clone())?;
+            events.emit(&EmergencyPauseExtended { reason, expires_at })?;
+        }
+        TransactionType::ModulePause => {
+            let (module, value) =
+                <(PauseModule, bool)>::try_from_slice(&proposal.data)?;
+            let reason = proposal.title.clone();
+            nc_token::cpi::set_module_pause(cpi_ctx, module, value, reason)?;
+            events.emit(&ModulePauseSet { module, paused: value })?;
+        }
+        TransactionType::Blacklist => {
+            let (account, value, details, freeze_token_accounts) =
+                <(Pubkey, bool, BlacklistDetails, Vec<Pubkey>)>::try_from_slice(&proposal.data)?;
+            nc_token::cpi::set_blacklist(cpi_ctx, account, value, details)?;
+            // Optionally freeze (or, when lifting, thaw) the wallet's token accounts
+            for token_account in freeze_token_accounts {
+                set_token_account_frozen(
+                    governance,
+                    &nc_token_program,
+                    &nc_token_state,
+                    &nc_token_event_authority,
+                    remaining_accounts,
+                    events,
+                    token_account,
+                    Some(account),
+                    value,
+                )?;
             }
-            TransactionType::Pair => {
-                let (pool, value): (Pubkey, bool) = 
-                    try_from_slice(&proposal.data)?;
-                let cpi_accounts = nc_token::cpi::accounts::GovernanceOnly {
-                    state: ctx.accounts.nc_token_state.to_account_info(),
-                    governance: governance.to_account_info(),
-                };
-                let cpi_ctx = CpiContext::new_with_signer(
-                    cpi_program,
-                    cpi_accounts,
-                    &[governance_seeds],
-                );
-                nc_token::cpi::set_liquidity_pool(cpi_ctx, pool, value)?;
-                emit!(PairSet { account: pool, status: value });
+            events.emit(&AddressBlacklisted {
+                account,
+                status: value,
+                reason_code: details.reason_code,
+                evidence_hash: details.evidence_hash,
+                proposed_by: details.proposed_by,
+                expires_at: details.expires_at,
+            })?;
+        }
+        TransactionType::NoSellLimit => {
+            let (account, value) =
+                <(Pubkey, bool)>::try_from_slice(&proposal.data)?;
+            nc_token::cpi::set_no_sell_limit(cpi_ctx, account, value)?;
+            events.emit(&NoSellLimitSet { account, status: value })?;
+        }
+        TransactionType::Restrict => {
+            let (account, value) =
+                <(Pubkey, bool)>::try_from_slice(&proposal.data)?;
+            nc_token::cpi::set_restricted(cpi_ctx, account, value)?;
+            events.emit(&AddressRestricted { account, status: value })?;
+        }
+        TransactionType::Freeze => {
+            let (token_account, value) =
+                <(Pubkey, bool)>::try_from_slice(&proposal.data)?;
+            set_token_account_frozen(
+                governance,
+                &nc_token_program,
+                &nc_token_state,
+                &nc_token_event_authority,
+                remaining_accounts,
+                events,
+                token_account,
+                None,
+                value,
+            )?;
+        }
+        TransactionType::Pair => {
+            let (pool, value) =
+                <(Pubkey, bool)>::try_from_slice(&proposal.data)?;
+            nc_token::cpi::set_liquidity_pool(cpi_ctx, pool, value)?;
+            events.emit(&PairSet { account: pool, status: value })?;
+        }
+        TransactionType::ModuleAddress => {
+            let (module, address) =
+                <(ProtocolModule, Pubkey)>::try_from_slice(&proposal.data)?;
+            nc_token::cpi::set_module_address(cpi_ctx, module, address)?;
+            events.emit(&ModuleAddressSet { module, address })?;
+        }
+        TransactionType::MerkleRoot => {
+            let (list, root) =
+                <(AddressList, [u8; 32])>::try_from_slice(&proposal.data)?;
+            nc_token::cpi::set_merkle_root(cpi_ctx, list, root)?;
+            events.emit(&MerkleRootUpdated { list, root })?;
+        }
+        TransactionType::ListBatch => {
+            // Applied BATCH_CHUNK entries per execution call; the proposal
+            // stays Pending until the last chunk lands
+            let (list, details, total) = ListBatch::header(&proposal.data)?;
+            let start = proposal.executed_count as usize;
+            let end = (start + PendingTransaction::BATCH_CHUNK).min(total);
+            let updates = ListBatch::entries(&proposal.data, start, end)?;
+            nc_token::cpi::set_list_batch(cpi_ctx, list, updates, details)?;
+            proposal.executed_count = end as u32;
+            events.emit(&ListBatchChunkExecuted {
+                tx_id: proposal.id,
+                list,
+                from: start as u32,
+                to: end as u32,
+                total: total as u32,
+            })?;
+            if end < total {
+                return Ok(());
             }
-            _ => return Err(ErrorCode::InvalidTransactionType.into()),
         }
-        
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.status = TransactionStatus::Executed;
-        
-        emit!(TransactionExecuted { tx_id, auto_executed: false });
-        Ok(())
+        _ => return Err(ErrorCode::InvalidTransactionType.into()),
     }
+    
+    proposal.status = match mode {
+        ExecutionMode::Manual => TransactionStatus::Executed,
+        ExecutionMode::Auto => TransactionStatus::AutoExecuted,
+        ExecutionMode::Crank => TransactionStatus::CrankExecuted,
+    };
+    
+    events.emit(&TransactionExecuted {
+        tx_id: proposal.id,
+        auto_executed: mode == ExecutionMode::Auto,
+        mode,
+        executor,
+    })?;
+    Ok(())
 }
 
 // Account Structures
@@ -501,18 +1258,41 @@ pub struct Governance {
     pub cooldown_period: i64,
     pub required_approvals: u8,
     pub nc_token: Pubkey,
+    pub nc_token_state: Pubkey,     // nc_token_state PDA of nc_token
     pub token_set: bool,
     pub next_transaction_id: u64,
+    pub admin: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub config_nonce: u64,          // bumped on every owner/threshold change
+    pub require_reapproval: bool,   // approvals from an older config don't count
+    pub guardians: Vec<Pubkey>,     // fast-path emergency pause committee
+    pub guardian_quorum: u8,
+    pub max_pause_duration: i64,    // seconds a guardian pause may last
+    pub guardian_pause_started_at: i64, // first of the latest back-to-back guardian pauses
+    pub guardian_pause_cooldown: i64, // seconds after a guardian pause window before the next
     pub bump: u8,
 }
 
 impl Governance {
+    pub const MAX_OWNERS: usize = 64;
+    pub const MAX_GUARDIANS: usize = 10;
+    
     pub const LEN: usize = 8 + // discriminator
         8 + // cooldown_period
         1 + // required_approvals
         32 + // nc_token
+        32 + // nc_token_state
         1 + // token_set
         8 + // next_transaction_id
+        32 + // admin
+        4 + (32 * Self::MAX_OWNERS) + // owners
+        8 + // config_nonce
+        1 + // require_reapproval
+        4 + (32 * Self::MAX_GUARDIANS) + // guardians
+        1 + // guardian_quorum
+        8 + // max_pause_duration
+        8 + // guardian_pause_started_at
+        8 + // guardian_pause_cooldown
         1; // bump
 }
 
@@ -527,38 +1307,111 @@ pub struct PendingTransaction {
     pub data: Vec<u8>,
     pub timestamp: i64,
     pub execute_after: i64,
-    pub approval_count: u8,
-    pub approvals: Vec<Pubkey>,
+    pub approval_count: u8, // approval PDAs created, valid or not
     pub rejection_reason: String,
     pub rejector: Pubkey,
+    pub rejection_rent: u64, // rent the rejector paid to store the reason
+    pub config_nonce: u64, // governance config the proposal was created under
+    pub title: String,
+    pub description_uri: String,     // off-chain description
+    pub description_hash: [u8; 32],  // SHA-256 of the off-chain description
+    pub executed_count: u32,         // list batch entries applied so far
     pub exists: bool,
     pub bump: u8,
 }
 
 impl PendingTransaction {
-    pub const MAX_DATA: usize = 256;
+    pub const MAX_DATA: usize = 8192; // ~240 list batch entries
+    pub const BATCH_CHUNK: usize = 20; // list batch entries applied per execution call
     pub const MAX_REASON: usize = 256;
-    pub const MAX_APPROVALS: usize = 10;
+    pub const MAX_TITLE: usize = 64;
+    pub const MAX_DESCRIPTION_URI: usize = 200;
     
-    pub const LEN: usize = 8 + // discriminator
+    // Size with empty data, rejection_reason, title and description_uri
+    pub const BASE_LEN: usize = 8 + // discriminator
         8 + // id
         1 + // tx_type
         1 + // status
         32 + // initiator
         32 + // target
         8 + // amount
-        4 + Self::MAX_DATA + // data
+        4 + // data (length prefix)
         8 + // timestamp
         8 + // execute_after
         1 + // approval_count
-        4 + (32 * Self::MAX_APPROVALS) + // approvals
-        4 + Self::MAX_REASON + // rejection_reason
+        4 + // rejection_reason (length prefix)
         32 + // rejector
+        8 + // rejection_rent
+        8 + // config_nonce
+        4 + // title (length prefix)
+        4 + // description_uri (length prefix)
+        32 + // description_hash
+        4 + // executed_count
         1 + // exists
         1; // bump
+    
+    // Space for this proposal sized to its current payload
+    pub fn space(&self) -> usize {
+        Self::BASE_LEN +
+            self.data.len() +
+            self.rejection_reason.len() +
+            self.title.len() +
+            self.description_uri.len()
+    }
+    
+    // SHA-256 of what the proposal does and says (not its votes or
+    // progress) - offline signers commit to it through expected_hash
+    pub fn content_hash(&self) -> [u8; 32] {
+        let tx_type = [self.tx_type as u8];
+        let data_len = (self.data.len() as u32).to_le_bytes();
+        let title_len = (self.title.len() as u32).to_le_bytes();
+        let uri_len = (self.description_uri.len() as u32).to_le_bytes();
+        hashv(&[
+            &self.id.to_le_bytes(),
+            &tx_type,
+            self.initiator.as_ref(),
+            self.target.as_ref(),
+            &self.amount.to_le_bytes(),
+            &data_len,
+            &self.data,
+            &title_len,
+            self.title.as_bytes(),
+            &uri_len,
+            self.description_uri.as_bytes(),
+            &self.description_hash,
+        ])
+        .to_bytes()
+    }
+}
+
+// Human-readable context supplied when queueing a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct ProposalMetadata {
+    pub title: String,
+    pub description_uri: String,
+    pub description_hash: [u8; 32], // SHA-256 of the document at description_uri
+}
+
+// One per (proposal, owner) - existence prevents double-voting
+#[account]
+pub struct ApprovalRecord {
+    pub proposal: Pubkey,
+    pub owner: Pubkey,
+    pub config_nonce: u64, // governance config the approval was given under
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+impl ApprovalRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // proposal
+        32 + // owner
+        8 + // config_nonce
+        8 + // timestamp
+        1; // bump
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
 pub enum TransactionType {
     Unpause,
     Blacklist,
@@ -567,14 +1420,74 @@ pub enum TransactionType {
     Pair,
     RoleGrant,
     RoleRevoke,
+    ExtendPause,
+    ModulePause,
+    Freeze,
+    ListBatch,
+    MerkleRoot,
+    ModuleAddress,
+}
+
+// Proposal data for TransactionType::ListBatch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ListBatch {
+    pub list: AddressList,
+    pub details: BlacklistDetails, // Blacklist only
+    pub updates: Vec<ListUpdate>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+// Queued batches are read and extended in place - decoding all of a full
+// batch at once would take a third of the program heap
+impl ListBatch {
+    const COUNT_AT: usize = 1 + BlacklistDetails::LEN; // after list and details
+    const ENTRIES_AT: usize = Self::COUNT_AT + 4;
+    
+    // Helper: list, details and entry count of encoded batch data
+    pub fn header(data: &[u8]) -> Result<(AddressList, BlacklistDetails, usize)> {
+        let (list, details, count) =
+            <(AddressList, BlacklistDetails, u32)>::deserialize(&mut &data[..])?;
+        Ok((list, details, count as usize))
+    }
+    
+    // Helper: entries start..end of encoded batch data
+    pub fn entries(data: &[u8], start: usize, end: usize) -> Result<Vec<ListUpdate>> {
+        let bytes = data
+            .get(Self::ENTRIES_AT + start * ListUpdate::LEN..Self::ENTRIES_AT + end * ListUpdate::LEN)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        bytes
+            .chunks(ListUpdate::LEN)
+            .map(|entry| Ok(ListUpdate::try_from_slice(entry)?))
+            .collect()
+    }
+    
+    // Helper: add entries to encoded batch data, returning the new count
+    pub fn append(data: &mut Vec<u8>, updates: &[ListUpdate]) -> Result<usize> {
+        let (_, _, count) = Self::header(data)?;
+        let total = count + updates.len();
+        data[Self::COUNT_AT..Self::ENTRIES_AT].copy_from_slice(&(total as u32).to_le_bytes());
+        data.reserve_exact(updates.len() * ListUpdate::LEN);
+        for update in updates {
+            update.serialize(data)?;
+        }
+        Ok(total)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
 pub enum TransactionStatus {
     Pending,
     Rejected,
     Executed,
     AutoExecuted,
+    CrankExecuted,
+}
+
+// How a proposal reached execution
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ExecutionMode {
+    Manual, // execute_transaction by a signer
+    Auto,   // final approval landed after the cooldown
+    Crank,  // permissionless crank_execute
 }
 
 // Context Structures
@@ -593,14 +1506,27 @@ pub struct InitializeGovernance<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program: Program<'info, crate::program::MultisigGovernance>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct AdminOnly<'info> {
     #[account(
+        mut,
         seeds = [b"governance"],
-        bump = governance.bump
+        bump = governance.bump,
+        constraint = governance.admin == authority.key() @ ErrorCode::Unauthorized
     )]
     pub governance: Account<'info, Governance>,
     
@@ -618,6 +1544,7 @@ pub struct SignerOnly<'info> {
     pub authority: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct QueueTransaction<'info> {
     #[account(
@@ -630,17 +1557,176 @@ pub struct QueueTransaction<'info> {
     #[account(
         init,
         payer = signer,
-        space = 8 + PendingTransaction::LEN,
+        space = PendingTransaction::BASE_LEN,
         seeds = [b"proposal", governance.next_transaction_id.to_le_bytes().as_ref()],
         bump
     )]
     pub proposal: Account<'info, PendingTransaction>,
     
+    #[account(mut)]
     pub signer: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(tx_id: u64)]
+pub struct ApproveTransaction<'info> {
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", tx_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, PendingTransaction>,
+    
+    #[account(
+        init,
+        payer = approver,
+        space = ApprovalRecord::LEN,
+        seeds = [b"approval", proposal.key().as_ref(), approver.key().as_ref()],
+        bump
+    )]
+    pub approval: Account<'info, ApprovalRecord>,
+    
+    #[account(
+        address = governance.nc_token @ ErrorCode::InvalidTokenProgram
+    )]
+    pub nc_token_program: Program<'info, NcToken>,
+    
+    /// CHECK: NC Token state (pinned to the PDA recorded by set_token)
+    #[account(
+        mut,
+        address = governance.nc_token_state @ ErrorCode::InvalidTokenState
+    )]
+    pub nc_token_state: UncheckedAccount<'info>,
+    
+    /// CHECK: nc_token's event authority PDA, for the events it emits
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = nc_token_program.key()
+    )]
+    pub nc_token_event_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub approver: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(tx_id: u64)]
+pub struct ReapproveTransaction<'info> {
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", tx_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, PendingTransaction>,
+    
+    #[account(
+        mut,
+        seeds = [b"approval", proposal.key().as_ref(), approver.key().as_ref()],
+        bump = approval.bump
+    )]
+    pub approval: Account<'info, ApprovalRecord>,
+    
+    pub approver: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(tx_id: u64)]
+pub struct RejectTransaction<'info> {
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", tx_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, PendingTransaction>,
+    
+    #[account(mut)]
+    pub approver: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(tx_id: u64)]
+pub struct AppendListBatch<'info> {
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", tx_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = initiator @ ErrorCode::Unauthorized
+    )]
+    pub proposal: Account<'info, PendingTransaction>,
+    
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(tx_id: u64)]
+pub struct CloseProposal<'info> {
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", tx_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = initiator @ ErrorCode::Unauthorized
+    )]
+    pub proposal: Account<'info, PendingTransaction>,
+    
+    #[account(mut)]
+    pub initiator: SystemAccount<'info>,
+    
+    /// CHECK: Receives the rejector's share of the rent (required if it paid any)
+    #[account(
+        mut,
+        address = proposal.rejector @ ErrorCode::Unauthorized
+    )]
+    pub rejector: Option<UncheckedAccount<'info>>,
+    
+    pub closer: Signer<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(tx_id: u64)]
 pub struct CanApprove<'info> {
@@ -657,49 +1743,130 @@ pub struct CanApprove<'info> {
     )]
     pub proposal: Account<'info, PendingTransaction>,
     
-    /// CHECK: NC Token program
-    pub nc_token_program: UncheckedAccount<'info>,
+    #[account(
+        address = governance.nc_token @ ErrorCode::InvalidTokenProgram
+    )]
+    pub nc_token_program: Program<'info, NcToken>,
     
-    /// CHECK: NC Token state
+    /// CHECK: NC Token state (pinned to the PDA recorded by set_token)
+    #[account(
+        mut,
+        address = governance.nc_token_state @ ErrorCode::InvalidTokenState
+    )]
     pub nc_token_state: UncheckedAccount<'info>,
     
+    /// CHECK: nc_token's event authority PDA, for the events it emits
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = nc_token_program.key()
+    )]
+    pub nc_token_event_authority: UncheckedAccount<'info>,
+    
     pub approver: Signer<'info>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(tx_id: u64)]
+pub struct CrankExecute<'info> {
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", tx_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, PendingTransaction>,
+    
+    #[account(
+        address = governance.nc_token @ ErrorCode::InvalidTokenProgram
+    )]
+    pub nc_token_program: Program<'info, NcToken>,
+    
+    /// CHECK: NC Token state (pinned to the PDA recorded by set_token)
+    #[account(
+        mut,
+        address = governance.nc_token_state @ ErrorCode::InvalidTokenState
+    )]
+    pub nc_token_state: UncheckedAccount<'info>,
+    
+    /// CHECK: nc_token's event authority PDA, for the events it emits
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = nc_token_program.key()
+    )]
+    pub nc_token_event_authority: UncheckedAccount<'info>,
+    
+    // Anyone may crank - no approval rights required
+    pub cranker: Signer<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct EmergencyPauseContext<'info> {
     #[account(
+        mut,
         seeds = [b"governance"],
         bump = governance.bump
     )]
     pub governance: Account<'info, Governance>,
     
-    /// CHECK: NC Token program
-    pub nc_token_program: UncheckedAccount<'info>,
+    #[account(
+        address = governance.nc_token @ ErrorCode::InvalidTokenProgram
+    )]
+    pub nc_token_program: Program<'info, NcToken>,
     
-    /// CHECK: NC Token state
+    /// CHECK: NC Token state (pinned to the PDA recorded by set_token)
+    #[account(
+        mut,
+        address = governance.nc_token_state @ ErrorCode::InvalidTokenState
+    )]
     pub nc_token_state: UncheckedAccount<'info>,
     
+    /// CHECK: nc_token's event authority PDA, for the events it emits
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = nc_token_program.key()
+    )]
+    pub nc_token_event_authority: UncheckedAccount<'info>,
+    
+    // Guardian - co-signing guardians follow as remaining accounts
     pub authority: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
-// Events
+// Events (emitted through event CPI, so indexers read them from inner
+// instructions)
 #[event]
+#[derive(Debug)]
 pub struct TransactionQueued {
     pub tx_id: u64,
     pub tx_type: TransactionType,
     pub initiator: Pubkey,
+    pub config_nonce: u64,
+    pub title: String,
+    pub description_uri: String,
+    pub description_hash: [u8; 32],
 }
 
 #[event]
+#[derive(Debug)]
 pub struct TransactionApproved {
     pub tx_id: u64,
     pub approver: Pubkey,
+    pub config_nonce: u64,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct TransactionRejected {
     pub tx_id: u64,
     pub approver: Pubkey,
@@ -707,51 +1874,162 @@ pub struct TransactionRejected {
 }
 
 #[event]
+#[derive(Debug)]
+pub struct ProposalClosed {
+    pub tx_id: u64,
+    pub initiator: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+#[derive(Debug)]
 pub struct TransactionExecuted {
     pub tx_id: u64,
     pub auto_executed: bool,
+    pub mode: ExecutionMode,
+    pub executor: Pubkey,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct AddressBlacklisted {
     pub account: Pubkey,
     pub status: bool,
+    pub reason_code: u16,
+    pub evidence_hash: [u8; 32],
+    pub proposed_by: Pubkey,
+    pub expires_at: i64,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct TokenContractUpdated {
     pub new_token_contract: Pubkey,
+    pub state: Pubkey,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct RequiredApprovalsChanged {
     pub old_value: u8,
     pub new_value: u8,
+    pub config_nonce: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct OwnerAdded {
+    pub owner: Pubkey,
+    pub config_nonce: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct OwnerRemoved {
+    pub owner: Pubkey,
+    pub config_nonce: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct RequireReapprovalSet {
+    pub value: bool,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct NoSellLimitSet {
     pub account: Pubkey,
     pub status: bool,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct AddressRestricted {
     pub account: Pubkey,
     pub status: bool,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct PairSet {
     pub account: Pubkey,
     pub status: bool,
 }
 
 #[event]
-pub struct EmergencyPause {}
+#[derive(Debug)]
+pub struct EmergencyPause {
+    pub guardians: Vec<Pubkey>,
+    pub reason: String,
+    pub expires_at: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct EmergencyPauseExtended {
+    pub reason: String,
+    pub expires_at: i64, // 0 = until unpaused
+}
 
 #[event]
-pub struct EmergencyUnpause {}
+#[derive(Debug)]
+pub struct EmergencyUnpause {
+    pub reason: String,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct TokenAccountFreezeSet {
+    pub token_account: Pubkey,
+    pub frozen: bool,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ListBatchAppended {
+    pub tx_id: u64,
+    pub added: u32,
+    pub total: u32,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ListBatchChunkExecuted {
+    pub tx_id: u64,
+    pub list: AddressList,
+    pub from: u32,
+    pub to: u32,
+    pub total: u32,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ModuleAddressSet {
+    pub module: ProtocolModule,
+    pub address: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct MerkleRootUpdated {
+    pub list: AddressList,
+    pub root: [u8; 32],
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ModulePauseSet {
+    pub module: PauseModule,
+    pub paused: bool,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct GuardiansChanged {
+    pub guardians: Vec<Pubkey>,
+    pub quorum: u8,
+}
 
 // Error Codes
 #[error_code]
@@ -784,5 +2062,67 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Transaction ID mismatch")]
     TransactionIdMismatch,
+    #[msg("Not an owner")]
+    NotOwner,
+    #[msg("Owner already exists")]
+    OwnerAlreadyExists,
+    #[msg("Too many owners")]
+    TooManyOwners,
+    #[msg("Invalid approval record")]
+    InvalidApprovalRecord,
+    #[msg("Duplicate approval record")]
+    DuplicateApproval,
+    #[msg("Proposal data too large")]
+    DataTooLarge,
+    #[msg("Rejection reason too long")]
+    RejectionReasonTooLong,
+    #[msg("Transaction still pending")]
+    TransactionStillPending,
+    #[msg("Proposal title too long")]
+    TitleTooLong,
+    #[msg("Description URI too long")]
+    DescriptionUriTooLong,
+    #[msg("Not a guardian")]
+    NotGuardian,
+    #[msg("Insufficient guardian signatures")]
+    InsufficientGuardians,
+    #[msg("Too many guardians")]
+    TooManyGuardians,
+    #[msg("Duplicate guardian")]
+    DuplicateGuardian,
+    #[msg("Invalid guardian quorum")]
+    InvalidGuardianQuorum,
+    #[msg("Invalid pause duration")]
+    InvalidPauseDuration,
+    #[msg("Invalid expiry")]
+    InvalidExpiry,
+    #[msg("Required account missing from remaining accounts")]
+    MissingAccount,
+    #[msg("Batch has no entries")]
+    EmptyBatch,
+    #[msg("Batch already has approvals")]
+    BatchLocked,
+    #[msg("Batch execution in progress")]
+    BatchInProgress,
+    #[msg("Signer is not the program upgrade authority")]
+    NotUpgradeAuthority,
+    #[msg("NC Token program does not match the one recorded in governance")]
+    InvalidTokenProgram,
+    #[msg("NC Token state does not match the one recorded in governance")]
+    InvalidTokenState,
+    #[msg("Removing the owner would leave fewer owners than required approvals")]
+    TooFewOwners,
+    #[msg("Proposal was queued under an older config and must be re-approved")]
+    StaleProposal,
+    #[msg("Token account is not owned by the blacklisted address")]
+    TokenAccountOwnerMismatch,
+    #[msg("A pause lasting as long or longer is already in force")]
+    PauseAlreadyInForce,
+    #[msg("Guardian pauses may not run past max_pause_duration")]
+    PauseLimitExceeded,
+    #[msg("Proposal does not match the expected hash")]
+    ProposalMismatch,
+    #[msg("Guardians must wait out the cooldown after their last pause")]
+    GuardianPauseCooldown,
 }
 
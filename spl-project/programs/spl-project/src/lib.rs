@@ -1,238 +1,590 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, FreezeAccount, Mint, ThawAccount, Token, TokenAccount, Transfer};
+
+pub mod merkle;
+
+use merkle::{NonMembershipProof, TransferProofs, EMPTY_ROOT};
+use nc_event_cpi::EventCpi;
+use nc_rules::{Bucket, Denied, Listing, Module, Party, Window};
+
+pub use nc_rules;
 
 declare_id!("Gdcm1yXvSNjvLNWUdi7XfghXhatjrkWB8EHbtUpmPkUL");
 
-#[program]
-pub mod nc_token {
+// multisig_governance program id (keep in sync with its declare_id!)
+pub const GOVERNANCE_PROGRAM_ID: Pubkey = pubkey!("MuLtiSigGov11111111111111111111111111111111");
+
+// The governance PDA - the only key accepted as nc_token governance
+pub fn governance_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"governance"], &GOVERNANCE_PROGRAM_ID).0
+}
+
+// anchor 0.31's #[program] expansion calls the deprecated
+// AccountInfo::realloc in the IDL handlers it emits next to the program
+// module, so the program lives in a wrapper the allow is scoped to
+#[allow(deprecated)]
+mod program_module {
     use super::*;
 
-    // Initialize NC Token with governance, bridge, treasury, bond addresses
-    pub fn initialize(
-        ctx: Context<Initialize>,
-        governance: Pubkey,
-        bridge: Pubkey,
-        treasury: Pubkey,
-        bond: Pubkey,
-    ) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        state.governance = governance;
-        state.bridge = bridge;
-        state.treasury = treasury;
-        state.bond = bond;
-        state.emergency_paused = false;
-        state.bump = ctx.bumps.state;
-        
-        // Set no sell limit for bridge and treasury (as per Ethereum contract)
-        state.no_sell_limit.push(bridge);
-        state.no_sell_limit.push(treasury);
-        
-        msg!("NC Token initialized with governance: {}", governance);
-        Ok(())
-    }
+    #[program]
+    pub mod nc_token {
+        use super::*;
 
-    // Governance functions (only callable by governance program via CPI)
-    pub fn set_emergency_pause(ctx: Context<GovernanceOnly>, value: bool) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        state.emergency_paused = value;
-        emit!(EmergencyPauseSet { paused: value });
-        msg!("Emergency pause set to: {}", value);
-        Ok(())
-    }
+        // Initialize NC Token with governance, bridge, treasury, bond addresses
+        // and the NC mint (upgrade authority only; governance must be the
+        // multisig PDA)
+        pub fn initialize(
+            ctx: Context<Initialize>,
+            governance: Pubkey,
+            bridge: Pubkey,
+            treasury: Pubkey,
+            bond: Pubkey,
+            mint: Pubkey,
+        ) -> Result<()> {
+            require_keys_eq!(governance, governance_pda(), ErrorCode::InvalidGovernance);
+            let state = &mut ctx.accounts.state;
+            state.governance = governance;
+            state.bridge = bridge;
+            state.treasury = treasury;
+            state.bond = bond;
+            state.mint = mint;
+            state.emergency_paused = false;
+            state.pause_expires_at = 0;
+            state.paused_modules = 0;
+            state.bump = ctx.bumps.state;
+            state.blacklist_root = EMPTY_ROOT;
+            state.no_sell_limit_root = EMPTY_ROOT;
+            
+            // Set no sell limit for bridge and treasury (as per Ethereum contract)
+            state.no_sell_limit.push(bridge);
+            state.no_sell_limit.push(treasury);
+            
+            // The state starts at its current size and grows as governance
+            // fills the lists, which has no payer - prepay rent for all of LEN
+            let rent = Rent::get()?.minimum_balance(NCTokenState::LEN);
+            let top_up = rent.saturating_sub(state.to_account_info().lamports());
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: state.to_account_info(),
+                    },
+                ),
+                top_up,
+            )?;
+            fit_state(state)?;
+            
+            msg!("NC Token initialized with governance: {}", governance);
+            Ok(())
+        }
 
-    pub fn set_blacklist(ctx: Context<GovernanceOnly>, account: Pubkey, value: bool) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        if value {
-            if !state.blacklisted.contains(&account) {
-                state.blacklisted.push(account);
+        // Governance functions (only callable by governance program via CPI)
+        // expires_at: unix timestamp after which transfers auto-resume (0 = no expiry).
+        // A pause in force is never cut short by another pause - the later
+        // expiry wins; lifting it takes value = false
+        pub fn set_emergency_pause(
+            ctx: Context<GovernanceOnly>,
+            value: bool,
+            expires_at: i64,
+            reason: String,
+        ) -> Result<()> {
+            let state = &mut ctx.accounts.state;
+            let now_ts = Clock::get()?.unix_timestamp;
+            let keep = value &&
+                state.is_paused(now_ts) &&
+                (state.pause_expires_at == 0 ||
+                 (expires_at != 0 && expires_at < state.pause_expires_at));
+            if !keep {
+                state.pause_expires_at = if value { expires_at } else { 0 };
             }
-        } else {
-            state.blacklisted.retain(|&x| x != account);
+            state.emergency_paused = value;
+            emit_cpi!(EmergencyPauseSet {
+                module: PauseModule::Protocol,
+                paused: value,
+                expires_at: state.pause_expires_at,
+                reason,
+            });
+            msg!("Emergency pause set to: {} (expires at {})", value, state.pause_expires_at);
+            Ok(())
         }
-        emit!(BlacklistSet { account, value });
-        msg!("Blacklist set for {}: {}", account, value);
-        Ok(())
-    }
 
-    pub fn set_restricted(ctx: Context<GovernanceOnly>, account: Pubkey, value: bool) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        if value {
-            if !state.restricted_list.contains(&account) {
-                state.restricted_list.push(account);
+        // Per-module pause switch (no expiry - lifted through governance)
+        pub fn set_module_pause(
+            ctx: Context<GovernanceOnly>,
+            module: PauseModule,
+            value: bool,
+            reason: String,
+        ) -> Result<()> {
+            require!(module != PauseModule::Protocol, ErrorCode::InvalidPauseModule);
+            let state = &mut ctx.accounts.state;
+            if value {
+                state.paused_modules |= module.flag();
+            } else {
+                state.paused_modules &= !module.flag();
             }
-        } else {
-            state.restricted_list.retain(|&x| x != account);
+            emit_cpi!(EmergencyPauseSet {
+                module,
+                paused: value,
+                expires_at: 0,
+                reason,
+            });
+            msg!("Module pause set for {:?}: {}", module, value);
+            Ok(())
+        }
+
+        // Listing an address that is already blacklisted replaces its details
+        pub fn set_blacklist(
+            ctx: Context<GovernanceOnly>,
+            account: Pubkey,
+            value: bool,
+            details: BlacklistDetails,
+        ) -> Result<()> {
+            let now_ts = Clock::get()?.unix_timestamp;
+            let events = EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority);
+            apply_blacklist(&mut ctx.accounts.state, &events, account, value, details, now_ts)
+        }
+
+        pub fn set_restricted(ctx: Context<GovernanceOnly>, account: Pubkey, value: bool) -> Result<()> {
+            let events = EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority);
+            apply_restricted(&mut ctx.accounts.state, &events, account, value)
+        }
+
+        pub fn set_no_sell_limit(ctx: Context<GovernanceOnly>, account: Pubkey, value: bool) -> Result<()> {
+            let events = EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority);
+            apply_no_sell_limit(&mut ctx.accounts.state, &events, account, value)
+        }
+
+        pub fn set_liquidity_pool(ctx: Context<GovernanceOnly>, pool: Pubkey, value: bool) -> Result<()> {
+            let events = EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority);
+            apply_liquidity_pool(&mut ctx.accounts.state, &events, pool, value)
         }
-        emit!(RestrictedSet { account, value });
-        msg!("Restricted set for {}: {}", account, value);
-        Ok(())
-    }
 
-    pub fn set_no_sell_limit(ctx: Context<GovernanceOnly>, account: Pubkey, value: bool) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        if value {
-            if !state.no_sell_limit.contains(&account) {
-                state.no_sell_limit.push(account);
+        // Batched list changes - `details` is only used for the blacklist
+        pub fn set_list_batch(
+            ctx: Context<GovernanceOnly>,
+            list: AddressList,
+            updates: Vec<ListUpdate>,
+            details: BlacklistDetails,
+        ) -> Result<()> {
+            let state = &mut ctx.accounts.state;
+            let events = EventCpi::new(crate::ID, &ctx.accounts.event_authority, ctx.bumps.event_authority);
+            let now_ts = Clock::get()?.unix_timestamp;
+            for update in &updates {
+                match list {
+                    AddressList::Blacklist => {
+                        apply_blacklist(state, &events, update.account, update.value, details, now_ts)?
+                    }
+                    AddressList::Restricted => {
+                        apply_restricted(state, &events, update.account, update.value)?
+                    }
+                    AddressList::NoSellLimit => {
+                        apply_no_sell_limit(state, &events, update.account, update.value)?
+                    }
+                    AddressList::LiquidityPool => {
+                        apply_liquidity_pool(state, &events, update.account, update.value)?
+                    }
+                }
             }
-        } else {
-            state.no_sell_limit.retain(|&x| x != account);
+            msg!("Batch of {} updates applied to {:?}", updates.len(), list);
+            Ok(())
         }
-        msg!("No sell limit set for {}: {}", account, value);
-        Ok(())
-    }
 
-    pub fn set_liquidity_pool(ctx: Context<GovernanceOnly>, pool: Pubkey, value: bool) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        if value {
-            if !state.is_liquidity_pool.contains(&pool) {
-                state.is_liquidity_pool.push(pool);
+        // Rotate a module address, carrying its restricted / no-sell-limit
+        // entries over to the new address
+        pub fn set_module_address(
+            ctx: Context<GovernanceOnly>,
+            module: ProtocolModule,
+            new_address: Pubkey,
+        ) -> Result<()> {
+            require!(new_address != Pubkey::default(), ErrorCode::InvalidModuleAddress);
+            let state: &mut NCTokenState = &mut ctx.accounts.state;
+            // One address per module, or its exemptions would follow two roles
+            let others = match module {
+                ProtocolModule::Bridge => [state.treasury, state.bond],
+                ProtocolModule::Treasury => [state.bridge, state.bond],
+                ProtocolModule::Bond => [state.bridge, state.treasury],
+            };
+            require!(!others.contains(&new_address), ErrorCode::ModuleAddressInUse);
+            let slot = match module {
+                ProtocolModule::Bridge => &mut state.bridge,
+                ProtocolModule::Treasury => &mut state.treasury,
+                ProtocolModule::Bond => &mut state.bond,
+            };
+            let old_address = std::mem::replace(slot, new_address);
+            
+            for list in [&mut state.restricted_list, &mut state.no_sell_limit] {
+                if list.contains(&old_address) {
+                    list.retain(|&x| x != old_address && x != new_address);
+                    list.push(new_address);
+                }
             }
-        } else {
-            state.is_liquidity_pool.retain(|&x| x != pool);
+            
+            emit_cpi!(ModuleAddressChanged {
+                module,
+                old_address,
+                new_address,
+            });
+            msg!("{:?} address changed: {} -> {}", module, old_address, new_address);
+            Ok(())
         }
-        emit!(LiquidityPoolUpdated { pool, is_pool: value });
-        msg!("Liquidity pool set for {}: {}", pool, value);
-        Ok(())
-    }
 
-    // Transfer with all rules enforced (matches Ethereum _transfer logic)
-    pub fn transfer(ctx: Context<TransferNC>, amount: u64) -> Result<()> {
-        let state = &ctx.accounts.state;
-        let from_key = ctx.accounts.from_authority.key();
-        let to_key = ctx.accounts.to_account.key();
-        
-        // Check if from or to is restricted (Bridge, Bond, Treasury, etc.)
-        let from_restricted = state.restricted_list.contains(&from_key);
-        let to_restricted = state.restricted_list.contains(&to_key);
-        
-        // Emergency pause rules - only freezes protocol modules
-        // P2P transfers remain allowed unless blacklist applies
-        if state.emergency_paused {
-            if from_restricted || to_restricted || 
-               state.blacklisted.contains(&from_key) || 
-               state.blacklisted.contains(&to_key) {
-                return Err(ErrorCode::Paused.into());
+        // Merkle-root mode for large lists (EMPTY_ROOT disables it). Entries in
+        // the on-chain Vec still apply alongside the root.
+        pub fn set_merkle_root(
+            ctx: Context<GovernanceOnly>,
+            list: AddressList,
+            root: [u8; 32],
+        ) -> Result<()> {
+            let state = &mut ctx.accounts.state;
+            match list {
+                AddressList::Blacklist => state.blacklist_root = root,
+                AddressList::NoSellLimit => state.no_sell_limit_root = root,
+                _ => return Err(ErrorCode::InvalidMerkleList.into()),
             }
+            emit_cpi!(MerkleRootSet { list, root });
+            msg!("Merkle root set for {:?}", list);
+            Ok(())
         }
-        
-        // Blacklist rules - can ONLY block interactions WITH protocol modules
-        // Blacklisted users can still do normal P2P transfers
-        // Both from & to blacklisted are not allowed
-        if state.blacklisted.contains(&from_key) && to_restricted {
-            return Err(ErrorCode::Blacklisted.into());
+
+        // SPL-level freeze (the state PDA must be the mint's freeze authority)
+        pub fn freeze_account(ctx: Context<FreezeControl>) -> Result<()> {
+            let state_seeds: &[&[u8]] = &[b"nc_token_state", &[ctx.accounts.state.bump]];
+            token::freeze_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                FreezeAccount {
+                    account: ctx.accounts.token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.state.to_account_info(),
+                },
+                &[state_seeds],
+            ))?;
+            emit_cpi!(TokenAccountFrozen {
+                token_account: ctx.accounts.token_account.key(),
+                owner: ctx.accounts.token_account.owner,
+                frozen: true,
+            });
+            msg!("Token account frozen: {}", ctx.accounts.token_account.key());
+            Ok(())
         }
-        if state.blacklisted.contains(&to_key) && from_restricted {
-            return Err(ErrorCode::Blacklisted.into());
+
+        pub fn thaw_account(ctx: Context<FreezeControl>) -> Result<()> {
+            let state_seeds: &[&[u8]] = &[b"nc_token_state", &[ctx.accounts.state.bump]];
+            token::thaw_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                ThawAccount {
+                    account: ctx.accounts.token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.state.to_account_info(),
+                },
+                &[state_seeds],
+            ))?;
+            emit_cpi!(TokenAccountFrozen {
+                token_account: ctx.accounts.token_account.key(),
+                owner: ctx.accounts.token_account.owner,
+                frozen: false,
+            });
+            msg!("Token account thawed: {}", ctx.accounts.token_account.key());
+            Ok(())
         }
-        if state.blacklisted.contains(&from_key) && state.blacklisted.contains(&to_key) {
-            return Err(ErrorCode::Blacklisted.into());
+
+        // Transfer with all rules enforced (matches Ethereum _transfer logic)
+        pub fn transfer(ctx: Context<TransferNC>, amount: u64) -> Result<()> {
+            process_transfer(ctx, amount, &TransferProofs::default())
         }
-        
-        // Max sell limit rule - enforce ONLY when:
-        // - destination is LP
-        // - sender is NOT exempt (Bridge / Treasury / Governance-approved)
-        if state.is_liquidity_pool.contains(&to_key) &&
-           !state.no_sell_limit.contains(&from_key) &&
-           !from_restricted {
-            enforce_sell_limit(&ctx, from_key, amount)?;
+
+        // Transfer carrying Merkle proofs for the root-mode lists
+        pub fn transfer_with_proofs(
+            ctx: Context<TransferNC>,
+            amount: u64,
+            proofs: TransferProofs,
+        ) -> Result<()> {
+            process_transfer(ctx, amount, &proofs)
         }
-        
-        // Perform the transfer
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.from_account.to_account_info(),
-                    to: ctx.accounts.to_account.to_account_info(),
-                    authority: ctx.accounts.from_authority.to_account_info(),
-                },
-            ),
-            amount,
-        )?;
-        
-        emit!(TransferEvent {
-            from: from_key,
-            to: to_key,
-            amount,
-        });
-        
-        msg!("Transfer completed: {} tokens from {} to {}", amount, from_key, to_key);
-        Ok(())
-    }
 
-    // Helper function to enforce 5% sell limit per 24 hours (matches Ethereum _enforceSellLimit)
-    fn enforce_sell_limit(
-        ctx: &Context<TransferNC>,
-        seller: Pubkey,
-        amount: u64,
-    ) -> Result<()> {
-        let state = &ctx.accounts.state;
-        let from_account = &ctx.accounts.from_account;
-        let sell_window = &mut ctx.accounts.sell_window;
-        
-        let current_balance = from_account.amount;
-        let now_ts = Clock::get()?.unix_timestamp;
-        
-        // Start or reset rolling 24h window (true seconds-based)
-        if sell_window.start_time == 0 || now_ts - sell_window.start_time >= 86400 {
-            sell_window.start_time = now_ts;
-            sell_window.start_balance = current_balance;
-            // Clear old buckets
-            sell_window.buckets = [SellBucket::default(); 24];
+        // View functions
+        pub fn is_blacklisted(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
+            let state = &ctx.accounts.state;
+            Ok(state.is_blacklisted(&account, Clock::get()?.unix_timestamp))
+        }
+
+        // On the no-sell-limit list (see is_sell_limit_exempt for the exemption
+        // itself)
+        pub fn is_sell_limit(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
+            let state = &ctx.accounts.state;
+            Ok(state.no_sell_limit.contains(&account))
+        }
+
+        pub fn is_restricted(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
+            let state = &ctx.accounts.state;
+            Ok(state.restricted_list.contains(&account))
         }
         
-        // Calculate 5% limit (500 basis points / 10000)
-        let limit = sell_window.start_balance
-            .checked_mul(500)
-            .and_then(|x| x.checked_div(10000))
-            .ok_or(ErrorCode::MathOverflow)?;
+        pub fn is_liquidity_pool(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
+            let state = &ctx.accounts.state;
+            Ok(state.is_liquidity_pool.contains(&account))
+        }
         
-        // Calculate total sold in last 24 hours
-        let mut sold: u64 = 0;
-        let hour_start = (now_ts / 3600) * 3600; // Round to hour start (seconds)
+        // Whether sales by `account` (a wallet) skip the sell limit: the
+        // no-sell-limit list or a restricted module. A Merkle-root exemption only
+        // counts with its proof, in transfer_with_proofs
+        pub fn is_sell_limit_exempt(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
+            let state = &ctx.accounts.state;
+            let party = state.party(&account, None, Clock::get()?.unix_timestamp);
+            Ok(party.is_sell_limit_exempt())
+        }
         
-        for i in 0..24 {
-            let bucket = &sell_window.buckets[i];
-            if bucket.timestamp != 0 && now_ts - bucket.timestamp < 86400 {
-                sold = sold.checked_add(bucket.amount).ok_or(ErrorCode::MathOverflow)?;
-            }
+        // Everything in NCTokenState but the list contents
+        pub fn get_config(ctx: Context<ViewOnly>) -> Result<TokenConfig> {
+            let state = &ctx.accounts.state;
+            Ok(TokenConfig {
+                governance: state.governance,
+                bridge: state.bridge,
+                treasury: state.treasury,
+                bond: state.bond,
+                mint: state.mint,
+                emergency_paused: state.emergency_paused,
+                pause_expires_at: state.pause_expires_at,
+                pause_in_force: state.is_paused(Clock::get()?.unix_timestamp),
+                paused_modules: state.paused_modules,
+                blacklist_root: state.blacklist_root,
+                no_sell_limit_root: state.no_sell_limit_root,
+                blacklisted_count: state.blacklisted.len() as u32,
+                restricted_count: state.restricted_list.len() as u32,
+                no_sell_limit_count: state.no_sell_limit.len() as u32,
+                liquidity_pool_count: state.is_liquidity_pool.len() as u32,
+            })
         }
         
-        // Check if this transfer would exceed limit
-        let new_total = sold.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
-        require!(new_total <= limit, ErrorCode::SellLimitExceeded);
+        // Sell allowance of a wallet selling from token_account (read only)
+        pub fn get_sell_status(ctx: Context<SellStatusView>) -> Result<SellStatus> {
+            let window = read_sell_window(&ctx.accounts.sell_window)?;
+            let now_ts = Clock::get()?.unix_timestamp;
+            Ok(SellStatus::new(&window, ctx.accounts.token_account.amount, now_ts))
+        }
         
-        // Update current hour bucket
-        let hour_index = ((hour_start / 3600) % 24) as usize;
-        if sell_window.buckets[hour_index].timestamp != hour_start {
-            sell_window.buckets[hour_index] = SellBucket {
-                timestamp: hour_start,
-                amount: 0,
+        // Dry run of transfer / transfer_with_proofs for amount: the verdict,
+        // the rule that would block it and the sender's sell allowance. Reads
+        // the sender's SellWindow without creating or updating it
+        pub fn preview_transfer(
+            ctx: Context<PreviewTransfer>,
+            amount: u64,
+            proofs: TransferProofs,
+        ) -> Result<TransferPreview> {
+            let accounts = &ctx.accounts;
+            let state = &accounts.state;
+            let now_ts = Clock::get()?.unix_timestamp;
+            
+            let (from, to) = state.parties(
+                &accounts.from_authority.key(),
+                &accounts.to_account.key(),
+                &proofs,
+                now_ts,
+            );
+            let transfer = nc_rules::Transfer {
+                from,
+                to,
+                amount,
+                balance: accounts.from_account.amount,
+                now: now_ts,
             };
+            let window = read_sell_window(&accounts.sell_window)?;
+            let verdict = nc_rules::check(&state.rules(), &window, &transfer);
+            
+            // Allowance in the window this transfer would count against (a new
+            // one from the current balance if the old one has lapsed)
+            let window = window.current(now_ts, transfer.balance);
+            Ok(TransferPreview {
+                allowed: verdict.is_ok(),
+                blocked_by: verdict.err().map(TransferDenial::from),
+                sell_limited: transfer.is_sell_limited(),
+                remaining_allowance: window.remaining(now_ts),
+                resets_at: window.start_time + nc_rules::WINDOW_SECONDS,
+            })
         }
-        sell_window.buckets[hour_index].amount = sell_window.buckets[hour_index]
-            .amount
-            .checked_add(amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        Ok(())
     }
+}
 
-    // View functions
-    pub fn is_blacklisted(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
-        let state = &ctx.accounts.state;
-        Ok(state.blacklisted.contains(&account))
+pub use program_module::*;
+
+// Transfer rules shared by transfer and transfer_with_proofs (decided by
+// nc_rules::check, the same code clients use to predict the outcome)
+fn process_transfer(
+    mut ctx: Context<TransferNC>,
+    amount: u64,
+    proofs: &TransferProofs,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let state = &accounts.state;
+    let from_key = accounts.from_authority.key();
+    let to_key = accounts.to_account.key();
+    let now_ts = Clock::get()?.unix_timestamp;
+    
+    let (from, to) = state.parties(&from_key, &to_key, proofs, now_ts);
+    let transfer = nc_rules::Transfer {
+        from,
+        to,
+        amount,
+        balance: accounts.from_account.amount,
+        now: now_ts,
+    };
+    let mut window = accounts.sell_window.window();
+    let allowed = nc_rules::check(&state.rules(), &window, &transfer).map_err(|denied| {
+        msg!("Transfer denied: {}", denied);
+        ErrorCode::from(denied)
+    })?;
+    
+    // Max sell limit - count the sale in the sender's rolling 24h window
+    if allowed.sell_limited {
+        window.record(now_ts, transfer.balance, amount);
+        accounts.sell_window.set_window(&window);
     }
+    
+    // Perform the transfer
+    token::transfer(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.from_account.to_account_info(),
+                to: accounts.to_account.to_account_info(),
+                authority: accounts.from_authority.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    
+    emit_cpi!(TransferEvent {
+        from: from_key,
+        to: to_key,
+        amount,
+    });
+    
+    msg!("Transfer completed: {} tokens from {} to {}", amount, from_key, to_key);
+    Ok(())
+}
+
+// The sender's SellWindow, or an unopened one before its first limited sale
+fn read_sell_window(sell_window: &AccountInfo) -> Result<Window> {
+    if sell_window.owner != &crate::ID || sell_window.data_is_empty() {
+        return Ok(Window::default());
+    }
+    let data = sell_window.try_borrow_data()?;
+    Ok(SellWindow::try_deserialize(&mut &data[..])?.window())
+}
+
+// List helpers shared by the single and batched governance setters. Each
+// grows the state account to fit the list it changed.
+
+fn apply_blacklist(
+    state: &mut Account<NCTokenState>,
+    events: &EventCpi,
+    account: Pubkey,
+    value: bool,
+    details: BlacklistDetails,
+    now_ts: i64,
+) -> Result<()> {
+    // Drop lapsed entries and any existing entry for this account
+    state.blacklisted.retain(|e| e.account != account && !e.details.is_expired(now_ts));
+    if value {
+        require!(
+            state.blacklisted.len() < NCTokenState::MAX_BLACKLIST,
+            ErrorCode::ListFull
+        );
+        state.blacklisted.push(BlacklistEntry { account, details });
+    }
+    fit_state(state)?;
+    events.emit(&BlacklistSet {
+        account,
+        value,
+        reason_code: details.reason_code,
+        evidence_hash: details.evidence_hash,
+        proposed_by: details.proposed_by,
+        expires_at: details.expires_at,
+    })?;
+    msg!("Blacklist set for {}: {}", account, value);
+    Ok(())
+}
+
+fn apply_restricted(
+    state: &mut Account<NCTokenState>,
+    events: &EventCpi,
+    account: Pubkey,
+    value: bool,
+) -> Result<()> {
+    if value {
+        if !state.restricted_list.contains(&account) {
+            require!(
+                state.restricted_list.len() < NCTokenState::MAX_RESTRICTED,
+                ErrorCode::ListFull
+            );
+            state.restricted_list.push(account);
+        }
+    } else {
+        state.restricted_list.retain(|&x| x != account);
+    }
+    fit_state(state)?;
+    events.emit(&RestrictedSet { account, value })?;
+    msg!("Restricted set for {}: {}", account, value);
+    Ok(())
+}
+
+fn apply_no_sell_limit(
+    state: &mut Account<NCTokenState>,
+    events: &EventCpi,
+    account: Pubkey,
+    value: bool,
+) -> Result<()> {
+    if value {
+        if !state.no_sell_limit.contains(&account) {
+            require!(
+                state.no_sell_limit.len() < NCTokenState::MAX_NO_SELL_LIMIT,
+                ErrorCode::ListFull
+            );
+            state.no_sell_limit.push(account);
+        }
+    } else {
+        state.no_sell_limit.retain(|&x| x != account);
+    }
+    fit_state(state)?;
+    events.emit(&NoSellLimitSet { account, value })?;
+    msg!("No sell limit set for {}: {}", account, value);
+    Ok(())
+}
 
-    pub fn is_sell_limit(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
-        let state = &ctx.accounts.state;
-        Ok(state.no_sell_limit.contains(&account))
+fn apply_liquidity_pool(
+    state: &mut Account<NCTokenState>,
+    events: &EventCpi,
+    pool: Pubkey,
+    value: bool,
+) -> Result<()> {
+    if value {
+        if !state.is_liquidity_pool.contains(&pool) {
+            require!(
+                state.is_liquidity_pool.len() < NCTokenState::MAX_LIQUIDITY_POOLS,
+                ErrorCode::ListFull
+            );
+            state.is_liquidity_pool.push(pool);
+        }
+    } else {
+        state.is_liquidity_pool.retain(|&x| x != pool);
     }
+    fit_state(state)?;
+    events.emit(&LiquidityPoolUpdated { pool, is_pool: value })?;
+    msg!("Liquidity pool set for {}: {}", pool, value);
+    Ok(())
+}
 
-    pub fn is_restricted(ctx: Context<ViewOnly>, account: Pubkey) -> Result<bool> {
-        let state = &ctx.accounts.state;
-        Ok(state.restricted_list.contains(&account))
+// Grow the state account to its serialized size before anchor writes it
+// back (never shrinks). Rent for LEN is prepaid, and the lists only grow by
+// a batch at a time - well within MAX_PERMITTED_DATA_INCREASE.
+fn fit_state(state: &Account<NCTokenState>) -> Result<()> {
+    let info = state.to_account_info();
+    let space = state.space();
+    if space > info.data_len() {
+        info.resize(space)?;
     }
+    Ok(())
 }
 
 // Account Structures
@@ -243,32 +595,218 @@ pub struct NCTokenState {
     pub bridge: Pubkey,
     pub treasury: Pubkey,
     pub bond: Pubkey,
+    pub mint: Pubkey, // the NC mint - the only one freeze/thaw act on
     pub emergency_paused: bool,
+    pub pause_expires_at: i64, // auto-resume time for emergency_paused (0 = no expiry)
+    pub paused_modules: u8,    // PauseModule::flag() bits
     pub bump: u8,
+    pub blacklist_root: [u8; 32],     // Merkle root of extra blacklist entries (EMPTY_ROOT = off)
+    pub no_sell_limit_root: [u8; 32], // Merkle root of extra sell-limit exemptions (EMPTY_ROOT = off)
     // Using Vec for storage (matches Ethereum mapping behavior)
-    pub blacklisted: Vec<Pubkey>,        // Max 1000
-    pub restricted_list: Vec<Pubkey>,    // Max 100
-    pub no_sell_limit: Vec<Pubkey>,      // Max 100
-    pub is_liquidity_pool: Vec<Pubkey>,  // Max 100
+    pub blacklisted: Vec<BlacklistEntry>, // Max 32
+    pub restricted_list: Vec<Pubkey>,    // Max 64
+    pub no_sell_limit: Vec<Pubkey>,      // Max 64
+    pub is_liquidity_pool: Vec<Pubkey>,  // Max 64
 }
 
 impl NCTokenState {
-    pub const MAX_BLACKLIST: usize = 1000;
-    pub const MAX_RESTRICTED: usize = 100;
-    pub const MAX_NO_SELL_LIMIT: usize = 100;
-    pub const MAX_LIQUIDITY_POOLS: usize = 100;
+    // Every instruction deserializes the whole state onto the 32 KiB program
+    // heap - caps sized so full lists still leave room for a list batch
+    // chunk; longer lists go behind the Merkle roots
+    pub const MAX_BLACKLIST: usize = 32;
+    pub const MAX_RESTRICTED: usize = 64;
+    pub const MAX_NO_SELL_LIMIT: usize = 64;
+    pub const MAX_LIQUIDITY_POOLS: usize = 64;
     
+    // Size with every list empty - the account is created at this size (plus
+    // the entries initialize adds) and grows with the lists
+    pub const BASE_LEN: usize = Self::LEN -
+        BlacklistEntry::LEN * Self::MAX_BLACKLIST -
+        32 * (Self::MAX_RESTRICTED + Self::MAX_NO_SELL_LIMIT + Self::MAX_LIQUIDITY_POOLS);
+    
+    // Size with every list full (rent is prepaid for this much)
     pub const LEN: usize = 8 + // discriminator
         32 + // governance
         32 + // bridge
         32 + // treasury
         32 + // bond
+        32 + // mint
         1 +  // emergency_paused
+        8 +  // pause_expires_at
+        1 +  // paused_modules
         1 +  // bump
-        4 + (32 * Self::MAX_BLACKLIST) + // blacklisted vec
+        32 + // blacklist_root
+        32 + // no_sell_limit_root
+        4 + (BlacklistEntry::LEN * Self::MAX_BLACKLIST) + // blacklisted vec
         4 + (32 * Self::MAX_RESTRICTED) + // restricted_list vec
         4 + (32 * Self::MAX_NO_SELL_LIMIT) + // no_sell_limit vec
         4 + (32 * Self::MAX_LIQUIDITY_POOLS); // is_liquidity_pool vec
+    
+    // Size of this state as serialized
+    pub fn space(&self) -> usize {
+        Self::BASE_LEN +
+            BlacklistEntry::LEN * self.blacklisted.len() +
+            32 * (self.restricted_list.len() + self.no_sell_limit.len() + self.is_liquidity_pool.len())
+    }
+    
+    // Pause switches as the transfer rules see them
+    pub fn rules(&self) -> nc_rules::State {
+        nc_rules::State {
+            emergency_paused: self.emergency_paused,
+            pause_expires_at: self.pause_expires_at,
+            paused_modules: self.paused_modules,
+        }
+    }
+    
+    // Emergency pause in force (lapses once pause_expires_at has passed)
+    pub fn is_paused(&self, now_ts: i64) -> bool {
+        self.rules().is_paused(now_ts)
+    }
+    
+    pub fn is_blacklisted(&self, account: &Pubkey, now_ts: i64) -> bool {
+        self.blacklisted
+            .iter()
+            .any(|e| e.account == *account && !e.details.is_expired(now_ts))
+    }
+    
+    pub fn is_module_paused(&self, module: PauseModule) -> bool {
+        self.paused_modules & module.flag() != 0
+    }
+    
+    // Sender (by wallet) and recipient (by token account) of a transfer as
+    // the rules see them, with any Merkle proofs applied
+    pub fn parties(
+        &self,
+        from_key: &Pubkey,
+        to_key: &Pubkey,
+        proofs: &TransferProofs,
+        now_ts: i64,
+    ) -> (Party, Party) {
+        let mut from = self.party(from_key, proofs.from_not_blacklisted.as_ref(), now_ts);
+        from.sell_exempt |= proofs.from_sell_exempt
+            .as_ref()
+            .is_some_and(|p| p.verify(&self.no_sell_limit_root, from_key));
+        let to = self.party(to_key, proofs.to_not_blacklisted.as_ref(), now_ts);
+        (from, to)
+    }
+    
+    // One address as the rules see it; `not_blacklisted` clears it of the
+    // Merkle blacklist
+    pub fn party(&self, key: &Pubkey, not_blacklisted: Option<&NonMembershipProof>, now_ts: i64) -> Party {
+        // Blacklist entries past their expiry are treated as lifted; with a
+        // Merkle root set, a party is unproven until it shows it is absent
+        let blacklisted = if self.is_blacklisted(key, now_ts) {
+            Listing::Listed
+        } else if self.blacklist_root != EMPTY_ROOT &&
+            !not_blacklisted.is_some_and(|p| p.verify(&self.blacklist_root, key))
+        {
+            Listing::Unproven
+        } else {
+            Listing::Clear
+        };
+        let module = [
+            (self.bridge, Module::Bridge),
+            (self.bond, Module::Bond),
+            (self.treasury, Module::Treasury),
+        ]
+        .into_iter()
+        .find(|(address, _)| address == key)
+        .map(|(_, module)| module);
+        
+        Party {
+            restricted: self.restricted_list.contains(key),
+            blacklisted,
+            liquidity_pool: self.is_liquidity_pool.contains(key),
+            module,
+            sell_exempt: self.no_sell_limit.contains(key),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BlacklistEntry {
+    pub account: Pubkey,
+    pub details: BlacklistDetails,
+}
+
+impl BlacklistEntry {
+    pub const LEN: usize = 32 + BlacklistDetails::LEN;
+}
+
+// Compliance record kept with each blacklist entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct BlacklistDetails {
+    pub reason_code: u16,
+    pub evidence_hash: [u8; 32], // hash of the off-chain evidence file
+    pub proposed_by: Pubkey,     // governance proposal initiator
+    pub expires_at: i64,         // lifted automatically after this (0 = no expiry)
+}
+
+impl BlacklistDetails {
+    pub const LEN: usize = 2 + 32 + 32 + 8;
+    
+    pub fn is_expired(&self, now_ts: i64) -> bool {
+        self.expires_at != 0 && now_ts >= self.expires_at
+    }
+}
+
+// Governance-managed address lists
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AddressList {
+    Blacklist,
+    Restricted,
+    NoSellLimit,
+    LiquidityPool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct ListUpdate {
+    pub account: Pubkey,
+    pub value: bool,
+}
+
+impl ListUpdate {
+    pub const LEN: usize = 32 + 1;
+}
+
+// Pause switch identifiers (Protocol = the emergency pause over restricted
+// and blacklisted addresses)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PauseModule {
+    Protocol,
+    Global,
+    Bridge,
+    Bond,
+    Treasury,
+    LpTrading,
+    P2p,
+}
+
+impl PauseModule {
+    pub fn flag(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+impl From<Module> for PauseModule {
+    fn from(module: Module) -> Self {
+        match module {
+            Module::Global => PauseModule::Global,
+            Module::Bridge => PauseModule::Bridge,
+            Module::Bond => PauseModule::Bond,
+            Module::Treasury => PauseModule::Treasury,
+            Module::LpTrading => PauseModule::LpTrading,
+            Module::P2p => PauseModule::P2p,
+        }
+    }
+}
+
+// Module addresses that governance can rotate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProtocolModule {
+    Bridge,
+    Treasury,
+    Bond,
 }
 
 #[account]
@@ -291,6 +829,128 @@ impl SellWindow {
         8 + // start_balance
         (8 + 8) * 24 + // buckets (timestamp + amount) * 24
         1; // bump
+    
+    pub fn window(&self) -> Window {
+        Window {
+            start_time: self.start_time,
+            start_balance: self.start_balance,
+            buckets: self.buckets.map(|b| Bucket {
+                timestamp: b.timestamp,
+                amount: b.amount,
+            }),
+        }
+    }
+    
+    pub fn set_window(&mut self, window: &Window) {
+        self.start_time = window.start_time;
+        self.start_balance = window.start_balance;
+        self.buckets = window.buckets.map(|b| SellBucket {
+            timestamp: b.timestamp,
+            amount: b.amount,
+        });
+    }
+}
+
+// Result of preview_transfer (return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TransferPreview {
+    pub allowed: bool,
+    pub blocked_by: Option<TransferDenial>, // None when allowed
+    pub sell_limited: bool,                 // the transfer counts against the sell limit
+    pub remaining_allowance: u64,           // sell allowance left in the sender's window
+    pub resets_at: i64,                     // when that window lapses and the full 5% returns
+}
+
+// Result of get_config (return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TokenConfig {
+    pub governance: Pubkey,
+    pub bridge: Pubkey,
+    pub treasury: Pubkey,
+    pub bond: Pubkey,
+    pub mint: Pubkey,
+    pub emergency_paused: bool,
+    pub pause_expires_at: i64,
+    pub pause_in_force: bool, // emergency pause set and not yet expired
+    pub paused_modules: u8,   // PauseModule::flag() bits
+    pub blacklist_root: [u8; 32],
+    pub no_sell_limit_root: [u8; 32],
+    pub blacklisted_count: u32, // entries stored, lapsed ones included until pruned
+    pub restricted_count: u32,
+    pub no_sell_limit_count: u32,
+    pub liquidity_pool_count: u32,
+}
+
+// Result of get_sell_status (return data) - the window the wallet's next
+// LP sale counts against, as transfer enforces it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct SellStatus {
+    pub window_start: i64,          // now if the stored window has lapsed
+    pub baseline: u64,              // balance at window start (5% of it may be sold)
+    pub sold: u64,                  // in the last 24h of hour buckets
+    pub remaining: u64,
+    pub resets_at: i64,             // window lapses; the next sale opens a new one
+    pub buckets: Vec<BucketStatus>, // buckets still counting, oldest first
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BucketStatus {
+    pub hour_start: i64,
+    pub amount: u64,
+    pub expires_at: i64, // stops counting against the limit
+}
+
+impl SellStatus {
+    // `balance` is the sending token account's (baseline of a new window)
+    pub fn new(window: &Window, balance: u64, now_ts: i64) -> Self {
+        let window = window.current(now_ts, balance);
+        let mut buckets: Vec<BucketStatus> = window
+            .buckets
+            .iter()
+            .filter(|b| b.counts_at(now_ts))
+            .map(|b| BucketStatus {
+                hour_start: b.timestamp,
+                amount: b.amount,
+                expires_at: b.expires_at(),
+            })
+            .collect();
+        buckets.sort_by_key(|b| b.hour_start);
+        
+        SellStatus {
+            window_start: window.start_time,
+            baseline: window.start_balance,
+            sold: window.sold(now_ts),
+            remaining: window.remaining(now_ts),
+            resets_at: window.start_time + nc_rules::WINDOW_SECONDS,
+            buckets,
+        }
+    }
+}
+
+// Rule that blocks a transfer (nc_rules::Denied)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransferDenial {
+    ModulePaused { module: PauseModule },
+    EmergencyPaused,
+    BlacklistedSender,
+    BlacklistedRecipient,
+    BlacklistedPair,
+    SellLimitExceeded,
+}
+
+impl From<Denied> for TransferDenial {
+    fn from(denied: Denied) -> Self {
+        match denied {
+            Denied::ModulePaused(module) => TransferDenial::ModulePaused {
+                module: module.into(),
+            },
+            Denied::EmergencyPaused => TransferDenial::EmergencyPaused,
+            Denied::BlacklistedSender => TransferDenial::BlacklistedSender,
+            Denied::BlacklistedRecipient => TransferDenial::BlacklistedRecipient,
+            Denied::BlacklistedPair => TransferDenial::BlacklistedPair,
+            Denied::SellLimitExceeded { .. } => TransferDenial::SellLimitExceeded,
+        }
+    }
 }
 
 // Context Structures
@@ -300,7 +960,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + NCTokenState::LEN,
+        space = NCTokenState::BASE_LEN,
         seeds = [b"nc_token_state"],
         bump
     )]
@@ -309,12 +969,24 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program: Program<'info, crate::program::NcToken>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct GovernanceOnly<'info> {
     #[account(
+        mut,
         seeds = [b"nc_token_state"],
         bump = state.bump,
         constraint = state.governance == governance.key() @ ErrorCode::Unauthorized
@@ -325,6 +997,35 @@ pub struct GovernanceOnly<'info> {
     pub governance: Signer<'info>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FreezeControl<'info> {
+    #[account(
+        seeds = [b"nc_token_state"],
+        bump = state.bump,
+        constraint = state.governance == governance.key() @ ErrorCode::Unauthorized
+    )]
+    pub state: Account<'info, NCTokenState>,
+    
+    /// CHECK: Governance PDA (validated via constraint - must match state.governance)
+    pub governance: Signer<'info>,
+    
+    #[account(
+        address = state.mint @ ErrorCode::InvalidMint,
+        constraint = mint.freeze_authority == Some(state.key()).into() @ ErrorCode::NotFreezeAuthority
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        token::mint = mint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct TransferNC<'info> {
     #[account(
@@ -333,18 +1034,27 @@ pub struct TransferNC<'info> {
     )]
     pub state: Account<'info, NCTokenState>,
     
-    /// CHECK: Token mint
+    /// CHECK: Token mint (must be the NC mint)
+    #[account(address = state.mint @ ErrorCode::InvalidMint)]
     pub mint: UncheckedAccount<'info>,
     
-    /// CHECK: From token account
-    #[account(mut)]
+    // The signer's own account - a delegate would sell against its own
+    // window instead of the holder's
+    #[account(
+        mut,
+        token::mint = state.mint,
+        token::authority = from_authority
+    )]
     pub from_account: Account<'info, TokenAccount>,
     
-    /// CHECK: To token account
-    #[account(mut)]
+    #[account(
+        mut,
+        token::mint = state.mint
+    )]
     pub to_account: Account<'info, TokenAccount>,
     
     /// CHECK: From authority (signer)
+    #[account(mut)]
     pub from_authority: Signer<'info>,
     
     #[account(
@@ -369,31 +1079,129 @@ pub struct ViewOnly<'info> {
     pub state: Account<'info, NCTokenState>,
 }
 
-// Events (matches Ethereum events)
+#[derive(Accounts)]
+pub struct SellStatusView<'info> {
+    #[account(
+        seeds = [b"nc_token_state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, NCTokenState>,
+    
+    /// CHECK: Selling wallet (need not sign)
+    pub wallet: UncheckedAccount<'info>,
+    
+    /// CHECK: The wallet's SellWindow PDA, read only if it exists
+    #[account(
+        seeds = [b"sell_window", wallet.key().as_ref()],
+        bump
+    )]
+    pub sell_window: UncheckedAccount<'info>,
+    
+    // Account the next sale would come from (its balance opens a new window)
+    #[account(
+        token::mint = state.mint,
+        token::authority = wallet
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewTransfer<'info> {
+    #[account(
+        seeds = [b"nc_token_state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, NCTokenState>,
+    
+    // Held to the same mint and owner checks as transfer
+    #[account(
+        token::mint = state.mint,
+        token::authority = from_authority
+    )]
+    pub from_account: Account<'info, TokenAccount>,
+    
+    #[account(token::mint = state.mint)]
+    pub to_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Sender wallet (need not sign a preview)
+    pub from_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: Sender's SellWindow PDA, read only if it exists
+    #[account(
+        seeds = [b"sell_window", from_authority.key().as_ref()],
+        bump
+    )]
+    pub sell_window: UncheckedAccount<'info>,
+}
+
+// Events (matches Ethereum events) - emitted through event CPI, so they
+// reach indexers as inner instructions rather than truncatable logs
 #[event]
+#[derive(Debug)]
 pub struct EmergencyPauseSet {
+    pub module: PauseModule,
     pub paused: bool,
+    pub expires_at: i64,
+    pub reason: String,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct BlacklistSet {
     pub account: Pubkey,
     pub value: bool,
+    pub reason_code: u16,
+    pub evidence_hash: [u8; 32],
+    pub proposed_by: Pubkey,
+    pub expires_at: i64,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct RestrictedSet {
     pub account: Pubkey,
     pub value: bool,
 }
 
 #[event]
+#[derive(Debug)]
+pub struct NoSellLimitSet {
+    pub account: Pubkey,
+    pub value: bool,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct ModuleAddressChanged {
+    pub module: ProtocolModule,
+    pub old_address: Pubkey,
+    pub new_address: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct MerkleRootSet {
+    pub list: AddressList,
+    pub root: [u8; 32],
+}
+
+#[event]
+#[derive(Debug)]
+pub struct TokenAccountFrozen {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub frozen: bool,
+}
+
+#[event]
+#[derive(Debug)]
 pub struct LiquidityPoolUpdated {
     pub pool: Pubkey,
     pub is_pool: bool,
 }
 
 #[event]
+#[derive(Debug)]
 pub struct TransferEvent {
     pub from: Pubkey,
     pub to: Pubkey,
@@ -413,4 +1221,34 @@ pub enum ErrorCode {
     MathOverflow,
     #[msg("Unauthorized - only governance")]
     Unauthorized,
+    #[msg("Invalid pause module")]
+    InvalidPauseModule,
+    #[msg("State PDA is not the mint freeze authority")]
+    NotFreezeAuthority,
+    #[msg("Merkle roots are only supported for the blacklist and no-sell-limit lists")]
+    InvalidMerkleList,
+    #[msg("Invalid module address")]
+    InvalidModuleAddress,
+    #[msg("Signer is not the program upgrade authority")]
+    NotUpgradeAuthority,
+    #[msg("Governance must be the multisig governance PDA")]
+    InvalidGovernance,
+    #[msg("Address is already assigned to another module")]
+    ModuleAddressInUse,
+    #[msg("Mint is not the NC mint")]
+    InvalidMint,
+    #[msg("List is full")]
+    ListFull,
+}
+
+impl From<Denied> for ErrorCode {
+    fn from(denied: Denied) -> Self {
+        match denied {
+            Denied::ModulePaused(_) | Denied::EmergencyPaused => ErrorCode::Paused,
+            Denied::BlacklistedSender | Denied::BlacklistedRecipient | Denied::BlacklistedPair => {
+                ErrorCode::Blacklisted
+            }
+            Denied::SellLimitExceeded { .. } => ErrorCode::SellLimitExceeded,
+        }
+    }
 }
@@ -0,0 +1,172 @@
+// Merkle-root address lists (alternative to the on-chain Vec lists)
+//
+// Leaves are the sorted list members between two sentinels (all-zero and
+// all-0xff keys), padded to a power of two with the high sentinel. Any
+// address then has two adjacent leaves around it, which is what a
+// non-membership proof for the blacklist shows.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+// Root value meaning "Merkle mode disabled"
+pub const EMPTY_ROOT: [u8; 32] = [0; 32];
+
+pub const LOW_SENTINEL: Pubkey = Pubkey::new_from_array([0; 32]);
+pub const HIGH_SENTINEL: Pubkey = Pubkey::new_from_array([0xff; 32]);
+
+// 2^24 leaves is far beyond what a transaction can carry proofs for
+pub const MAX_DEPTH: usize = 24;
+
+pub fn leaf_hash(account: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0u8], account.as_ref()]).to_bytes()
+}
+
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&[1u8], left, right]).to_bytes()
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub index: u32,                // leaf position in the padded tree
+    pub siblings: Vec<[u8; 32]>,   // bottom-up
+}
+
+impl MerkleProof {
+    pub fn root_for(&self, account: &Pubkey) -> Option<[u8; 32]> {
+        if self.siblings.len() > MAX_DEPTH || (self.index as u64) >> self.siblings.len() != 0 {
+            return None;
+        }
+        let mut node = leaf_hash(account);
+        let mut index = self.index;
+        for sibling in &self.siblings {
+            node = if index & 1 == 0 {
+                node_hash(&node, sibling)
+            } else {
+                node_hash(sibling, &node)
+            };
+            index >>= 1;
+        }
+        Some(node)
+    }
+
+    // Membership (allow-list) check - sentinels are never members
+    pub fn verify(&self, root: &[u8; 32], account: &Pubkey) -> bool {
+        *root != EMPTY_ROOT &&
+            *account != LOW_SENTINEL &&
+            *account != HIGH_SENTINEL &&
+            self.root_for(account).as_ref() == Some(root)
+    }
+}
+
+// Proves `account` is absent: two adjacent leaves with low < account < high
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct NonMembershipProof {
+    pub low: Pubkey,
+    pub low_proof: MerkleProof,
+    pub high: Pubkey,
+    pub high_proof: MerkleProof,
+}
+
+impl NonMembershipProof {
+    pub fn verify(&self, root: &[u8; 32], account: &Pubkey) -> bool {
+        *root != EMPTY_ROOT &&
+            self.low.to_bytes() < account.to_bytes() &&
+            account.to_bytes() < self.high.to_bytes() &&
+            self.low_proof.index.checked_add(1) == Some(self.high_proof.index) &&
+            self.low_proof.root_for(&self.low).as_ref() == Some(root) &&
+            self.high_proof.root_for(&self.high).as_ref() == Some(root)
+    }
+}
+
+// Proofs a sender attaches to `transfer_with_proofs` (for the sender's
+// wallet and the recipient's token account)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct TransferProofs {
+    pub from_not_blacklisted: Option<NonMembershipProof>,
+    pub to_not_blacklisted: Option<NonMembershipProof>,
+    pub from_sell_exempt: Option<MerkleProof>,
+}
+
+// Tree builder for off-chain tooling
+#[cfg(not(target_os = "solana"))]
+pub struct MerkleTree {
+    leaves: Vec<Pubkey>,
+    levels: Vec<Vec<[u8; 32]>>, // levels[0] = leaf hashes, last = [root]
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree {
+    pub fn new(accounts: impl IntoIterator<Item = Pubkey>) -> Self {
+        let mut leaves: Vec<Pubkey> = accounts
+            .into_iter()
+            .filter(|a| *a != LOW_SENTINEL && *a != HIGH_SENTINEL)
+            .collect();
+        leaves.sort_by_key(|a| a.to_bytes());
+        leaves.dedup();
+        leaves.insert(0, LOW_SENTINEL);
+        leaves.push(HIGH_SENTINEL);
+        leaves.resize(leaves.len().next_power_of_two(), HIGH_SENTINEL);
+
+        let mut levels = vec![leaves.iter().map(leaf_hash).collect::<Vec<_>>()];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| node_hash(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        Self { leaves, levels }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    // List members, excluding sentinels and padding
+    pub fn members(&self) -> impl Iterator<Item = &Pubkey> {
+        self.leaves
+            .iter()
+            .filter(|a| **a != LOW_SENTINEL && **a != HIGH_SENTINEL)
+    }
+
+    pub fn prove(&self, account: &Pubkey) -> Option<MerkleProof> {
+        if *account == LOW_SENTINEL || *account == HIGH_SENTINEL {
+            return None;
+        }
+        self.position(account).ok().map(|index| self.proof_at(index))
+    }
+
+    pub fn prove_absent(&self, account: &Pubkey) -> Option<NonMembershipProof> {
+        // Err(i) = insertion point, so leaves[i - 1] < account < leaves[i]
+        let high = self.position(account).err()?;
+        if high == 0 || high >= self.leaves.len() {
+            return None;
+        }
+        Some(NonMembershipProof {
+            low: self.leaves[high - 1],
+            low_proof: self.proof_at(high - 1),
+            high: self.leaves[high],
+            high_proof: self.proof_at(high),
+        })
+    }
+
+    fn position(&self, account: &Pubkey) -> std::result::Result<usize, usize> {
+        self.leaves.binary_search_by_key(&account.to_bytes(), |a| a.to_bytes())
+    }
+
+    fn proof_at(&self, index: usize) -> MerkleProof {
+        let siblings = self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(level, hashes)| hashes[(index >> level) ^ 1])
+            .collect();
+        MerkleProof {
+            index: index as u32,
+            siblings,
+        }
+    }
+}
@@ -0,0 +1,218 @@
+// nc-rules - NC Token transfer rules
+//
+// `check` decides a transfer from a snapshot of the pause switches, where
+// each party stands on the address lists and the sender's sell window. It
+// is what nc_token::transfer runs on-chain, so wallets and backends get
+// the same answer without simulating. No accounts, clock or allocation:
+// resolving parties from the state (including Merkle proofs) is left to
+// the caller (`NCTokenState::parties` in nc_token).
+
+#![no_std]
+
+mod sell;
+
+pub use sell::{Bucket, Window, BUCKETS, BUCKET_SECONDS, SELL_LIMIT_BPS, WINDOW_SECONDS};
+
+use core::fmt;
+
+// Pause switches, numbered as nc_token::PauseModule (bit 0 is the
+// emergency pause, which has its own rule)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Module {
+    Global = 1,
+    Bridge,
+    Bond,
+    Treasury,
+    LpTrading,
+    P2p,
+}
+
+impl Module {
+    pub const fn flag(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+// Pause switches of the token state
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct State {
+    pub emergency_paused: bool,
+    pub pause_expires_at: i64, // 0 = no expiry
+    pub paused_modules: u8,    // Module::flag() bits
+}
+
+impl State {
+    // Emergency pause in force (lapses once pause_expires_at has passed)
+    pub fn is_paused(&self, now: i64) -> bool {
+        self.emergency_paused && (self.pause_expires_at == 0 || now < self.pause_expires_at)
+    }
+
+    pub fn is_module_paused(&self, module: Module) -> bool {
+        self.paused_modules & module.flag() != 0
+    }
+
+    // First paused switch that covers a from -> to transfer
+    pub fn paused_module_for(&self, from: &Party, to: &Party) -> Option<Module> {
+        let touches = |module: Module| from.module == Some(module) || to.module == Some(module);
+        let is_pool = from.liquidity_pool || to.liquidity_pool;
+        let is_p2p = !is_pool
+            && !from.restricted
+            && !to.restricted
+            && from.module.is_none()
+            && to.module.is_none();
+
+        [
+            (Module::Global, true),
+            (Module::Bridge, touches(Module::Bridge)),
+            (Module::Bond, touches(Module::Bond)),
+            (Module::Treasury, touches(Module::Treasury)),
+            (Module::LpTrading, is_pool),
+            (Module::P2p, is_p2p),
+        ]
+        .into_iter()
+        .find(|&(module, applies)| applies && self.is_module_paused(module))
+        .map(|(module, _)| module)
+    }
+}
+
+// Blacklist standing of a party
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Listing {
+    #[default]
+    Clear,
+    // On-chain entry in force
+    Listed,
+    // Merkle blacklist active and no non-membership proof given - counts
+    // as listed when trading with a restricted address, or with a party
+    // that is itself listed or unproven (see `check`)
+    Unproven,
+}
+
+impl Listing {
+    // Whether the listing blocks trading with a restricted counterparty
+    pub fn applies(self, counterparty_restricted: bool) -> bool {
+        match self {
+            Listing::Clear => false,
+            Listing::Listed => true,
+            Listing::Unproven => counterparty_restricted,
+        }
+    }
+}
+
+// Where one side of a transfer stands (the sender by wallet, the
+// recipient by token account)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Party {
+    pub restricted: bool, // on the restricted list (protocol modules)
+    pub blacklisted: Listing,
+    pub liquidity_pool: bool,
+    pub module: Option<Module>, // the bridge, bond or treasury address itself
+    pub sell_exempt: bool,      // no-sell-limit list, or proven against its root
+}
+
+impl Party {
+    // Restricted senders are exempt as well as listed ones
+    pub fn is_sell_limit_exempt(&self) -> bool {
+        self.sell_exempt || self.restricted
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: Party,
+    pub to: Party,
+    pub amount: u64,
+    pub balance: u64, // sender's token balance (the baseline of a new window)
+    pub now: i64,
+}
+
+impl Transfer {
+    // Sales into a pool by anyone but an exempt or restricted sender
+    pub fn is_sell_limited(&self) -> bool {
+        self.to.liquidity_pool && !self.from.is_sell_limit_exempt()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Allowed {
+    // The sale counts against the sender's window - record it with
+    // `Window::record`
+    pub sell_limited: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Denied {
+    ModulePaused(Module),
+    // Emergency pause with a restricted or blacklisted party
+    EmergencyPaused,
+    // Blacklisted sender paying a restricted address
+    BlacklistedSender,
+    // Restricted address paying a blacklisted recipient
+    BlacklistedRecipient,
+    // Both parties listed, or unproven against the Merkle blacklist
+    BlacklistedPair,
+    SellLimitExceeded { remaining: u64 },
+}
+
+impl fmt::Display for Denied {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Denied::ModulePaused(module) => write!(f, "{:?} module paused", module),
+            Denied::EmergencyPaused => write!(f, "emergency pause"),
+            Denied::BlacklistedSender => write!(f, "blacklisted sender to a restricted address"),
+            Denied::BlacklistedRecipient => {
+                write!(f, "restricted address to a blacklisted recipient")
+            }
+            Denied::BlacklistedPair => write!(f, "sender and recipient blacklisted or unproven"),
+            Denied::SellLimitExceeded { remaining } => {
+                write!(f, "sell limit exceeded ({} left in the window)", remaining)
+            }
+        }
+    }
+}
+
+// Transfer rules in the order nc_token applies them: pause switches, the
+// emergency pause, blacklist, then the 5% sell limit
+pub fn check(state: &State, window: &Window, transfer: &Transfer) -> Result<Allowed, Denied> {
+    let (from, to) = (&transfer.from, &transfer.to);
+    let from_blacklisted = from.blacklisted.applies(to.restricted);
+    let to_blacklisted = to.blacklisted.applies(from.restricted);
+
+    if let Some(module) = state.paused_module_for(from, to) {
+        return Err(Denied::ModulePaused(module));
+    }
+
+    // Only freezes protocol modules - P2P transfers remain allowed unless
+    // the blacklist applies
+    if state.is_paused(transfer.now)
+        && (from.restricted || to.restricted || from_blacklisted || to_blacklisted)
+    {
+        return Err(Denied::EmergencyPaused);
+    }
+
+    // The blacklist can ONLY block interactions WITH protocol modules, or
+    // between two blacklisted parties - an unproven party may be on the
+    // Merkle blacklist, so it needs a proof to trade with another one or
+    // with a listed party
+    if from_blacklisted && to.restricted {
+        return Err(Denied::BlacklistedSender);
+    }
+    if to_blacklisted && from.restricted {
+        return Err(Denied::BlacklistedRecipient);
+    }
+    if from.blacklisted != Listing::Clear && to.blacklisted != Listing::Clear {
+        return Err(Denied::BlacklistedPair);
+    }
+
+    let sell_limited = transfer.is_sell_limited();
+    if sell_limited {
+        let window = window.current(transfer.now, transfer.balance);
+        let sold = window.sold(transfer.now);
+        if sold.saturating_add(transfer.amount) > window.limit() {
+            return Err(Denied::SellLimitExceeded {
+                remaining: window.limit().saturating_sub(sold),
+            });
+        }
+    }
+    Ok(Allowed { sell_limited })
+}
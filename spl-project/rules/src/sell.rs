@@ -0,0 +1,99 @@
+// Rolling 24h sell window (matches Ethereum _enforceSellLimit)
+//
+// A window opens at a wallet's first limited sale with that moment's
+// balance as its baseline and runs for 24h; sales are summed in hour
+// buckets that each count for 24h after their hour started.
+
+pub const WINDOW_SECONDS: i64 = 86_400;
+pub const BUCKET_SECONDS: i64 = 3_600;
+pub const BUCKETS: usize = 24;
+// 5% of the window's baseline
+pub const SELL_LIMIT_BPS: u64 = 500;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bucket {
+    pub timestamp: i64, // hour-start timestamp (seconds)
+    pub amount: u64,    // sold in that hour bucket
+}
+
+impl Bucket {
+    // An age that overflows i64 is past the window if now is the later one
+    pub fn counts_at(&self, now: i64) -> bool {
+        self.timestamp != 0
+            && now
+                .checked_sub(self.timestamp)
+                .map_or(now < self.timestamp, |age| age < WINDOW_SECONDS)
+    }
+
+    // First moment the bucket no longer counts
+    pub fn expires_at(&self) -> i64 {
+        self.timestamp.saturating_add(WINDOW_SECONDS)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Window {
+    pub start_time: i64,
+    pub start_balance: u64,
+    pub buckets: [Bucket; BUCKETS],
+}
+
+impl Window {
+    // Never opened, or 24h past its start - the next sale opens a new one
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.start_time == 0
+            || now
+                .checked_sub(self.start_time)
+                .map_or(now > self.start_time, |age| age >= WINDOW_SECONDS)
+    }
+
+    // The window a sale at `now` by a holder of `balance` is checked against
+    pub fn current(&self, now: i64, balance: u64) -> Window {
+        if self.is_expired(now) {
+            Window {
+                start_time: now,
+                start_balance: balance,
+                buckets: [Bucket::default(); BUCKETS],
+            }
+        } else {
+            *self
+        }
+    }
+
+    // In u128 so large balances can't overflow; 5% of a u64 always fits back
+    pub fn limit(&self) -> u64 {
+        (self.start_balance as u128 * SELL_LIMIT_BPS as u128 / 10_000) as u64
+    }
+
+    // Sold over the last 24h of buckets (bounded by the limit, since every
+    // recorded sale fitted it)
+    pub fn sold(&self, now: i64) -> u64 {
+        self.buckets
+            .iter()
+            .filter(|bucket| bucket.counts_at(now))
+            .fold(0, |sold, bucket| sold.saturating_add(bucket.amount))
+    }
+
+    pub fn remaining(&self, now: i64) -> u64 {
+        self.limit().saturating_sub(self.sold(now))
+    }
+
+    // Adds an allowed sale, opening a new window first if this one lapsed
+    pub fn record(&mut self, now: i64, balance: u64, amount: u64) {
+        if self.is_expired(now) {
+            *self = self.current(now, balance);
+        }
+        // Euclidean so pre-epoch clocks floor to their hour and never index
+        // below zero
+        let hour_start = now.saturating_sub(now.rem_euclid(BUCKET_SECONDS));
+        let hour = now.div_euclid(BUCKET_SECONDS);
+        let bucket = &mut self.buckets[hour.rem_euclid(BUCKETS as i64) as usize];
+        if bucket.timestamp != hour_start {
+            *bucket = Bucket {
+                timestamp: hour_start,
+                amount: 0,
+            };
+        }
+        bucket.amount = bucket.amount.saturating_add(amount);
+    }
+}
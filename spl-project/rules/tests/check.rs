@@ -0,0 +1,67 @@
+use nc_rules::{check, Allowed, Denied, Listing, Party, State, Transfer, Window};
+
+const NOW: i64 = 1_700_000_000;
+
+fn party(blacklisted: Listing, restricted: bool) -> Party {
+    Party {
+        restricted,
+        blacklisted,
+        ..Party::default()
+    }
+}
+
+fn transfer(from: Party, to: Party) -> Result<Allowed, Denied> {
+    transfer_under(&State::default(), from, to)
+}
+
+fn transfer_under(state: &State, from: Party, to: Party) -> Result<Allowed, Denied> {
+    let transfer = Transfer {
+        from,
+        to,
+        amount: 10,
+        balance: 1_000,
+        now: NOW,
+    };
+    check(state, &Window::default(), &transfer)
+}
+
+#[test]
+fn unproven_parties_trade_only_with_clear_ones() {
+    let clear = party(Listing::Clear, false);
+    let listed = party(Listing::Listed, false);
+    let unproven = party(Listing::Unproven, false);
+
+    assert!(transfer(unproven, clear).is_ok());
+    assert!(transfer(clear, unproven).is_ok());
+    assert!(transfer(listed, clear).is_ok());
+    assert_eq!(transfer(unproven, unproven), Err(Denied::BlacklistedPair));
+    assert_eq!(transfer(unproven, listed), Err(Denied::BlacklistedPair));
+    assert_eq!(transfer(listed, unproven), Err(Denied::BlacklistedPair));
+    assert_eq!(transfer(listed, listed), Err(Denied::BlacklistedPair));
+}
+
+#[test]
+fn unproven_parties_count_as_listed_against_modules() {
+    let module = party(Listing::Clear, true);
+    let unproven = party(Listing::Unproven, false);
+
+    assert_eq!(transfer(unproven, module), Err(Denied::BlacklistedSender));
+    assert_eq!(transfer(module, unproven), Err(Denied::BlacklistedRecipient));
+    assert!(transfer(module, party(Listing::Clear, false)).is_ok());
+}
+
+#[test]
+fn emergency_pause_stops_unproven_parties_only_with_modules() {
+    let state = State {
+        emergency_paused: true,
+        pause_expires_at: 0,
+        paused_modules: 0,
+    };
+    let unproven = party(Listing::Unproven, false);
+
+    assert!(transfer_under(&state, unproven, party(Listing::Clear, false)).is_ok());
+    assert_eq!(
+        transfer_under(&state, unproven, party(Listing::Clear, true)),
+        Err(Denied::EmergencyPaused)
+    );
+}
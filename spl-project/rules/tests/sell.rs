@@ -0,0 +1,69 @@
+use nc_rules::{Bucket, Window, BUCKETS, BUCKET_SECONDS, WINDOW_SECONDS};
+
+#[test]
+fn pre_epoch_sales_land_in_their_floored_hour() {
+    let mut window = Window::default();
+    let now = -1;
+    window.record(now, 1_000_000, 10);
+
+    let hour_start = -BUCKET_SECONDS;
+    let index = (-1i64).rem_euclid(BUCKETS as i64) as usize;
+    assert_eq!(
+        window.buckets[index],
+        Bucket {
+            timestamp: hour_start,
+            amount: 10,
+        }
+    );
+    assert_eq!(window.sold(now), 10);
+}
+
+#[test]
+fn buckets_rotate_across_the_window() {
+    let mut window = Window::default();
+    let start = 10 * WINDOW_SECONDS;
+    for hour in 0..BUCKETS as i64 {
+        window.record(start + hour * BUCKET_SECONDS, 1_000_000, 1);
+    }
+    assert_eq!(window.sold(start + WINDOW_SECONDS - 1), BUCKETS as u64);
+    // The window lapses and the next sale starts a fresh one
+    window.record(start + WINDOW_SECONDS, 1_000_000, 5);
+    assert_eq!(window.start_time, start + WINDOW_SECONDS);
+    assert_eq!(window.sold(start + WINDOW_SECONDS), 5);
+}
+
+#[test]
+fn extreme_clocks_do_not_overflow() {
+    let window = Window {
+        start_time: i64::MIN + 1,
+        start_balance: 1_000,
+        buckets: [Bucket {
+            timestamp: i64::MIN + 1,
+            amount: 7,
+        }; BUCKETS],
+    };
+    assert!(window.is_expired(i64::MAX));
+    assert!(!window.buckets[0].counts_at(i64::MAX));
+    assert_eq!(window.sold(i64::MAX), 0);
+
+    let future = Window {
+        start_time: i64::MAX,
+        ..window
+    };
+    assert!(!future.is_expired(i64::MIN + 1));
+    assert_eq!(future.buckets[0].expires_at(), i64::MIN + 1 + WINDOW_SECONDS);
+    assert_eq!(
+        Bucket {
+            timestamp: i64::MAX,
+            amount: 1,
+        }
+        .expires_at(),
+        i64::MAX
+    );
+
+    for now in [i64::MIN, i64::MAX] {
+        let mut window = Window::default();
+        window.record(now, u64::MAX, 1);
+        assert_eq!(window.sold(now), 1);
+    }
+}
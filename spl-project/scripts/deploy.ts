@@ -21,6 +21,7 @@ import {
   sendAndConfirmTransaction,
   Connection,
   clusterApiUrl,
+  BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
 } from "@solana/web3.js";
 // Metaplex Token Metadata Program ID
 const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
@@ -104,6 +105,14 @@ async function main() {
   console.log("📍 Governance PDA:", governancePda.toString());
   console.log("");
 
+  // initialize is gated to the upgrade authority recorded in ProgramData,
+  // so this wallet must be the one that deployed both programs
+  const programDataFor = (programId: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+    )[0];
+
   // Step 1: Initialize Governance
   console.log("1️⃣ Initializing MultiSig Governance...");
   try {
@@ -112,6 +121,8 @@ async function main() {
       .accounts({
         governance: governancePda,
         authority: walletKeypair.publicKey,
+        program: governanceProgram.programId,
+        programData: programDataFor(governanceProgram.programId),
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -128,18 +139,21 @@ async function main() {
   // Step 2: Initialize NC Token
   console.log("2️⃣ Initializing NC Token...");
   
-  // For now, use governance PDA as governance address
-  // In production, you'd use the actual governance program ID
+  // nc_token only accepts the governance PDA as its governance key
   const bridge = walletKeypair.publicKey; // Placeholder
   const treasury = walletKeypair.publicKey; // Placeholder
   const bond = walletKeypair.publicKey; // Placeholder
+  // The mint is created in step 4; its address is recorded up front
+  const mintKeypair = Keypair.generate();
 
   try {
     const initTokenTx = await ncTokenProgram.methods
-      .initialize(governanceProgram.programId, bridge, treasury, bond)
+      .initialize(governancePda, bridge, treasury, bond, mintKeypair.publicKey)
       .accounts({
         state: ncTokenStatePda,
         authority: walletKeypair.publicKey,
+        program: ncTokenProgram.programId,
+        programData: programDataFor(ncTokenProgram.programId),
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -157,7 +171,7 @@ async function main() {
   console.log("3️⃣ Setting token in governance...");
   try {
     const setTokenTx = await governanceProgram.methods
-      .setToken(ncTokenProgram.programId)
+      .setToken(ncTokenProgram.programId, ncTokenStatePda)
       .accounts({
         governance: governancePda,
         authority: walletKeypair.publicKey,
@@ -175,7 +189,6 @@ async function main() {
 
   // Step 4: Create token mint
   console.log("4️⃣ Creating token mint...");
-  const mintKeypair = Keypair.generate();
   const mintRent = await getMinimumBalanceForRentExemptMint(connection);
 
   const createMintTx = new Transaction().add(
@@ -0,0 +1,30 @@
+// Account decoders (raw account data, discriminator included)
+
+use anchor_lang::{AccountDeserialize, Result};
+
+pub use multisig_governance::{ApprovalRecord, Governance, PendingTransaction};
+pub use nc_token::{NCTokenState, SellWindow};
+
+pub fn decode<T: AccountDeserialize>(mut data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut data)
+}
+
+pub fn nc_token_state(data: &[u8]) -> Result<NCTokenState> {
+    decode(data)
+}
+
+pub fn sell_window(data: &[u8]) -> Result<SellWindow> {
+    decode(data)
+}
+
+pub fn governance(data: &[u8]) -> Result<Governance> {
+    decode(data)
+}
+
+pub fn pending_transaction(data: &[u8]) -> Result<PendingTransaction> {
+    decode(data)
+}
+
+pub fn approval_record(data: &[u8]) -> Result<ApprovalRecord> {
+    decode(data)
+}
@@ -0,0 +1,197 @@
+// Event decoders for every #[event] of both programs
+//
+// The payload of an event is its discriminator followed by the
+// borsh-encoded event. Both programs emit events through event CPI: a
+// self-invocation whose instruction data is EVENT_IX_TAG followed by the
+// payload, found in the transaction's inner instructions. Older
+// transactions logged them as "Program data: <base64>" instead. Both
+// programs have same-named events, so payloads are attributed to the
+// program that emitted them.
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+
+macro_rules! program_events {
+    ($name:ident, $program:ident, [$($event:ident),* $(,)?]) => {
+        #[derive(Debug)]
+        pub enum $name {
+            $($event($program::$event),)*
+        }
+
+        impl $name {
+            // Decodes discriminator + borsh payload
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                $(
+                    if let Some(body) = data.strip_prefix($program::$event::DISCRIMINATOR) {
+                        return $program::$event::try_from_slice(body).ok().map(Self::$event);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$event(_) => stringify!($event),)*
+                }
+            }
+
+            // The event struct itself, for printing its fields
+            pub fn fields(&self) -> &dyn fmt::Debug {
+                match self {
+                    $(Self::$event(event) => event,)*
+                }
+            }
+        }
+    };
+}
+
+program_events!(NcTokenEvent, nc_token, [
+    EmergencyPauseSet,
+    BlacklistSet,
+    RestrictedSet,
+    NoSellLimitSet,
+    ModuleAddressChanged,
+    MerkleRootSet,
+    TokenAccountFrozen,
+    LiquidityPoolUpdated,
+    TransferEvent,
+]);
+
+program_events!(GovernanceEvent, multisig_governance, [
+    TransactionQueued,
+    TransactionApproved,
+    TransactionRejected,
+    ProposalClosed,
+    TransactionExecuted,
+    AddressBlacklisted,
+    TokenContractUpdated,
+    RequiredApprovalsChanged,
+    OwnerAdded,
+    OwnerRemoved,
+    RequireReapprovalSet,
+    NoSellLimitSet,
+    AddressRestricted,
+    PairSet,
+    EmergencyPause,
+    EmergencyPauseExtended,
+    EmergencyUnpause,
+    TokenAccountFreezeSet,
+    ListBatchAppended,
+    ListBatchChunkExecuted,
+    ModuleAddressSet,
+    MerkleRootUpdated,
+    ModulePauseSet,
+    GuardiansChanged,
+]);
+
+#[derive(Debug)]
+pub enum Event {
+    NcToken(NcTokenEvent),
+    Governance(GovernanceEvent),
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::NcToken(event) => event.name(),
+            Event::Governance(event) => event.name(),
+        }
+    }
+
+    pub fn fields(&self) -> &dyn fmt::Debug {
+        match self {
+            Event::NcToken(event) => event.fields(),
+            Event::Governance(event) => event.fields(),
+        }
+    }
+}
+
+// An event with the program that emitted it and its raw payload
+// (discriminator + borsh)
+#[derive(Debug)]
+pub struct LoggedEvent {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+    pub event: Event,
+}
+
+// Decodes an event payload emitted by `program_id`
+pub fn decode_event(program_id: &Pubkey, data: &[u8]) -> Option<Event> {
+    if *program_id == NC_TOKEN_PROGRAM_ID {
+        NcTokenEvent::decode(data).map(Event::NcToken)
+    } else if *program_id == GOVERNANCE_PROGRAM_ID {
+        GovernanceEvent::decode(data).map(Event::Governance)
+    } else {
+        None
+    }
+}
+
+// Decodes the data of an instruction `program_id` invoked - an event if it
+// is one of its event CPIs
+pub fn decode_cpi_event(program_id: &Pubkey, ix_data: &[u8]) -> Option<LoggedEvent> {
+    let data = ix_data.strip_prefix(EVENT_IX_TAG_LE)?;
+    decode_event(program_id, data).map(|event| LoggedEvent {
+        program_id: *program_id,
+        data: data.to_vec(),
+        event,
+    })
+}
+
+// Decodes every event CPI among a transaction's inner instructions, given
+// as (program id, instruction data) in execution order
+pub fn parse_inner_instructions<'a, I>(instructions: I) -> Vec<LoggedEvent>
+where
+    I: IntoIterator<Item = (&'a Pubkey, &'a [u8])>,
+{
+    instructions
+        .into_iter()
+        .filter_map(|(program_id, data)| decode_cpi_event(program_id, data))
+        .collect()
+}
+
+// Decodes every event in a transaction's log messages, in log order (events
+// emitted before the programs switched to event CPI)
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Event> {
+    parse_logged(logs).into_iter().map(|logged| logged.event).collect()
+}
+
+// parse_logs, keeping the logging program and payload of each event
+pub fn parse_logged<S: AsRef<str>>(logs: &[S]) -> Vec<LoggedEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        let line = line.as_ref();
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            let program_id = stack.last();
+            let payload = STANDARD.decode(data.trim()).ok();
+            if let (Some(program_id), Some(data)) = (program_id, payload) {
+                if let Some(event) = decode_event(program_id, &data) {
+                    events.push(LoggedEvent {
+                        program_id: *program_id,
+                        data,
+                        event,
+                    });
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            // "Program <id> invoke [n]" / "Program <id> success" / "... failed: ..."
+            let mut parts = rest.split_whitespace();
+            let program_id = parts.next().and_then(|id| Pubkey::from_str(id).ok());
+            match (program_id, parts.next()) {
+                (Some(id), Some("invoke")) => stack.push(id),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
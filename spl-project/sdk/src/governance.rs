@@ -0,0 +1,616 @@
+// multisig_governance instruction builders
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anchor_lang::{AnchorDeserialize, Result};
+use multisig_governance::{
+    accounts, instruction as ix, ErrorCode, ListBatch, PendingTransaction, ProposalMetadata,
+    TransactionType,
+};
+use nc_token::{AddressList, BlacklistDetails, ListUpdate, PauseModule, ProtocolModule};
+
+use crate::{instruction, pda, GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+
+// Every kind of proposal owners can queue, with its arguments
+#[derive(Clone, Debug)]
+pub enum Proposal {
+    Unpause,
+    ExtendPause {
+        expires_at: i64,
+    },
+    ModulePause {
+        module: PauseModule,
+        value: bool,
+    },
+    Blacklist {
+        account: Pubkey,
+        value: bool,
+        reason_code: u16,
+        evidence_hash: [u8; 32],
+        expires_at: i64,
+        freeze_token_accounts: Vec<Pubkey>,
+    },
+    Freeze {
+        token_account: Pubkey,
+        value: bool,
+    },
+    ListBatch {
+        list: AddressList,
+        updates: Vec<ListUpdate>,
+        reason_code: u16,
+        evidence_hash: [u8; 32],
+        expires_at: i64,
+    },
+    ModuleAddress {
+        module: ProtocolModule,
+        new_address: Pubkey,
+    },
+    MerkleRoot {
+        list: AddressList,
+        root: [u8; 32],
+    },
+    NoSellLimit {
+        account: Pubkey,
+        value: bool,
+    },
+    Restrict {
+        account: Pubkey,
+        value: bool,
+    },
+    Pair {
+        pool: Pubkey,
+        value: bool,
+    },
+}
+
+// PendingTransaction.data decoded according to its TransactionType
+#[derive(Clone, Debug)]
+pub enum ProposalData {
+    Unpause,
+    ExtendPause {
+        expires_at: i64,
+    },
+    ModulePause {
+        module: PauseModule,
+        value: bool,
+    },
+    Blacklist {
+        account: Pubkey,
+        value: bool,
+        details: BlacklistDetails,
+        freeze_token_accounts: Vec<Pubkey>,
+    },
+    Freeze {
+        token_account: Pubkey,
+        value: bool,
+    },
+    ListBatch(ListBatch),
+    ModuleAddress {
+        module: ProtocolModule,
+        address: Pubkey,
+    },
+    MerkleRoot {
+        list: AddressList,
+        root: [u8; 32],
+    },
+    NoSellLimit {
+        account: Pubkey,
+        value: bool,
+    },
+    Restrict {
+        account: Pubkey,
+        value: bool,
+    },
+    Pair {
+        pool: Pubkey,
+        value: bool,
+    },
+}
+
+impl ProposalData {
+    pub fn decode(tx_type: TransactionType, data: &[u8]) -> Result<Self> {
+        Ok(match tx_type {
+            TransactionType::Unpause => Self::Unpause,
+            TransactionType::ExtendPause => Self::ExtendPause {
+                expires_at: i64::try_from_slice(data)?,
+            },
+            TransactionType::ModulePause => {
+                let (module, value) = <(PauseModule, bool)>::try_from_slice(data)?;
+                Self::ModulePause { module, value }
+            }
+            TransactionType::Blacklist => {
+                let (account, value, details, freeze_token_accounts) =
+                    <(Pubkey, bool, BlacklistDetails, Vec<Pubkey>)>::try_from_slice(data)?;
+                Self::Blacklist {
+                    account,
+                    value,
+                    details,
+                    freeze_token_accounts,
+                }
+            }
+            TransactionType::Freeze => {
+                let (token_account, value) = <(Pubkey, bool)>::try_from_slice(data)?;
+                Self::Freeze {
+                    token_account,
+                    value,
+                }
+            }
+            TransactionType::ListBatch => Self::ListBatch(ListBatch::try_from_slice(data)?),
+            TransactionType::ModuleAddress => {
+                let (module, address) = <(ProtocolModule, Pubkey)>::try_from_slice(data)?;
+                Self::ModuleAddress { module, address }
+            }
+            TransactionType::MerkleRoot => {
+                let (list, root) = <(AddressList, [u8; 32])>::try_from_slice(data)?;
+                Self::MerkleRoot { list, root }
+            }
+            TransactionType::NoSellLimit => {
+                let (account, value) = <(Pubkey, bool)>::try_from_slice(data)?;
+                Self::NoSellLimit { account, value }
+            }
+            TransactionType::Restrict => {
+                let (account, value) = <(Pubkey, bool)>::try_from_slice(data)?;
+                Self::Restrict { account, value }
+            }
+            TransactionType::Pair => {
+                let (pool, value) = <(Pubkey, bool)>::try_from_slice(data)?;
+                Self::Pair { pool, value }
+            }
+            TransactionType::RoleGrant | TransactionType::RoleRevoke => {
+                return Err(ErrorCode::InvalidTransactionType.into())
+            }
+        })
+    }
+
+    // Token accounts execution freezes or thaws (passed as remaining accounts
+    // along with their mint and the token program)
+    pub fn freeze_token_accounts(&self) -> Vec<Pubkey> {
+        match self {
+            Self::Blacklist {
+                freeze_token_accounts,
+                ..
+            } => freeze_token_accounts.clone(),
+            Self::Freeze { token_account, .. } => vec![*token_account],
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Upgrade-authority-only; the signer becomes the governance admin
+pub fn initialize(authority: Pubkey) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::InitializeGovernance {
+            governance: pda::governance().0,
+            authority,
+            program: GOVERNANCE_PROGRAM_ID,
+            program_data: pda::program_data(&GOVERNANCE_PROGRAM_ID),
+            system_program: system_program::ID,
+        },
+        ix::Initialize {},
+    )
+}
+
+// Admin configuration
+
+fn admin_accounts(admin: Pubkey) -> accounts::AdminOnly {
+    accounts::AdminOnly {
+        governance: pda::governance().0,
+        authority: admin,
+        event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+        program: GOVERNANCE_PROGRAM_ID,
+    }
+}
+
+pub fn set_required_approvals(admin: Pubkey, required: u8) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        admin_accounts(admin),
+        ix::SetRequiredApprovals { required },
+    )
+}
+
+pub fn add_owner(admin: Pubkey, owner: Pubkey) -> Instruction {
+    instruction(GOVERNANCE_PROGRAM_ID, admin_accounts(admin), ix::AddOwner { owner })
+}
+
+pub fn remove_owner(admin: Pubkey, owner: Pubkey) -> Instruction {
+    instruction(GOVERNANCE_PROGRAM_ID, admin_accounts(admin), ix::RemoveOwner { owner })
+}
+
+pub fn set_require_reapproval(admin: Pubkey, value: bool) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        admin_accounts(admin),
+        ix::SetRequireReapproval { value },
+    )
+}
+
+// Records the nc_token program and its state PDA
+pub fn set_token(admin: Pubkey) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        admin_accounts(admin),
+        ix::SetToken {
+            token: NC_TOKEN_PROGRAM_ID,
+            state: pda::nc_token_state().0,
+        },
+    )
+}
+
+pub fn set_cooldown_period(admin: Pubkey, period: i64) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        admin_accounts(admin),
+        ix::SetCooldownPeriod { period },
+    )
+}
+
+pub fn set_guardians(admin: Pubkey, guardians: Vec<Pubkey>, quorum: u8) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        admin_accounts(admin),
+        ix::SetGuardians { guardians, quorum },
+    )
+}
+
+pub fn set_max_pause_duration(admin: Pubkey, duration: i64) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        admin_accounts(admin),
+        ix::SetMaxPauseDuration { duration },
+    )
+}
+
+pub fn set_guardian_pause_cooldown(admin: Pubkey, cooldown: i64) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        admin_accounts(admin),
+        ix::SetGuardianPauseCooldown { cooldown },
+    )
+}
+
+// Guardian fast path - co-signing guardians are appended as signers
+pub fn set_emergency_pause(
+    guardian: Pubkey,
+    co_signers: &[Pubkey],
+    duration: i64,
+    reason: String,
+) -> Instruction {
+    let mut ix = instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::EmergencyPauseContext {
+            governance: pda::governance().0,
+            nc_token_program: NC_TOKEN_PROGRAM_ID,
+            nc_token_state: pda::nc_token_state().0,
+            nc_token_event_authority: pda::event_authority(&NC_TOKEN_PROGRAM_ID).0,
+            authority: guardian,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::SetEmergencyPause { duration, reason },
+    );
+    ix.accounts
+        .extend(co_signers.iter().map(|g| AccountMeta::new_readonly(*g, true)));
+    ix
+}
+
+// Proposal lifecycle
+
+// `tx_id` must be the governance account's current next_transaction_id
+pub fn queue(
+    signer: Pubkey,
+    tx_id: u64,
+    proposal: Proposal,
+    metadata: ProposalMetadata,
+) -> Instruction {
+    let accounts = accounts::QueueTransaction {
+        governance: pda::governance().0,
+        proposal: pda::proposal(tx_id).0,
+        signer,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+        program: GOVERNANCE_PROGRAM_ID,
+    };
+    match proposal {
+        Proposal::Unpause => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetUnpause { metadata },
+        ),
+        Proposal::ExtendPause { expires_at } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::ExtendPause {
+                expires_at,
+                metadata,
+            },
+        ),
+        Proposal::ModulePause { module, value } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetModulePause {
+                module,
+                value,
+                metadata,
+            },
+        ),
+        Proposal::Blacklist {
+            account,
+            value,
+            reason_code,
+            evidence_hash,
+            expires_at,
+            freeze_token_accounts,
+        } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetBlacklist {
+                account,
+                value,
+                reason_code,
+                evidence_hash,
+                expires_at,
+                freeze_token_accounts,
+                metadata,
+            },
+        ),
+        Proposal::Freeze {
+            token_account,
+            value,
+        } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetFreeze {
+                token_account,
+                value,
+                metadata,
+            },
+        ),
+        Proposal::ListBatch {
+            list,
+            updates,
+            reason_code,
+            evidence_hash,
+            expires_at,
+        } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetListBatch {
+                list,
+                updates,
+                reason_code,
+                evidence_hash,
+                expires_at,
+                metadata,
+            },
+        ),
+        Proposal::ModuleAddress {
+            module,
+            new_address,
+        } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetModuleAddress {
+                module,
+                new_address,
+                metadata,
+            },
+        ),
+        Proposal::MerkleRoot { list, root } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetMerkleRoot {
+                list,
+                root,
+                metadata,
+            },
+        ),
+        Proposal::NoSellLimit { account, value } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetNoSellLimit {
+                account,
+                value,
+                metadata,
+            },
+        ),
+        Proposal::Restrict { account, value } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetRestricted {
+                account,
+                value,
+                metadata,
+            },
+        ),
+        Proposal::Pair { pool, value } => instruction(
+            GOVERNANCE_PROGRAM_ID,
+            accounts,
+            ix::SetLiquidityPool {
+                pool,
+                value,
+                metadata,
+            },
+        ),
+    }
+}
+
+pub fn append_list_batch(initiator: Pubkey, tx_id: u64, updates: Vec<ListUpdate>) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::AppendListBatch {
+            governance: pda::governance().0,
+            proposal: pda::proposal(tx_id).0,
+            initiator,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::AppendListBatch { tx_id, updates },
+    )
+}
+
+// Approval PDAs of the given owners, as remaining accounts for
+// approve / execute / crank
+pub fn approval_accounts(tx_id: u64, owners: &[Pubkey]) -> Vec<AccountMeta> {
+    let proposal = pda::proposal(tx_id).0;
+    owners
+        .iter()
+        .map(|owner| AccountMeta::new_readonly(pda::approval(&proposal, owner).0, false))
+        .collect()
+}
+
+// `remaining` carries the other approval PDAs (see approval_accounts) plus
+// any token accounts, mint and token program a freeze needs. With
+// `expected_hash` (PendingTransaction::content_hash) the approval fails if
+// the proposal changed since it was read.
+pub fn approve_transaction(
+    approver: Pubkey,
+    tx_id: u64,
+    expected_hash: Option<[u8; 32]>,
+    remaining: Vec<AccountMeta>,
+) -> Instruction {
+    let proposal = pda::proposal(tx_id).0;
+    let mut ix = instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::ApproveTransaction {
+            governance: pda::governance().0,
+            proposal,
+            approval: pda::approval(&proposal, &approver).0,
+            nc_token_program: NC_TOKEN_PROGRAM_ID,
+            nc_token_state: pda::nc_token_state().0,
+            nc_token_event_authority: pda::event_authority(&NC_TOKEN_PROGRAM_ID).0,
+            approver,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::ApproveTransaction { tx_id, expected_hash },
+    );
+    ix.accounts.extend(remaining);
+    ix
+}
+
+pub fn reapprove_transaction(approver: Pubkey, tx_id: u64) -> Instruction {
+    let proposal = pda::proposal(tx_id).0;
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::ReapproveTransaction {
+            governance: pda::governance().0,
+            proposal,
+            approval: pda::approval(&proposal, &approver).0,
+            approver,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::ReapproveTransaction { tx_id },
+    )
+}
+
+pub fn reject_transaction(approver: Pubkey, tx_id: u64, reason: String) -> Instruction {
+    instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::RejectTransaction {
+            governance: pda::governance().0,
+            proposal: pda::proposal(tx_id).0,
+            approver,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::RejectTransaction { tx_id, reason },
+    )
+}
+
+// Approval records a close_proposal can carry and still fit a transaction
+pub const CLOSE_RECORDS_PER_TX: usize = 12;
+
+// `closer` is the initiator or an owner. `approvers` name owners of approval
+// records on the proposal - those records are closed and their rent goes back
+// to each approver. Once no record is left the proposal closes too, its rent
+// going back to the initiator, less what the rejector paid to store its
+// reason
+pub fn close_proposal(closer: Pubkey, proposal: &PendingTransaction, approvers: &[Pubkey]) -> Instruction {
+    let key = pda::proposal(proposal.id).0;
+    let mut ix = instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::CloseProposal {
+            governance: pda::governance().0,
+            proposal: key,
+            initiator: proposal.initiator,
+            rejector: (proposal.rejection_rent > 0).then_some(proposal.rejector),
+            closer,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::CloseProposal { tx_id: proposal.id },
+    );
+    for approver in approvers {
+        ix.accounts.push(AccountMeta::new(pda::approval(&key, approver).0, false));
+        ix.accounts.push(AccountMeta::new(*approver, false));
+    }
+    ix
+}
+
+// close_proposal for every approval record, CLOSE_RECORDS_PER_TX to an
+// instruction sent in a transaction of its own; the last one closes the
+// proposal
+pub fn close_proposal_batches(
+    closer: Pubkey,
+    proposal: &PendingTransaction,
+    approvers: &[Pubkey],
+) -> Vec<Instruction> {
+    if approvers.is_empty() {
+        return vec![close_proposal(closer, proposal, &[])];
+    }
+    approvers
+        .chunks(CLOSE_RECORDS_PER_TX)
+        .map(|chunk| close_proposal(closer, proposal, chunk))
+        .collect()
+}
+
+pub fn execute_transaction(
+    approver: Pubkey,
+    tx_id: u64,
+    expected_hash: Option<[u8; 32]>,
+    remaining: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::CanApprove {
+            governance: pda::governance().0,
+            proposal: pda::proposal(tx_id).0,
+            nc_token_program: NC_TOKEN_PROGRAM_ID,
+            nc_token_state: pda::nc_token_state().0,
+            nc_token_event_authority: pda::event_authority(&NC_TOKEN_PROGRAM_ID).0,
+            approver,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::ExecuteTransaction { tx_id, expected_hash },
+    );
+    ix.accounts.extend(remaining);
+    ix
+}
+
+pub fn crank_execute(cranker: Pubkey, tx_id: u64, remaining: Vec<AccountMeta>) -> Instruction {
+    let mut ix = instruction(
+        GOVERNANCE_PROGRAM_ID,
+        accounts::CrankExecute {
+            governance: pda::governance().0,
+            proposal: pda::proposal(tx_id).0,
+            nc_token_program: NC_TOKEN_PROGRAM_ID,
+            nc_token_state: pda::nc_token_state().0,
+            nc_token_event_authority: pda::event_authority(&NC_TOKEN_PROGRAM_ID).0,
+            cranker,
+            event_authority: pda::event_authority(&GOVERNANCE_PROGRAM_ID).0,
+            program: GOVERNANCE_PROGRAM_ID,
+        },
+        ix::CrankExecute { tx_id },
+    );
+    ix.accounts.extend(remaining);
+    ix
+}
@@ -0,0 +1,35 @@
+// nc-sdk - off-chain client for NC Token and MultiSig Governance
+//
+// Everything here works without an RPC connection: PDA derivation,
+// instruction building, account decoding, event decoding and transfer
+// outcome prediction. Sending transactions is left to the caller.
+
+pub mod accounts;
+pub mod events;
+pub mod governance;
+pub mod pda;
+pub mod rules;
+pub mod token;
+
+pub use multisig_governance;
+pub use nc_rules;
+pub use nc_token;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+pub const NC_TOKEN_PROGRAM_ID: Pubkey = nc_token::ID;
+pub const GOVERNANCE_PROGRAM_ID: Pubkey = multisig_governance::ID;
+
+fn instruction(
+    program_id: Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,40 @@
+// Program-derived addresses used by both programs
+
+use anchor_lang::prelude::Pubkey;
+use solana_sdk_ids::bpf_loader_upgradeable;
+
+use crate::{GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+
+pub fn nc_token_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"nc_token_state"], &NC_TOKEN_PROGRAM_ID)
+}
+
+// Per-wallet 24h sell window, keyed by the transfer authority
+pub fn sell_window(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sell_window", authority.as_ref()], &NC_TOKEN_PROGRAM_ID)
+}
+
+pub fn governance() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"governance"], &GOVERNANCE_PROGRAM_ID)
+}
+
+pub fn proposal(tx_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"proposal", &tx_id.to_le_bytes()], &GOVERNANCE_PROGRAM_ID)
+}
+
+pub fn approval(proposal: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"approval", proposal.as_ref(), owner.as_ref()],
+        &GOVERNANCE_PROGRAM_ID,
+    )
+}
+
+// Signer of a program's event CPIs (#[event_cpi] instructions take it)
+pub fn event_authority(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id)
+}
+
+// ProgramData account holding a program's upgrade authority
+pub fn program_data(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
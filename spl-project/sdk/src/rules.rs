@@ -0,0 +1,44 @@
+// Transfer outcome and sell allowance prediction - the nc_rules checks
+// nc_token runs on-chain, applied to decoded accounts
+
+use anchor_lang::prelude::Pubkey;
+use nc_token::merkle::TransferProofs;
+use nc_token::SellStatus;
+
+pub use nc_rules::{Allowed, Denied, Listing, Module, Party, Transfer, Window};
+
+use crate::accounts::{NCTokenState, SellWindow};
+
+// The sender is checked by wallet and the recipient by token account, as
+// nc_token checks them. `sell_window` is the sender's SellWindow account (None before its first
+// transfer), `balance` the from token account's balance and `now` the
+// cluster's unix timestamp
+#[allow(clippy::too_many_arguments)]
+pub fn check_transfer(
+    state: &NCTokenState,
+    sell_window: Option<&SellWindow>,
+    from_authority: &Pubkey,
+    to_account: &Pubkey,
+    proofs: &TransferProofs,
+    balance: u64,
+    amount: u64,
+    now: i64,
+) -> Result<Allowed, Denied> {
+    let (from, to) = state.parties(from_authority, to_account, proofs, now);
+    let transfer = Transfer {
+        from,
+        to,
+        amount,
+        balance,
+        now,
+    };
+    let window = sell_window.map(SellWindow::window).unwrap_or_default();
+    nc_rules::check(&state.rules(), &window, &transfer)
+}
+
+// What get_sell_status returns, from the decoded SellWindow (None before the
+// wallet's first transfer) and the selling token account's balance
+pub fn sell_status(sell_window: Option<&SellWindow>, balance: u64, now: i64) -> SellStatus {
+    let window = sell_window.map(SellWindow::window).unwrap_or_default();
+    SellStatus::new(&window, balance, now)
+}
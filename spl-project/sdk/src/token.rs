@@ -0,0 +1,157 @@
+// nc_token instruction builders
+//
+// The governance-only setters are CPI targets of multisig_governance and
+// are queued through `governance::queue` instead.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+use nc_token::merkle::TransferProofs;
+use nc_token::{accounts, instruction as ix};
+
+use crate::{instruction, pda, NC_TOKEN_PROGRAM_ID};
+
+// Upgrade-authority-only; governance is always the multisig PDA
+pub fn initialize(
+    authority: Pubkey,
+    bridge: Pubkey,
+    treasury: Pubkey,
+    bond: Pubkey,
+    mint: Pubkey,
+) -> Instruction {
+    instruction(
+        NC_TOKEN_PROGRAM_ID,
+        accounts::Initialize {
+            state: pda::nc_token_state().0,
+            authority,
+            program: NC_TOKEN_PROGRAM_ID,
+            program_data: pda::program_data(&NC_TOKEN_PROGRAM_ID),
+            system_program: system_program::ID,
+        },
+        ix::Initialize {
+            governance: pda::governance().0,
+            bridge,
+            treasury,
+            bond,
+            mint,
+        },
+    )
+}
+
+fn transfer_accounts(
+    mint: Pubkey,
+    from_account: Pubkey,
+    to_account: Pubkey,
+    from_authority: Pubkey,
+) -> accounts::TransferNC {
+    accounts::TransferNC {
+        state: pda::nc_token_state().0,
+        mint,
+        from_account,
+        to_account,
+        from_authority,
+        sell_window: pda::sell_window(&from_authority).0,
+        token_program: TOKEN_PROGRAM_ID,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority(&NC_TOKEN_PROGRAM_ID).0,
+        program: NC_TOKEN_PROGRAM_ID,
+    }
+}
+
+pub fn transfer(
+    mint: Pubkey,
+    from_account: Pubkey,
+    to_account: Pubkey,
+    from_authority: Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        NC_TOKEN_PROGRAM_ID,
+        transfer_accounts(mint, from_account, to_account, from_authority),
+        ix::Transfer { amount },
+    )
+}
+
+pub fn transfer_with_proofs(
+    mint: Pubkey,
+    from_account: Pubkey,
+    to_account: Pubkey,
+    from_authority: Pubkey,
+    amount: u64,
+    proofs: TransferProofs,
+) -> Instruction {
+    instruction(
+        NC_TOKEN_PROGRAM_ID,
+        transfer_accounts(mint, from_account, to_account, from_authority),
+        ix::TransferWithProofs { amount, proofs },
+    )
+}
+
+// Views - simulate and read the return data
+
+pub fn is_blacklisted(account: Pubkey) -> Instruction {
+    instruction(NC_TOKEN_PROGRAM_ID, view_accounts(), ix::IsBlacklisted { account })
+}
+
+pub fn is_sell_limit(account: Pubkey) -> Instruction {
+    instruction(NC_TOKEN_PROGRAM_ID, view_accounts(), ix::IsSellLimit { account })
+}
+
+pub fn is_restricted(account: Pubkey) -> Instruction {
+    instruction(NC_TOKEN_PROGRAM_ID, view_accounts(), ix::IsRestricted { account })
+}
+
+pub fn is_liquidity_pool(account: Pubkey) -> Instruction {
+    instruction(NC_TOKEN_PROGRAM_ID, view_accounts(), ix::IsLiquidityPool { account })
+}
+
+pub fn is_sell_limit_exempt(account: Pubkey) -> Instruction {
+    instruction(NC_TOKEN_PROGRAM_ID, view_accounts(), ix::IsSellLimitExempt { account })
+}
+
+// Returns nc_token::TokenConfig
+pub fn get_config() -> Instruction {
+    instruction(NC_TOKEN_PROGRAM_ID, view_accounts(), ix::GetConfig {})
+}
+
+// Returns nc_token::SellStatus for `wallet` selling from `token_account`
+pub fn get_sell_status(wallet: Pubkey, token_account: Pubkey) -> Instruction {
+    instruction(
+        NC_TOKEN_PROGRAM_ID,
+        accounts::SellStatusView {
+            state: pda::nc_token_state().0,
+            wallet,
+            sell_window: pda::sell_window(&wallet).0,
+            token_account,
+        },
+        ix::GetSellStatus {},
+    )
+}
+
+// Returns nc_token::TransferPreview; `from_authority` need not sign
+pub fn preview_transfer(
+    from_account: Pubkey,
+    to_account: Pubkey,
+    from_authority: Pubkey,
+    amount: u64,
+    proofs: TransferProofs,
+) -> Instruction {
+    instruction(
+        NC_TOKEN_PROGRAM_ID,
+        accounts::PreviewTransfer {
+            state: pda::nc_token_state().0,
+            from_account,
+            to_account,
+            from_authority,
+            sell_window: pda::sell_window(&from_authority).0,
+        },
+        ix::PreviewTransfer { amount, proofs },
+    )
+}
+
+fn view_accounts() -> accounts::ViewOnly {
+    accounts::ViewOnly {
+        state: pda::nc_token_state().0,
+    }
+}
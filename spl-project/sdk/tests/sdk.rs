@@ -0,0 +1,212 @@
+// PDAs, instruction builders, account and event decoding
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::{
+    AccountSerialize, AnchorDeserialize, AnchorSerialize, Discriminator, Event as _,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use nc_sdk::events::{parse_logs, Event, GovernanceEvent, NcTokenEvent};
+use nc_sdk::governance::{self as gov, Proposal, ProposalData};
+use nc_sdk::multisig_governance::{
+    self, instruction as gov_ix, ListBatch, ProposalMetadata, TransactionType,
+};
+use nc_sdk::nc_token::{self, AddressList, BlacklistDetails, ListUpdate, SellBucket, SellWindow};
+use nc_sdk::{accounts, pda, GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+
+fn metadata() -> ProposalMetadata {
+    ProposalMetadata {
+        title: "Restrict the old bridge".to_string(),
+        description_uri: "https://example.com/proposals/1".to_string(),
+        description_hash: [7; 32],
+    }
+}
+
+#[test]
+fn pdas_match_the_program_seeds() {
+    assert_eq!(pda::governance().0, nc_token::governance_pda());
+    let (proposal, bump) = pda::proposal(5);
+    assert_eq!(
+        Pubkey::create_program_address(
+            &[b"proposal", &5u64.to_le_bytes(), &[bump]],
+            &GOVERNANCE_PROGRAM_ID
+        ),
+        Ok(proposal)
+    );
+    let owner = Pubkey::new_unique();
+    assert_ne!(
+        pda::approval(&proposal, &owner).0,
+        pda::approval(&pda::proposal(6).0, &owner).0
+    );
+    assert_eq!(
+        pda::nc_token_state().0,
+        Pubkey::find_program_address(&[b"nc_token_state"], &NC_TOKEN_PROGRAM_ID).0
+    );
+}
+
+#[test]
+fn queue_builds_the_instruction_for_the_proposal_kind() {
+    let signer = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+    let ix = gov::queue(
+        signer,
+        9,
+        Proposal::Restrict {
+            account,
+            value: true,
+        },
+        metadata(),
+    );
+
+    assert_eq!(ix.program_id, GOVERNANCE_PROGRAM_ID);
+    assert_eq!(
+        ix.accounts,
+        vec![
+            AccountMeta::new(pda::governance().0, false),
+            AccountMeta::new(pda::proposal(9).0, false),
+            AccountMeta::new(signer, true),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+            AccountMeta::new_readonly(pda::event_authority(&GOVERNANCE_PROGRAM_ID).0, false),
+            AccountMeta::new_readonly(GOVERNANCE_PROGRAM_ID, false),
+        ]
+    );
+    let args = ix
+        .data
+        .strip_prefix(gov_ix::SetRestricted::DISCRIMINATOR)
+        .unwrap();
+    let args = gov_ix::SetRestricted::try_from_slice(args).unwrap();
+    assert_eq!((args.account, args.value), (account, true));
+    assert!(args.metadata == metadata());
+}
+
+#[test]
+fn approvals_and_remaining_accounts_follow_the_fixed_accounts() {
+    let approver = Pubkey::new_unique();
+    let others = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let remaining = gov::approval_accounts(3, &others);
+    let ix = gov::approve_transaction(approver, 3, None, remaining.clone());
+
+    let proposal = pda::proposal(3).0;
+    assert_eq!(ix.accounts[2].pubkey, pda::approval(&proposal, &approver).0);
+    assert_eq!(ix.accounts[ix.accounts.len() - 2..], remaining[..]);
+    assert!(remaining
+        .iter()
+        .all(|meta| !meta.is_signer && !meta.is_writable));
+
+    // Co-signing guardians are signers after the fixed accounts
+    let co_signer = Pubkey::new_unique();
+    let pause = gov::set_emergency_pause(approver, &[co_signer], 3600, "drill".to_string());
+    assert_eq!(
+        pause.accounts.last(),
+        Some(&AccountMeta::new_readonly(co_signer, true))
+    );
+}
+
+#[test]
+fn decodes_accounts_with_their_discriminator() {
+    let window = SellWindow {
+        start_time: 1_700_000_000,
+        start_balance: 5_000,
+        buckets: [SellBucket::default(); 24],
+        bump: 254,
+    };
+    let mut data = Vec::new();
+    window.try_serialize(&mut data).unwrap();
+
+    let decoded = accounts::sell_window(&data).unwrap();
+    assert_eq!(
+        (decoded.start_time, decoded.start_balance, decoded.bump),
+        (1_700_000_000, 5_000, 254)
+    );
+    // Another account type's discriminator is rejected
+    assert!(accounts::governance(&data).is_err());
+}
+
+#[test]
+fn attributes_logged_events_to_the_program_that_emitted_them() {
+    let account = Pubkey::new_unique();
+    let program_data = |event: Vec<u8>| format!("Program data: {}", STANDARD.encode(event));
+    let logs = [
+        format!("Program {} invoke [1]", GOVERNANCE_PROGRAM_ID),
+        format!("Program {} invoke [2]", NC_TOKEN_PROGRAM_ID),
+        program_data(
+            nc_token::RestrictedSet {
+                account,
+                value: true,
+            }
+            .data(),
+        ),
+        format!("Program {} success", NC_TOKEN_PROGRAM_ID),
+        program_data(
+            multisig_governance::AddressRestricted {
+                account,
+                status: true,
+            }
+            .data(),
+        ),
+        format!("Program {} success", GOVERNANCE_PROGRAM_ID),
+        // Payloads logged outside either program are skipped
+        format!("Program {} invoke [1]", Pubkey::new_unique()),
+        program_data(
+            nc_token::RestrictedSet {
+                account,
+                value: false,
+            }
+            .data(),
+        ),
+    ];
+
+    let events = parse_logs(&logs);
+    assert_eq!(events.len(), 2);
+    assert!(matches!(
+        &events[0],
+        Event::NcToken(NcTokenEvent::RestrictedSet(e)) if e.account == account && e.value
+    ));
+    assert!(matches!(
+        &events[1],
+        Event::Governance(GovernanceEvent::AddressRestricted(e)) if e.account == account && e.status
+    ));
+    assert_eq!(events[1].name(), "AddressRestricted");
+}
+
+#[test]
+fn decodes_proposal_data_by_transaction_type() {
+    let account = Pubkey::new_unique();
+    let token_accounts = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let details = BlacklistDetails {
+        reason_code: 3,
+        evidence_hash: [9; 32],
+        proposed_by: Pubkey::new_unique(),
+        expires_at: 1_800_000_000,
+    };
+    // Laid out the way set_blacklist stores it
+    let data = (account, true, details, token_accounts.clone())
+        .try_to_vec()
+        .unwrap();
+    let decoded = ProposalData::decode(TransactionType::Blacklist, &data).unwrap();
+    assert!(matches!(
+        &decoded,
+        ProposalData::Blacklist { account: a, value: true, details: d, .. }
+            if *a == account && d.reason_code == 3 && d.expires_at == 1_800_000_000
+    ));
+    assert_eq!(decoded.freeze_token_accounts(), token_accounts);
+
+    let batch = ListBatch {
+        list: AddressList::Restricted,
+        details: BlacklistDetails::default(),
+        updates: vec![ListUpdate {
+            account,
+            value: false,
+        }],
+    };
+    let decoded =
+        ProposalData::decode(TransactionType::ListBatch, &batch.try_to_vec().unwrap()).unwrap();
+    assert!(matches!(
+        &decoded,
+        ProposalData::ListBatch(b) if b.updates.len() == 1 && b.updates[0].account == account
+    ));
+    assert!(decoded.freeze_token_accounts().is_empty());
+
+    // Data that doesn't match its type is an error, not a guess
+    assert!(ProposalData::decode(TransactionType::Restrict, &[1, 2, 3]).is_err());
+}
@@ -19,8 +19,10 @@ import {
   PublicKey,
   Transaction,
   sendAndConfirmTransaction,
+  BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
 } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
 
 describe("NC Token & Governance", () => {
   // Configure the client to use the local cluster
@@ -56,6 +58,18 @@ describe("NC Token & Governance", () => {
   const TRANSFER_AMOUNT = 100 * 10 ** MINT_DECIMALS; // 100 tokens
   const SELL_AMOUNT = 60 * 10 ** MINT_DECIMALS; // 60 tokens (6% - should fail)
 
+  // Title, description URI and SHA-256 of the description for a proposal
+  const proposalMetadata = (title: string) => {
+    const description = `${title} (test)`;
+    return {
+      title,
+      descriptionUri: `https://example.com/proposals/${encodeURIComponent(title)}`,
+      descriptionHash: Array.from(
+        createHash("sha256").update(description).digest()
+      ),
+    };
+  };
+
   before(async () => {
     // Generate keypairs for testing
     authority = Keypair.generate();
@@ -118,13 +132,68 @@ describe("NC Token & Governance", () => {
     );
   });
 
+  // PDA of the proposal queued as `txId`
+  const proposalPda = (txId: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), txId.toArrayLike(Buffer, "le", 8)],
+      governanceProgram.programId
+    )[0];
+
+  // PDA recording `owner`'s approval of a proposal
+  const approvalPda = (txId: anchor.BN, owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("approval"), proposalPda(txId).toBuffer(), owner.toBuffer()],
+      governanceProgram.programId
+    )[0];
+
+  const sleep = (ms: number) =>
+    new Promise((resolve) => setTimeout(resolve, ms));
+
+  // Admin instruction signed by the governance admin
+  const admin = (method: any) =>
+    method
+      .accounts({
+        governance: governancePda,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+
+  // ProgramData account holding a program's upgrade authority
+  const programDataFor = (programId: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+    )[0];
+
   describe("Governance", () => {
+    it("Rejects initialize from a non-upgrade-authority signer", async () => {
+      try {
+        await governanceProgram.methods
+          .initialize()
+          .accounts({
+            governance: governancePda,
+            authority: authority.publicKey,
+            program: governanceProgram.programId,
+            programData: programDataFor(governanceProgram.programId),
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc();
+        expect.fail("initialize should be gated to the upgrade authority");
+      } catch (err) {
+        expect(err.toString()).to.include("NotUpgradeAuthority");
+      }
+    });
+
     it("Initializes governance", async () => {
+      // anchor test deploys with the provider wallet as upgrade authority
       const tx = await governanceProgram.methods
         .initialize()
         .accounts({
           governance: governancePda,
-          authority: authority.publicKey,
+          authority: provider.wallet.publicKey,
+          program: governanceProgram.programId,
+          programData: programDataFor(governanceProgram.programId),
           systemProgram: SystemProgram.programId,
         })
         .rpc();
@@ -139,10 +208,10 @@ describe("NC Token & Governance", () => {
 
     it("Sets token in governance", async () => {
       const tx = await governanceProgram.methods
-        .setToken(ncTokenProgram.programId)
+        .setToken(ncTokenProgram.programId, ncTokenStatePda)
         .accounts({
           governance: governancePda,
-          authority: authority.publicKey,
+          authority: provider.wallet.publicKey,
         })
         .rpc();
 
@@ -153,36 +222,105 @@ describe("NC Token & Governance", () => {
       expect(governance.ncToken.toString()).to.equal(
         ncTokenProgram.programId.toString()
       );
+      expect(governance.ncTokenState.toString()).to.equal(
+        ncTokenStatePda.toString()
+      );
+    });
+
+    it("Adds owners and bumps the config nonce", async () => {
+      for (const owner of [signer1, signer2]) {
+        await governanceProgram.methods
+          .addOwner(owner.publicKey)
+          .accounts({
+            governance: governancePda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      }
+
+      const governance = await governanceProgram.account.governance.fetch(
+        governancePda
+      );
+      expect(governance.owners.map((o) => o.toString())).to.deep.equal([
+        signer1.publicKey.toString(),
+        signer2.publicKey.toString(),
+      ]);
+      // two add_owner calls
+      expect(governance.configNonce.toNumber()).to.equal(2);
     });
 
     it("Sets required approvals", async () => {
       const tx = await governanceProgram.methods
-        .setRequiredApprovals(3)
+        .setRequiredApprovals(2)
         .accounts({
           governance: governancePda,
-          authority: authority.publicKey,
+          authority: provider.wallet.publicKey,
         })
         .rpc();
 
       const governance = await governanceProgram.account.governance.fetch(
         governancePda
       );
-      expect(governance.requiredApprovals).to.equal(3);
+      expect(governance.requiredApprovals).to.equal(2);
+      // two add_owner calls + set_required_approvals
+      expect(governance.configNonce.toNumber()).to.equal(3);
+    });
+
+    it("Rejects more required approvals than owners", async () => {
+      try {
+        await governanceProgram.methods
+          .setRequiredApprovals(3)
+          .accounts({
+            governance: governancePda,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+        expect.fail("required approvals should not exceed the owner count");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidApprovalRequirement");
+      }
     });
   });
 
   describe("NC Token", () => {
+    it("Rejects a governance key other than the multisig PDA", async () => {
+      try {
+        await ncTokenProgram.methods
+          .initialize(
+            governanceProgram.programId,
+            bridge.publicKey,
+            treasury.publicKey,
+            bond.publicKey,
+            mint.publicKey
+          )
+          .accounts({
+            state: ncTokenStatePda,
+            authority: provider.wallet.publicKey,
+            program: ncTokenProgram.programId,
+            programData: programDataFor(ncTokenProgram.programId),
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("initialize should require the governance PDA");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidGovernance");
+      }
+    });
+
     it("Initializes NC Token", async () => {
       const tx = await ncTokenProgram.methods
         .initialize(
-          governanceProgram.programId,
+          governancePda, // the PDA governance signs its CPIs with
           bridge.publicKey,
           treasury.publicKey,
-          bond.publicKey
+          bond.publicKey,
+          mint.publicKey
         )
         .accounts({
           state: ncTokenStatePda,
-          authority: authority.publicKey,
+          authority: provider.wallet.publicKey,
+          program: ncTokenProgram.programId,
+          programData: programDataFor(ncTokenProgram.programId),
           systemProgram: SystemProgram.programId,
         })
         .rpc();
@@ -190,12 +328,11 @@ describe("NC Token & Governance", () => {
       const state = await ncTokenProgram.account.nCTokenState.fetch(
         ncTokenStatePda
       );
-      expect(state.governance.toString()).to.equal(
-        governanceProgram.programId.toString()
-      );
+      expect(state.governance.toString()).to.equal(governancePda.toString());
       expect(state.bridge.toString()).to.equal(bridge.publicKey.toString());
       expect(state.treasury.toString()).to.equal(treasury.publicKey.toString());
       expect(state.bond.toString()).to.equal(bond.publicKey.toString());
+      expect(state.mint.toString()).to.equal(mint.publicKey.toString());
       expect(state.emergencyPaused).to.be.false;
     });
 
@@ -214,7 +351,7 @@ describe("NC Token & Governance", () => {
           mint.publicKey,
           MINT_DECIMALS,
           authority.publicKey,
-          null
+          ncTokenStatePda // freezes blacklisted holders' token accounts
         )
       );
 
@@ -306,7 +443,11 @@ describe("NC Token & Governance", () => {
     it("Enforces 5% sell limit when selling to liquidity pool", async () => {
       // Set liquidity pool
       const setPoolTx = await governanceProgram.methods
-        .setLiquidityPool(liquidityPool.publicKey, true)
+        .setLiquidityPool(
+          liquidityPool.publicKey,
+          true,
+          proposalMetadata("Add liquidity pool")
+        )
         .accounts({
           governance: governancePda,
           authority: signer1.publicKey,
@@ -315,7 +456,7 @@ describe("NC Token & Governance", () => {
 
       // Approve transaction
       await governanceProgram.methods
-        .approveTransaction(new anchor.BN(1))
+        .approveTransaction(new anchor.BN(1), null)
         .accounts({
           governance: governancePda,
           proposal: governancePda, // Simplified
@@ -383,7 +524,15 @@ describe("NC Token & Governance", () => {
     it("Blocks blacklisted addresses from interacting with restricted addresses", async () => {
       // Blacklist user
       const blacklistTx = await governanceProgram.methods
-        .setBlacklist(user.publicKey, true)
+        .setBlacklist(
+          user.publicKey,
+          true,
+          1, // reason code
+          Array.from(createHash("sha256").update("evidence").digest()),
+          new anchor.BN(0), // no expiry
+          [], // token accounts to freeze
+          proposalMetadata("Blacklist user")
+        )
         .accounts({
           governance: governancePda,
           authority: signer1.publicKey,
@@ -392,7 +541,11 @@ describe("NC Token & Governance", () => {
 
       // Set bridge as restricted
       const restrictTx = await governanceProgram.methods
-        .setRestricted(bridge.publicKey, true)
+        .setRestricted(
+          bridge.publicKey,
+          true,
+          proposalMetadata("Restrict bridge")
+        )
         .accounts({
           governance: governancePda,
           authority: signer1.publicKey,
@@ -446,9 +599,18 @@ describe("NC Token & Governance", () => {
     });
 
     it("Emergency pause only freezes protocol modules, not P2P transfers", async () => {
-      // Set emergency pause
+      // Single-guardian committee for the fast path
+      await governanceProgram.methods
+        .setGuardians([authority.publicKey], 1)
+        .accounts({
+          governance: governancePda,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      // Set emergency pause for one hour
       const pauseTx = await governanceProgram.methods
-        .setEmergencyPause()
+        .setEmergencyPause(new anchor.BN(60 * 60), "Incident response drill")
         .accounts({
           governance: governancePda,
           ncTokenProgram: ncTokenProgram.programId,
@@ -461,6 +623,7 @@ describe("NC Token & Governance", () => {
         ncTokenStatePda
       );
       expect(state.emergencyPaused).to.be.true;
+      expect(state.pauseExpiresAt.toNumber()).to.be.greaterThan(0);
 
       // P2P transfer should still work
       const normalUser = Keypair.generate();
@@ -507,5 +670,784 @@ describe("NC Token & Governance", () => {
       }
     });
   });
+
+  describe("Proposal execution", () => {
+    const COOLDOWN_SECONDS = 10;
+
+    // Queues the proposal `method` builds, returning its id
+    const queue = async (method: any) => {
+      const txId = (
+        await governanceProgram.account.governance.fetch(governancePda)
+      ).nextTransactionId;
+      await method
+        .accounts({
+          governance: governancePda,
+          proposal: proposalPda(txId),
+          signer: signer1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer1])
+        .rpc();
+      return txId;
+    };
+
+    const queueRestrict = (
+      target: PublicKey,
+      metadata = proposalMetadata("Restrict a test address")
+    ) => queue(governanceProgram.methods.setRestricted(target, true, metadata));
+
+    const approve = (txId: anchor.BN, owner: Keypair) =>
+      governanceProgram.methods
+        .approveTransaction(txId, null)
+        .accounts({
+          governance: governancePda,
+          proposal: proposalPda(txId),
+          approval: approvalPda(txId, owner.publicKey),
+          ncTokenProgram: ncTokenProgram.programId,
+          ncTokenState: ncTokenStatePda,
+          approver: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+    // Approval PDAs of `owners`, as execution's remaining accounts
+    const approvalAccounts = (txId: anchor.BN, owners: Keypair[]) =>
+      owners.map((owner) => ({
+        pubkey: approvalPda(txId, owner.publicKey),
+        isWritable: false,
+        isSigner: false,
+      }));
+
+    const fetchProposal = (txId: anchor.BN) =>
+      governanceProgram.account.pendingTransaction.fetch(proposalPda(txId));
+
+    // Approves with both owners, waits out the cooldown and cranks, passing
+    // `accounts` (token accounts to freeze and the like) after the approvals
+    const pass = async (
+      txId: anchor.BN,
+      accounts: anchor.web3.AccountMeta[] = []
+    ) => {
+      await approve(txId, signer1);
+      await approve(txId, signer2);
+      await sleep((COOLDOWN_SECONDS + 2) * 1000);
+      await crankApproved(txId, accounts);
+    };
+
+    // Cranks a proposal both owners approved
+    const crankApproved = (
+      txId: anchor.BN,
+      accounts: anchor.web3.AccountMeta[] = []
+    ) =>
+      governanceProgram.methods
+        .crankExecute(txId)
+        .accounts({
+          governance: governancePda,
+          proposal: proposalPda(txId),
+          ncTokenProgram: ncTokenProgram.programId,
+          ncTokenState: ncTokenStatePda,
+          cranker: provider.wallet.publicKey,
+        })
+        .remainingAccounts([
+          ...approvalAccounts(txId, [signer1, signer2]),
+          ...accounts,
+        ])
+        .rpc();
+
+    // Creates `owner`'s associated NC token account
+    const createTokenAccount = async (owner: PublicKey) => {
+      const account = await getAssociatedTokenAddress(mint.publicKey, owner);
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          createAssociatedTokenAccountInstruction(
+            provider.wallet.publicKey,
+            account,
+            owner,
+            mint.publicKey
+          )
+        )
+      );
+      return account;
+    };
+
+    it("Lets anyone crank an approved proposal once its cooldown has passed", async () => {
+      // Both owners approve, within a cooldown short enough to wait out
+      await admin(governanceProgram.methods.setRequiredApprovals(2));
+      await admin(
+        governanceProgram.methods.setCooldownPeriod(
+          new anchor.BN(COOLDOWN_SECONDS)
+        )
+      );
+
+      const target = Keypair.generate().publicKey;
+      const txId = await queueRestrict(target);
+      // Approved inside the cooldown, so the last approval can't
+      // auto-execute
+      await approve(txId, signer1);
+      await approve(txId, signer2);
+
+      // A keeper with no approval rights and no SOL
+      const cranker = Keypair.generate();
+      const crank = (approvers = [signer1, signer2]) =>
+        governanceProgram.methods
+          .crankExecute(txId)
+          .accounts({
+            governance: governancePda,
+            proposal: proposalPda(txId),
+            ncTokenProgram: ncTokenProgram.programId,
+            ncTokenState: ncTokenStatePda,
+            cranker: cranker.publicKey,
+          })
+          .remainingAccounts(approvalAccounts(txId, approvers))
+          .signers([cranker])
+          .rpc();
+
+      try {
+        await crank();
+        expect.fail("crank should wait out the cooldown");
+      } catch (err: any) {
+        expect(err.toString()).to.include("CooldownNotExpired");
+      }
+
+      await sleep((COOLDOWN_SECONDS + 2) * 1000);
+      // Approvals are counted from the records passed in, each once
+      for (const [approvers, error] of [
+        [[signer1], "InsufficientApprovals"],
+        [[signer1, signer1], "DuplicateApproval"],
+      ] as [Keypair[], string][]) {
+        try {
+          await crank(approvers);
+          expect.fail(`crank should fail with ${error}`);
+        } catch (err: any) {
+          expect(err.toString()).to.include(error);
+        }
+      }
+      await crank();
+
+      expect((await fetchProposal(txId)).status).to.deep.equal({
+        crankExecuted: {},
+      });
+      const state = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      expect(state.restrictedList.map((k) => k.toString())).to.include(
+        target.toString()
+      );
+
+      // Executed once only
+      try {
+        await crank();
+        expect.fail("an executed proposal can't be cranked again");
+      } catch (err: any) {
+        expect(err.toString()).to.include("TransactionNotPending");
+      }
+    });
+
+    it("Records each approval in a PDA of its own", async () => {
+      const txId = await queueRestrict(Keypair.generate().publicKey);
+      await approve(txId, signer1);
+
+      const record = await governanceProgram.account.approvalRecord.fetch(
+        approvalPda(txId, signer1.publicKey)
+      );
+      expect(record.proposal.toString()).to.equal(
+        proposalPda(txId).toString()
+      );
+      expect(record.owner.toString()).to.equal(signer1.publicKey.toString());
+      expect((await fetchProposal(txId)).approvalCount).to.equal(1);
+
+      // The system program refuses to create the record again
+      try {
+        await approve(txId, signer1);
+        expect.fail("an owner can't approve twice");
+      } catch (err: any) {
+        expect(err.logs?.join("\n") ?? err.toString()).to.include(
+          "already in use"
+        );
+      }
+    });
+
+    it("Stores a title, description URI and description hash with each proposal", async () => {
+      const metadata = proposalMetadata("Restrict the old bridge");
+      const txId = await queueRestrict(Keypair.generate().publicKey, metadata);
+      const proposal = await fetchProposal(txId);
+      expect(proposal.title).to.equal(metadata.title);
+      expect(proposal.descriptionUri).to.equal(metadata.descriptionUri);
+      expect(proposal.descriptionHash).to.deep.equal(metadata.descriptionHash);
+
+      try {
+        await queueRestrict(Keypair.generate().publicKey, {
+          ...metadata,
+          title: "x".repeat(65),
+        });
+        expect.fail("titles are capped at 64 bytes");
+      } catch (err: any) {
+        expect(err.toString()).to.include("TitleTooLong");
+      }
+    });
+
+    it("Sizes proposals to their payload and refunds the rent on close", async () => {
+      const txId = await queueRestrict(Keypair.generate().publicKey);
+      const size = async () =>
+        (await connection.getAccountInfo(proposalPda(txId))).data.length;
+      const queued = await size();
+
+      // The initiator or an owner may close
+      const close = (closer: Keypair, rejector: PublicKey | null = null) =>
+        governanceProgram.methods
+          .closeProposal(txId)
+          .accounts({
+            governance: governancePda,
+            proposal: proposalPda(txId),
+            initiator: signer1.publicKey,
+            rejector,
+            closer: closer.publicKey,
+          })
+          .signers([closer])
+          .rpc();
+      try {
+        await close(signer1);
+        expect.fail("a pending proposal can't be closed");
+      } catch (err: any) {
+        expect(err.toString()).to.include("TransactionStillPending");
+      }
+
+      // The rejector pays for the room the reason takes
+      const reason = "Target is a market maker";
+      const beforeReject = await connection.getBalance(signer2.publicKey);
+      await governanceProgram.methods
+        .rejectTransaction(txId, reason)
+        .accounts({
+          governance: governancePda,
+          proposal: proposalPda(txId),
+          approver: signer2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer2])
+        .rpc();
+      expect(await size()).to.equal(queued + reason.length);
+      const rejectionRent =
+        beforeReject - (await connection.getBalance(signer2.publicKey));
+      expect(rejectionRent).to.be.greaterThan(0);
+
+      try {
+        await close(Keypair.generate(), signer2.publicKey);
+        expect.fail("only the initiator or an owner can close");
+      } catch (err: any) {
+        expect(err.toString()).to.include("Unauthorized");
+      }
+
+      // The rejector gets its share back, the initiator the rest
+      const rent = await connection.getBalance(proposalPda(txId));
+      const initiatorBefore = await connection.getBalance(signer1.publicKey);
+      const rejectorBefore = await connection.getBalance(signer2.publicKey);
+      await close(signer2, signer2.publicKey);
+      expect(await connection.getBalance(signer2.publicKey)).to.equal(
+        rejectorBefore + rejectionRent
+      );
+      expect(await connection.getBalance(signer1.publicKey)).to.equal(
+        initiatorBefore + rent - rejectionRent
+      );
+      expect(await connection.getAccountInfo(proposalPda(txId))).to.be.null;
+    });
+
+    it("Stops counting approvals made under an older owner set", async () => {
+      // A long cooldown keeps the proposal pending throughout
+      await admin(
+        governanceProgram.methods.setCooldownPeriod(new anchor.BN(60 * 60))
+      );
+      await admin(governanceProgram.methods.setRequireReapproval(true));
+      const txId = await queueRestrict(Keypair.generate().publicKey);
+      await approve(txId, signer1);
+
+      // Any owner change bumps the config nonce
+      const passing = Keypair.generate().publicKey;
+      await admin(governanceProgram.methods.addOwner(passing));
+      await admin(governanceProgram.methods.removeOwner(passing));
+
+      const governance = await governanceProgram.account.governance.fetch(
+        governancePda
+      );
+      const record = () =>
+        governanceProgram.account.approvalRecord.fetch(
+          approvalPda(txId, signer1.publicKey)
+        );
+      expect((await record()).configNonce.toNumber()).to.be.lessThan(
+        governance.configNonce.toNumber()
+      );
+      // A new approval is given under the current config and carries the
+      // proposal over with it
+      await approve(txId, signer2);
+      expect((await fetchProposal(txId)).configNonce.toNumber()).to.equal(
+        governance.configNonce.toNumber()
+      );
+
+      // Re-approving brings the stale approval up to the current config
+      const reapprove = () =>
+        governanceProgram.methods
+          .reapproveTransaction(txId)
+          .accounts({
+            governance: governancePda,
+            proposal: proposalPda(txId),
+            approval: approvalPda(txId, signer1.publicKey),
+            approver: signer1.publicKey,
+          })
+          .signers([signer1])
+          .rpc();
+      await reapprove();
+      expect((await record()).configNonce.toNumber()).to.equal(
+        governance.configNonce.toNumber()
+      );
+      try {
+        await reapprove();
+        expect.fail("a current approval can't be refreshed");
+      } catch (err: any) {
+        expect(err.toString()).to.include("AlreadyApproved");
+      }
+    });
+
+    it("Pauses one module at a time through a proposal", async () => {
+      await admin(
+        governanceProgram.methods.setCooldownPeriod(
+          new anchor.BN(COOLDOWN_SECONDS)
+        )
+      );
+      const setP2pPause = (paused: boolean) =>
+        queue(
+          governanceProgram.methods.setModulePause(
+            { p2p: {} },
+            paused,
+            proposalMetadata(paused ? "Pause P2P" : "Resume P2P")
+          )
+        );
+      // A plain wallet-to-wallet transfer, between two unlisted holders
+      const friendTokenAccount = await createTokenAccount(
+        Keypair.generate().publicKey
+      );
+      const p2pTransfer = () =>
+        ncTokenProgram.methods
+          .transfer(new anchor.BN(10 ** MINT_DECIMALS))
+          .accounts({
+            state: ncTokenStatePda,
+            mint: mint.publicKey,
+            fromAccount: recipientTokenAccount,
+            toAccount: friendTokenAccount,
+            fromAuthority: recipient.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([recipient])
+          .rpc();
+
+      // The emergency pause itself can't be set this way
+      try {
+        await queue(
+          governanceProgram.methods.setModulePause(
+            { protocol: {} },
+            true,
+            proposalMetadata("Pause the protocol")
+          )
+        );
+        expect.fail("the protocol pause belongs to the guardians");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidTransactionType");
+      }
+
+      await pass(await setP2pPause(true));
+      const state = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      expect(state.pausedModules).to.equal(1 << 6); // PauseModule::P2p
+      try {
+        await p2pTransfer();
+        expect.fail("P2P transfers are paused");
+      } catch (err: any) {
+        expect(err.toString()).to.include("Paused");
+      }
+
+      await pass(await setP2pPause(false));
+      await p2pTransfer();
+    });
+
+    it("Records why an address is blacklisted and lifts the entry at expiry", async () => {
+      const target = Keypair.generate().publicKey;
+      const evidenceHash = Array.from(
+        createHash("sha256").update("case 2024-117").digest()
+      );
+      const expiresAt = Math.floor(Date.now() / 1000) + 45;
+      await pass(
+        await queue(
+          governanceProgram.methods.setBlacklist(
+            target,
+            true,
+            7, // reason code
+            evidenceHash,
+            new anchor.BN(expiresAt),
+            [],
+            proposalMetadata("Blacklist pending review")
+          )
+        )
+      );
+
+      const state = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      const entry = state.blacklisted.find(
+        (e) => e.account.toString() === target.toString()
+      );
+      expect(entry.details.reasonCode).to.equal(7);
+      expect(entry.details.evidenceHash).to.deep.equal(evidenceHash);
+      expect(entry.details.proposedBy.toString()).to.equal(
+        signer1.publicKey.toString()
+      );
+      expect(entry.details.expiresAt.toNumber()).to.equal(expiresAt);
+
+      const isBlacklisted = () =>
+        ncTokenProgram.methods
+          .isBlacklisted(target)
+          .accounts({ state: ncTokenStatePda })
+          .view();
+      expect(await isBlacklisted()).to.be.true;
+      await sleep((expiresAt + 2) * 1000 - Date.now());
+      expect(await isBlacklisted()).to.be.false;
+    });
+
+    it("Freezes a blacklisted holder's token account and thaws it when lifted", async () => {
+      const holder = Keypair.generate().publicKey;
+      const holderTokenAccount = await createTokenAccount(holder);
+      const setBlacklist = (value: boolean) =>
+        queue(
+          governanceProgram.methods.setBlacklist(
+            holder,
+            value,
+            2, // reason code
+            Array.from(createHash("sha256").update("case 2024-118").digest()),
+            new anchor.BN(0),
+            [holderTokenAccount],
+            proposalMetadata(value ? "Blacklist and freeze" : "Lift and thaw")
+          )
+        );
+      // The token account, its mint and the token program
+      const freezeAccounts = [
+        { pubkey: holderTokenAccount, isWritable: true, isSigner: false },
+        { pubkey: mint.publicKey, isWritable: false, isSigner: false },
+        { pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+      ];
+
+      await pass(await setBlacklist(true), freezeAccounts);
+      expect((await getAccount(connection, holderTokenAccount)).isFrozen).to.be
+        .true;
+
+      await pass(await setBlacklist(false), freezeAccounts);
+      expect((await getAccount(connection, holderTokenAccount)).isFrozen).to.be
+        .false;
+    });
+
+    it("Applies a list batch in chunks, one per execution call", async () => {
+      const updates = (count: number) =>
+        Array.from({ length: count }, () => ({
+          account: Keypair.generate().publicKey,
+          value: true,
+        }));
+      const first = updates(15);
+      const second = updates(10);
+      const txId = await queue(
+        governanceProgram.methods.setListBatch(
+          { restricted: {} },
+          first,
+          0,
+          Array(32).fill(0),
+          new anchor.BN(0),
+          proposalMetadata("Restrict market makers")
+        )
+      );
+      // More entries than one transaction carries
+      const append = (entries: typeof second) =>
+        governanceProgram.methods
+          .appendListBatch(txId, entries)
+          .accounts({
+            governance: governancePda,
+            proposal: proposalPda(txId),
+            initiator: signer1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([signer1])
+          .rpc();
+      await append(second);
+
+      await approve(txId, signer1);
+      try {
+        await append(updates(1));
+        expect.fail("an approved batch can't grow");
+      } catch (err: any) {
+        expect(err.toString()).to.include("BatchLocked");
+      }
+      await approve(txId, signer2);
+      await sleep((COOLDOWN_SECONDS + 2) * 1000);
+
+      // BATCH_CHUNK (20) entries, then the rest
+      await crankApproved(txId);
+      let proposal = await fetchProposal(txId);
+      expect(proposal.executedCount).to.equal(20);
+      expect(proposal.status).to.deep.equal({ pending: {} });
+      await crankApproved(txId);
+      proposal = await fetchProposal(txId);
+      expect(proposal.executedCount).to.equal(25);
+      expect(proposal.status).to.deep.equal({ crankExecuted: {} });
+
+      const state = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      const restricted = state.restrictedList.map((k) => k.toString());
+      for (const update of [...first, ...second]) {
+        expect(restricted).to.include(update.account.toString());
+      }
+    });
+
+    it("Exempts holders in the no-sell-limit Merkle root that carry a proof", async () => {
+      // Same tree as the program's merkle module: sorted members between
+      // an all-zero and an all-0xff sentinel, padded with the high one
+      const leafHash = (key: PublicKey) =>
+        createHash("sha256")
+          .update(Buffer.from([0]))
+          .update(key.toBuffer())
+          .digest();
+      const nodeHash = (left: Buffer, right: Buffer) =>
+        createHash("sha256")
+          .update(Buffer.from([1]))
+          .update(left)
+          .update(right)
+          .digest();
+      const members = [
+        recipient.publicKey,
+        ...Array.from({ length: 4 }, () => Keypair.generate().publicKey),
+      ].sort((a, b) => Buffer.compare(a.toBuffer(), b.toBuffer()));
+      const leaves = [
+        new PublicKey(Buffer.alloc(32, 0)),
+        ...members,
+        new PublicKey(Buffer.alloc(32, 0xff)),
+      ];
+      while (leaves.length & (leaves.length - 1)) {
+        leaves.push(new PublicKey(Buffer.alloc(32, 0xff)));
+      }
+      const levels = [leaves.map(leafHash)];
+      while (levels[levels.length - 1].length > 1) {
+        const level = levels[levels.length - 1];
+        levels.push(
+          Array.from({ length: level.length / 2 }, (_, i) =>
+            nodeHash(level[2 * i], level[2 * i + 1])
+          )
+        );
+      }
+      const root = levels[levels.length - 1][0];
+      const index = leaves.findIndex((k) => k.equals(recipient.publicKey));
+      const proof = {
+        index,
+        siblings: levels
+          .slice(0, -1)
+          .map((level, depth) => Array.from(level[(index >> depth) ^ 1])),
+      };
+
+      // Pools are listed by their token account
+      const poolTokenAccount = await createTokenAccount(
+        Keypair.generate().publicKey
+      );
+      await pass(
+        await queue(
+          governanceProgram.methods.setLiquidityPool(
+            poolTokenAccount,
+            true,
+            proposalMetadata("Add a liquidity pool")
+          )
+        )
+      );
+      // Over 5% of the recipient's balance
+      const sellAmount = new anchor.BN(10 * 10 ** MINT_DECIMALS);
+      const sellAccounts = {
+        state: ncTokenStatePda,
+        mint: mint.publicKey,
+        fromAccount: recipientTokenAccount,
+        toAccount: poolTokenAccount,
+        fromAuthority: recipient.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      };
+      try {
+        await ncTokenProgram.methods
+          .transfer(sellAmount)
+          .accounts(sellAccounts)
+          .signers([recipient])
+          .rpc();
+        expect.fail("the recipient isn't exempt yet");
+      } catch (err: any) {
+        expect(err.toString()).to.include("SellLimitExceeded");
+      }
+
+      // Only the blacklist and no-sell-limit lists have a Merkle mode
+      try {
+        await queue(
+          governanceProgram.methods.setMerkleRoot(
+            { restricted: {} },
+            Array.from(root),
+            proposalMetadata("Restricted list root")
+          )
+        );
+        expect.fail("the restricted list has no Merkle mode");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidTransactionType");
+      }
+      await pass(
+        await queue(
+          governanceProgram.methods.setMerkleRoot(
+            { noSellLimit: {} },
+            Array.from(root),
+            proposalMetadata("Market maker exemptions")
+          )
+        )
+      );
+      const state = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      expect(Buffer.from(state.noSellLimitRoot).equals(root)).to.be.true;
+
+      await ncTokenProgram.methods
+        .transferWithProofs(sellAmount, {
+          fromNotBlacklisted: null,
+          toNotBlacklisted: null,
+          fromSellExempt: proof,
+        })
+        .accounts(sellAccounts)
+        .signers([recipient])
+        .rpc();
+      const poolAccount = await getAccount(connection, poolTokenAccount);
+      expect(poolAccount.amount.toString()).to.equal(sellAmount.toString());
+    });
+
+    it("Rotates a module address and carries its list entries over", async () => {
+      const rotateTreasury = (address: PublicKey) =>
+        queue(
+          governanceProgram.methods.setModuleAddress(
+            { treasury: {} },
+            address,
+            proposalMetadata("Rotate the treasury")
+          )
+        );
+      try {
+        await rotateTreasury(PublicKey.default);
+        expect.fail("a module can't move to the zero address");
+      } catch (err: any) {
+        expect(err.toString()).to.include("ZeroAddress");
+      }
+
+      const before = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      const newTreasury = Keypair.generate().publicKey;
+      await pass(await rotateTreasury(newTreasury));
+
+      const state = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      expect(state.treasury.toString()).to.equal(newTreasury.toString());
+      expect(state.bridge.toString()).to.equal(before.bridge.toString());
+      // The treasury starts out exempt from the sell limit
+      const exempt = state.noSellLimit.map((k) => k.toString());
+      expect(exempt).to.include(newTreasury.toString());
+      expect(exempt).not.to.include(before.treasury.toString());
+    });
+
+    it("Only cranks into the nc_token state recorded by set_token", async () => {
+      const txId = await queueRestrict(Keypair.generate().publicKey);
+      await approve(txId, signer1);
+      await approve(txId, signer2);
+      await sleep((COOLDOWN_SECONDS + 2) * 1000);
+      try {
+        await governanceProgram.methods
+          .crankExecute(txId)
+          .accounts({
+            governance: governancePda,
+            proposal: proposalPda(txId),
+            ncTokenProgram: ncTokenProgram.programId,
+            ncTokenState: Keypair.generate().publicKey,
+            cranker: provider.wallet.publicKey,
+          })
+          .remainingAccounts(approvalAccounts(txId, [signer1, signer2]))
+          .rpc();
+        expect.fail("the CPI state account is pinned");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidTokenState");
+      }
+      await crankApproved(txId);
+      expect((await fetchProposal(txId)).status).to.deep.equal({
+        crankExecuted: {},
+      });
+    });
+  });
+
+  describe("Guardian pause", () => {
+    // Pause signed by `authority` and the guardians in `cosigners`
+    const pause = (cosigners: Keypair[], duration: number) =>
+      governanceProgram.methods
+        .setEmergencyPause(new anchor.BN(duration), "Bridge exploit")
+        .accounts({
+          governance: governancePda,
+          ncTokenProgram: ncTokenProgram.programId,
+          ncTokenState: ncTokenStatePda,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          cosigners.map((guardian) => ({
+            pubkey: guardian.publicKey,
+            isWritable: false,
+            isSigner: true,
+          }))
+        )
+        .signers([authority, ...cosigners])
+        .rpc();
+
+    it("Needs a quorum of guardians and a bounded duration to pause", async () => {
+      await admin(
+        governanceProgram.methods.setGuardians(
+          [authority.publicKey, signer1.publicKey],
+          2
+        )
+      );
+
+      const TWO_HOURS = 2 * 60 * 60;
+      for (const [cosigners, duration, error] of [
+        [[], TWO_HOURS, "InsufficientGuardians"],
+        [[signer2], TWO_HOURS, "NotGuardian"],
+        [[signer1], 25 * 60 * 60, "InvalidPauseDuration"],
+      ] as [Keypair[], number, string][]) {
+        try {
+          await pause(cosigners, duration);
+          expect.fail(`pause should fail with ${error}`);
+        } catch (err: any) {
+          expect(err.toString()).to.include(error);
+        }
+      }
+
+      const now = Math.floor(Date.now() / 1000);
+      await pause([signer1], TWO_HOURS);
+      const state = await ncTokenProgram.account.nCTokenState.fetch(
+        ncTokenStatePda
+      );
+      expect(state.emergencyPaused).to.be.true;
+      // Lifted on its own at the end of the window
+      expect(state.pauseExpiresAt.toNumber()).to.be.closeTo(
+        now + TWO_HOURS,
+        60
+      );
+
+      // A shorter pause would cut the one in force short
+      try {
+        await pause([signer1], 60);
+        expect.fail("a pause in force can't be shortened");
+      } catch (err: any) {
+        expect(err.toString()).to.include("PauseAlreadyInForce");
+      }
+    });
+  });
 });
 
@@ -0,0 +1,147 @@
+// Pretty-printing of proposals
+
+use nc_sdk::accounts::{Governance, PendingTransaction};
+use nc_sdk::governance::ProposalData;
+use nc_sdk::multisig_governance::TransactionStatus;
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn duration(seconds: i64) -> String {
+    let (h, m, s) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+// Time until the cooldown ends, or "ready"
+pub fn time_left(proposal: &PendingTransaction, now: i64) -> String {
+    if proposal.status != TransactionStatus::Pending {
+        return "-".to_string();
+    }
+    match proposal.execute_after - now {
+        left if left > 0 => format!("in {}", duration(left)),
+        _ => "ready".to_string(),
+    }
+}
+
+// One line per proposal for `list`
+pub fn summary(proposal: &PendingTransaction, governance: &Governance, now: i64) -> String {
+    format!(
+        "#{:<5} {:<14} {:<14} {}/{} approvals  {:<16} {}",
+        proposal.id,
+        format!("{:?}", proposal.tx_type),
+        format!("{:?}", proposal.status),
+        proposal.approval_count,
+        governance.required_approvals,
+        time_left(proposal, now),
+        proposal.title,
+    )
+}
+
+pub fn print_proposal(proposal: &PendingTransaction, governance: &Governance, now: i64) {
+    println!("Proposal #{}", proposal.id);
+    println!("  type:           {:?}", proposal.tx_type);
+    println!("  status:         {:?}", proposal.status);
+    println!("  title:          {}", proposal.title);
+    println!("  description:    {}", proposal.description_uri);
+    println!("  description sha256: {}", hex(&proposal.description_hash));
+    println!("  initiator:      {}", proposal.initiator);
+    println!("  queued at:      {}", proposal.timestamp);
+    println!(
+        "  approvals:      {}/{} (config nonce {}, governance at {})",
+        proposal.approval_count,
+        governance.required_approvals,
+        proposal.config_nonce,
+        governance.config_nonce,
+    );
+    println!(
+        "  execute after:  {} ({})",
+        proposal.execute_after,
+        time_left(proposal, now)
+    );
+    if proposal.status == TransactionStatus::Rejected {
+        println!("  rejected by:    {}", proposal.rejector);
+        println!("  reason:         {}", proposal.rejection_reason);
+    }
+    println!("  data:");
+    match ProposalData::decode(proposal.tx_type, &proposal.data) {
+        Ok(data) => {
+            for line in describe(&data) {
+                println!("    {}", line);
+            }
+        }
+        Err(err) => println!("    <undecodable: {}> {}", err, hex(&proposal.data)),
+    }
+}
+
+pub fn describe(data: &ProposalData) -> Vec<String> {
+    match data {
+        ProposalData::Unpause => vec!["lift the emergency pause".to_string()],
+        ProposalData::ExtendPause { expires_at } => vec![match expires_at {
+            0 => "keep paused until unpaused".to_string(),
+            at => format!("keep paused until {}", at),
+        }],
+        ProposalData::ModulePause { module, value } => {
+            vec![format!("module {:?}: paused = {}", module, value)]
+        }
+        ProposalData::Blacklist {
+            account,
+            value,
+            details,
+            freeze_token_accounts,
+        } => {
+            let mut lines = vec![
+                format!("account:        {}", account),
+                format!("blacklisted:    {}", value),
+                format!("reason code:    {}", details.reason_code),
+                format!("evidence hash:  {}", hex(&details.evidence_hash)),
+                format!("proposed by:    {}", details.proposed_by),
+                format!("expires at:     {}", details.expires_at),
+            ];
+            for token_account in freeze_token_accounts {
+                lines.push(format!("freeze:         {}", token_account));
+            }
+            lines
+        }
+        ProposalData::Freeze {
+            token_account,
+            value,
+        } => vec![format!("token account {}: frozen = {}", token_account, value)],
+        ProposalData::ListBatch(batch) => {
+            let mut lines = vec![format!("{:?} batch, {} entries", batch.list, batch.updates.len())];
+            if batch.list == nc_sdk::nc_token::AddressList::Blacklist {
+                lines.push(format!(
+                    "reason code {}, evidence {}, expires at {}",
+                    batch.details.reason_code,
+                    hex(&batch.details.evidence_hash),
+                    batch.details.expires_at
+                ));
+            }
+            for update in &batch.updates {
+                lines.push(format!("  {} = {}", update.account, update.value));
+            }
+            lines
+        }
+        ProposalData::ModuleAddress { module, address } => {
+            vec![format!("{:?} address -> {}", module, address)]
+        }
+        ProposalData::MerkleRoot { list, root } => {
+            vec![format!("{:?} Merkle root -> {}", list, hex(root))]
+        }
+        ProposalData::NoSellLimit { account, value } => {
+            vec![format!("{}: sell-limit exempt = {}", account, value)]
+        }
+        ProposalData::Restrict { account, value } => {
+            vec![format!("{}: restricted = {}", account, value)]
+        }
+        ProposalData::Pair { pool, value } => {
+            vec![format!("{}: liquidity pool = {}", pool, value)]
+        }
+    }
+}
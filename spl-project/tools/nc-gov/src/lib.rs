@@ -0,0 +1,29 @@
+// nc-gov library - the RPC client, proposal display, execution accounts
+// and offline signing flow behind the nc-gov command line
+
+pub mod display;
+pub mod offline;
+pub mod plan;
+pub mod rpc;
+
+use std::error::Error;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+// 32 bytes of hex, with or without a 0x prefix
+pub fn parse_hash32(value: &str) -> std::result::Result<[u8; 32], String> {
+    let digits = value.trim_start_matches("0x").as_bytes();
+    if digits.len() != 64 {
+        return Err("expected 32 bytes of hex".to_string());
+    }
+    let nibble = |digit: u8| {
+        (digit as char)
+            .to_digit(16)
+            .ok_or_else(|| "expected 32 bytes of hex".to_string())
+    };
+    let mut bytes = [0u8; 32];
+    for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+        *byte = (nibble(pair[0])? << 4 | nibble(pair[1])?) as u8;
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,560 @@
+// nc-gov - command-line client for NC Token MultiSig Governance
+//
+// Lists and decodes proposals, queues every proposal kind, and approves,
+// rejects and executes them. Defaults to a local validator. Approvals and
+// executions can also be built against a durable nonce and signed offline.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use nc_sdk::accounts::{self, ApprovalRecord, Governance, PendingTransaction};
+use nc_sdk::governance::{self as gov, Proposal};
+use nc_sdk::multisig_governance::{ProposalMetadata, TransactionStatus};
+use nc_sdk::nc_token::{AddressList, ListUpdate, PauseModule, ProtocolModule};
+use nc_sdk::{pda, GOVERNANCE_PROGRAM_ID};
+use serde_json::json;
+use solana_sdk::hash::hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use nc_gov::rpc::RpcClient;
+use nc_gov::{display, offline, parse_hash32, plan, Result};
+
+#[derive(Parser)]
+#[command(name = "nc-gov", about = "NC Token MultiSig Governance client")]
+struct Cli {
+    /// JSON-RPC endpoint
+    #[arg(long, short = 'u', global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Signer keypair file [default: ~/.config/solana/id.json]
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List proposals by scanning proposal PDAs
+    List {
+        /// Include executed, rejected and closed-out proposals
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show one proposal with its decoded data
+    Show { tx_id: u64 },
+    /// Queue a proposal
+    Queue {
+        #[command(flatten)]
+        metadata: MetadataArgs,
+        #[command(subcommand)]
+        proposal: QueueCommand,
+    },
+    /// Approve a proposal (auto-executes once approvals and cooldown allow)
+    Approve { tx_id: u64 },
+    /// Re-approve under the current governance config
+    Reapprove { tx_id: u64 },
+    /// Reject a pending proposal
+    Reject { tx_id: u64, reason: String },
+    /// Execute an approved proposal (owner)
+    Execute { tx_id: u64 },
+    /// Execute an approved proposal (anyone)
+    Crank { tx_id: u64 },
+    /// Close a finished proposal and refund its rent (initiator or an owner)
+    Close { tx_id: u64 },
+    /// Durable-nonce approve / execute for air-gapped owners
+    Offline {
+        #[command(subcommand)]
+        command: OfflineCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum OfflineCommand {
+    /// Build an unsigned approve or execute transaction (online)
+    Build {
+        action: offline::Action,
+        tx_id: u64,
+        /// Owner who will approve or execute
+        #[arg(long)]
+        signer: Pubkey,
+        /// Durable nonce account the transaction is built against
+        #[arg(long)]
+        nonce_account: Pubkey,
+        /// Nonce authority [default: --signer]
+        #[arg(long)]
+        nonce_authority: Option<Pubkey>,
+        /// Fee payer [default: --signer]
+        #[arg(long)]
+        fee_payer: Option<Pubkey>,
+        #[arg(long, short = 'o')]
+        out: PathBuf,
+    },
+    /// Review the proposal and add the keypair's signature (no network)
+    Sign {
+        file: PathBuf,
+        /// Write here instead of updating the file in place
+        #[arg(long, short = 'o')]
+        out: Option<PathBuf>,
+        /// Sign without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Combine signatures from copies of the same transaction
+    Merge {
+        #[arg(required = true, num_args = 2..)]
+        files: Vec<PathBuf>,
+        #[arg(long, short = 'o')]
+        out: PathBuf,
+    },
+    /// Broadcast a fully signed transaction
+    Send { file: PathBuf },
+}
+
+#[derive(Args)]
+struct MetadataArgs {
+    /// Short proposal title
+    #[arg(long)]
+    title: String,
+
+    /// Link to the full proposal description
+    #[arg(long, default_value = "")]
+    uri: String,
+
+    /// Local copy of the description; its SHA-256 is recorded on-chain
+    #[arg(long)]
+    description_file: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum QueueCommand {
+    /// Lift the emergency pause
+    Unpause,
+    /// Keep the emergency pause until a timestamp (0 = until unpaused)
+    ExtendPause { expires_at: i64 },
+    /// Pause or resume a module
+    ModulePause {
+        module: ModuleArg,
+        #[arg(action = ArgAction::Set)]
+        value: bool,
+    },
+    /// Add or remove a blacklist entry
+    Blacklist {
+        account: Pubkey,
+        #[arg(action = ArgAction::Set)]
+        value: bool,
+        #[arg(long, default_value_t = 0)]
+        reason_code: u16,
+        /// Hex SHA-256 of the evidence file
+        #[arg(long, value_parser = parse_hash32)]
+        evidence_hash: Option<[u8; 32]>,
+        /// Unix timestamp the entry lapses at (0 = never)
+        #[arg(long, default_value_t = 0)]
+        expires_at: i64,
+        /// Token accounts to freeze (or thaw when lifting)
+        #[arg(long = "freeze")]
+        freeze_token_accounts: Vec<Pubkey>,
+    },
+    /// SPL freeze or thaw a token account
+    Freeze {
+        token_account: Pubkey,
+        #[arg(action = ArgAction::Set)]
+        value: bool,
+    },
+    /// Batch list updates from a CSV of `address,true|false` lines
+    ListBatch {
+        list: ListArg,
+        file: PathBuf,
+        #[arg(long, default_value_t = 0)]
+        reason_code: u16,
+        #[arg(long, value_parser = parse_hash32)]
+        evidence_hash: Option<[u8; 32]>,
+        #[arg(long, default_value_t = 0)]
+        expires_at: i64,
+    },
+    /// Rotate the bridge, treasury or bond address
+    ModuleAddress {
+        module: ProtocolArg,
+        new_address: Pubkey,
+    },
+    /// Set (or clear with an all-zero root) a Merkle-root list
+    MerkleRoot {
+        list: ListArg,
+        #[arg(value_parser = parse_hash32)]
+        root: [u8; 32],
+    },
+    /// Exempt an address from the sell limit
+    NoSellLimit {
+        account: Pubkey,
+        #[arg(action = ArgAction::Set)]
+        value: bool,
+    },
+    /// Mark an address as a restricted protocol module
+    Restrict {
+        account: Pubkey,
+        #[arg(action = ArgAction::Set)]
+        value: bool,
+    },
+    /// Mark an address as a liquidity pool
+    Pair {
+        pool: Pubkey,
+        #[arg(action = ArgAction::Set)]
+        value: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ModuleArg {
+    Global,
+    Bridge,
+    Bond,
+    Treasury,
+    LpTrading,
+    P2p,
+}
+
+impl From<ModuleArg> for PauseModule {
+    fn from(module: ModuleArg) -> Self {
+        match module {
+            ModuleArg::Global => PauseModule::Global,
+            ModuleArg::Bridge => PauseModule::Bridge,
+            ModuleArg::Bond => PauseModule::Bond,
+            ModuleArg::Treasury => PauseModule::Treasury,
+            ModuleArg::LpTrading => PauseModule::LpTrading,
+            ModuleArg::P2p => PauseModule::P2p,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ProtocolArg {
+    Bridge,
+    Treasury,
+    Bond,
+}
+
+impl From<ProtocolArg> for ProtocolModule {
+    fn from(module: ProtocolArg) -> Self {
+        match module {
+            ProtocolArg::Bridge => ProtocolModule::Bridge,
+            ProtocolArg::Treasury => ProtocolModule::Treasury,
+            ProtocolArg::Bond => ProtocolModule::Bond,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ListArg {
+    Blacklist,
+    Restricted,
+    NoSellLimit,
+    LiquidityPool,
+}
+
+impl From<ListArg> for AddressList {
+    fn from(list: ListArg) -> Self {
+        match list {
+            ListArg::Blacklist => AddressList::Blacklist,
+            ListArg::Restricted => AddressList::Restricted,
+            ListArg::NoSellLimit => AddressList::NoSellLimit,
+            ListArg::LiquidityPool => AddressList::LiquidityPool,
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let rpc = RpcClient::new(&cli.url);
+    match cli.command {
+        Command::List { all } => list(&rpc, all),
+        Command::Show { tx_id } => show(&rpc, tx_id),
+        Command::Queue { metadata, proposal } => {
+            queue(&rpc, &load_keypair(&cli.keypair)?, metadata, proposal)
+        }
+        Command::Approve { tx_id } => {
+            let signer = load_keypair(&cli.keypair)?;
+            let remaining = execution_accounts(&rpc, tx_id, Some(&signer.pubkey()))?;
+            let ix = gov::approve_transaction(signer.pubkey(), tx_id, None, remaining);
+            send(&rpc, &signer, vec![ix])
+        }
+        Command::Reapprove { tx_id } => {
+            let signer = load_keypair(&cli.keypair)?;
+            send(&rpc, &signer, vec![gov::reapprove_transaction(signer.pubkey(), tx_id)])
+        }
+        Command::Reject { tx_id, reason } => {
+            let signer = load_keypair(&cli.keypair)?;
+            send(&rpc, &signer, vec![gov::reject_transaction(signer.pubkey(), tx_id, reason)])
+        }
+        Command::Execute { tx_id } => {
+            let signer = load_keypair(&cli.keypair)?;
+            let remaining = execution_accounts(&rpc, tx_id, None)?;
+            send(&rpc, &signer, vec![gov::execute_transaction(signer.pubkey(), tx_id, None, remaining)])
+        }
+        Command::Crank { tx_id } => {
+            let signer = load_keypair(&cli.keypair)?;
+            let remaining = execution_accounts(&rpc, tx_id, None)?;
+            send(&rpc, &signer, vec![gov::crank_execute(signer.pubkey(), tx_id, remaining)])
+        }
+        Command::Close { tx_id } => {
+            let signer = load_keypair(&cli.keypair)?;
+            let proposal = fetch_proposal(&rpc, tx_id)?;
+            let approvers = fetch_approvers(&rpc, tx_id)?;
+            // A transaction per batch of approval records; the last closes
+            // the proposal
+            for ix in gov::close_proposal_batches(signer.pubkey(), &proposal, &approvers) {
+                send(&rpc, &signer, vec![ix])?;
+            }
+            Ok(())
+        }
+        Command::Offline { command } => match command {
+            OfflineCommand::Build {
+                action,
+                tx_id,
+                signer,
+                nonce_account,
+                nonce_authority,
+                fee_payer,
+                out,
+            } => {
+                let approver = match action {
+                    offline::Action::Approve => Some(&signer),
+                    offline::Action::Execute => None,
+                };
+                let remaining = execution_accounts(&rpc, tx_id, approver)?;
+                offline::build(
+                    &rpc,
+                    offline::BuildArgs {
+                        action,
+                        tx_id,
+                        signer,
+                        nonce_account,
+                        nonce_authority,
+                        fee_payer,
+                        remaining,
+                    },
+                    &out,
+                )
+            }
+            OfflineCommand::Sign { file, out, yes } => {
+                offline::sign(&load_keypair(&cli.keypair)?, &file, out.as_deref(), yes)
+            }
+            OfflineCommand::Merge { files, out } => offline::merge(&files, &out),
+            OfflineCommand::Send { file } => offline::send(&rpc, &file),
+        },
+    }
+}
+
+fn list(rpc: &RpcClient, all: bool) -> Result<()> {
+    let governance = fetch_governance(rpc)?;
+    let now = rpc.unix_timestamp()?;
+    let ids: Vec<u64> = (1..governance.next_transaction_id).collect();
+    let keys: Vec<Pubkey> = ids.iter().map(|id| pda::proposal(*id).0).collect();
+    let mut shown = 0;
+    for data in rpc.get_multiple_accounts(&keys)?.into_iter().flatten() {
+        let proposal = accounts::pending_transaction(&data)?;
+        if all || proposal.status == TransactionStatus::Pending {
+            println!("{}", display::summary(&proposal, &governance, now));
+            shown += 1;
+        }
+    }
+    if shown == 0 {
+        println!("no {}proposals", if all { "" } else { "pending " });
+    }
+    Ok(())
+}
+
+fn show(rpc: &RpcClient, tx_id: u64) -> Result<()> {
+    let governance = fetch_governance(rpc)?;
+    let proposal = fetch_proposal(rpc, tx_id)?;
+    display::print_proposal(&proposal, &governance, rpc.unix_timestamp()?);
+    Ok(())
+}
+
+fn queue(
+    rpc: &RpcClient,
+    signer: &Keypair,
+    metadata: MetadataArgs,
+    command: QueueCommand,
+) -> Result<()> {
+    let description_hash = match &metadata.description_file {
+        Some(path) => hash(&fs::read(path)?).to_bytes(),
+        None => [0; 32],
+    };
+    let metadata = ProposalMetadata {
+        title: metadata.title,
+        description_uri: metadata.uri,
+        description_hash,
+    };
+    let tx_id = fetch_governance(rpc)?.next_transaction_id;
+
+    let mut appends = Vec::new();
+    let proposal = match command {
+        QueueCommand::Unpause => Proposal::Unpause,
+        QueueCommand::ExtendPause { expires_at } => Proposal::ExtendPause { expires_at },
+        QueueCommand::ModulePause { module, value } => Proposal::ModulePause {
+            module: module.into(),
+            value,
+        },
+        QueueCommand::Blacklist {
+            account,
+            value,
+            reason_code,
+            evidence_hash,
+            expires_at,
+            freeze_token_accounts,
+        } => Proposal::Blacklist {
+            account,
+            value,
+            reason_code,
+            evidence_hash: evidence_hash.unwrap_or_default(),
+            expires_at,
+            freeze_token_accounts,
+        },
+        QueueCommand::Freeze {
+            token_account,
+            value,
+        } => Proposal::Freeze {
+            token_account,
+            value,
+        },
+        QueueCommand::ListBatch {
+            list,
+            file,
+            reason_code,
+            evidence_hash,
+            expires_at,
+        } => {
+            let (updates, rest) = plan::split_batch(read_list_updates(&file)?);
+            appends = rest;
+            Proposal::ListBatch {
+                list: list.into(),
+                updates,
+                reason_code,
+                evidence_hash: evidence_hash.unwrap_or_default(),
+                expires_at,
+            }
+        }
+        QueueCommand::ModuleAddress {
+            module,
+            new_address,
+        } => Proposal::ModuleAddress {
+            module: module.into(),
+            new_address,
+        },
+        QueueCommand::MerkleRoot { list, root } => Proposal::MerkleRoot {
+            list: list.into(),
+            root,
+        },
+        QueueCommand::NoSellLimit { account, value } => Proposal::NoSellLimit { account, value },
+        QueueCommand::Restrict { account, value } => Proposal::Restrict { account, value },
+        QueueCommand::Pair { pool, value } => Proposal::Pair { pool, value },
+    };
+
+    send(rpc, signer, vec![gov::queue(signer.pubkey(), tx_id, proposal, metadata)])?;
+    for updates in appends {
+        send(rpc, signer, vec![gov::append_list_batch(signer.pubkey(), tx_id, updates)])?;
+    }
+    println!("queued proposal #{}", tx_id);
+    Ok(())
+}
+
+fn execution_accounts(
+    rpc: &RpcClient,
+    tx_id: u64,
+    approver: Option<&Pubkey>,
+) -> Result<Vec<AccountMeta>> {
+    plan::execution_accounts(
+        &fetch_governance(rpc)?,
+        &fetch_proposal(rpc, tx_id)?,
+        approver,
+        |keys| rpc.get_multiple_accounts(keys),
+    )
+}
+
+fn send(rpc: &RpcClient, signer: &Keypair, instructions: Vec<Instruction>) -> Result<()> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&signer.pubkey()),
+        &[signer],
+        blockhash,
+    );
+    let signature = rpc.send_and_confirm(&transaction)?;
+    println!("{}", signature);
+    Ok(())
+}
+
+fn fetch_governance(rpc: &RpcClient) -> Result<Governance> {
+    let data = rpc
+        .get_account_data(&pda::governance().0)?
+        .ok_or("governance is not initialized")?;
+    Ok(accounts::governance(&data)?)
+}
+
+fn fetch_proposal(rpc: &RpcClient, tx_id: u64) -> Result<PendingTransaction> {
+    let data = rpc
+        .get_account_data(&pda::proposal(tx_id).0)?
+        .ok_or_else(|| format!("proposal #{} not found", tx_id))?;
+    Ok(accounts::pending_transaction(&data)?)
+}
+
+// Owners of the proposal's approval records, including owners since removed
+fn fetch_approvers(rpc: &RpcClient, tx_id: u64) -> Result<Vec<Pubkey>> {
+    let proposal = pda::proposal(tx_id).0;
+    let filters = json!([
+        { "dataSize": ApprovalRecord::LEN },
+        // ApprovalRecord.proposal follows the discriminator
+        { "memcmp": { "offset": 8, "bytes": proposal.to_string() } },
+    ]);
+    let mut approvers = Vec::new();
+    for (_, data) in rpc.get_program_accounts(&GOVERNANCE_PROGRAM_ID, filters)? {
+        approvers.push(accounts::approval_record(&data)?.owner);
+    }
+    Ok(approvers)
+}
+
+fn load_keypair(path: &Option<PathBuf>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path.clone(),
+        None => PathBuf::from(std::env::var("HOME")?).join(".config/solana/id.json"),
+    };
+    read_keypair_file(&path).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+fn read_list_updates(path: &PathBuf) -> Result<Vec<ListUpdate>> {
+    let mut updates = Vec::new();
+    for (line_no, line) in fs::read_to_string(path)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(',').map(str::trim);
+        let parsed = match (fields.next(), fields.next()) {
+            (Some(account), Some(value)) => account.parse().ok().zip(value.parse().ok()),
+            _ => None,
+        };
+        match parsed {
+            Some((account, value)) => updates.push(ListUpdate { account, value }),
+            // First line may be a header
+            None if line_no == 0 => continue,
+            None => return Err(format!("{}:{}: expected address,true|false", path.display(), line_no + 1).into()),
+        }
+    }
+    if updates.is_empty() {
+        return Err(format!("{}: no entries", path.display()).into());
+    }
+    Ok(updates)
+}
@@ -0,0 +1,409 @@
+// Offline (air-gapped) signing of approve / execute transactions
+//
+// `build` runs online: it snapshots the proposal and governance accounts and
+// compiles an unsigned transaction against a durable nonce, so it stays valid
+// until the nonce is advanced. `sign` needs no network: it checks the message
+// only approves or executes the snapshotted proposal, shows the decoded
+// proposal and adds one signature. `merge` combines signature sets and `send`
+// broadcasts once every required signature is present.
+//
+// The snapshot comes from the online machine, so the signer can't trust it
+// as such. The instruction carries the snapshot's content hash instead
+// (expected_hash), and the program refuses it unless the proposal on-chain
+// still matches what the signer was shown. The governance snapshot (owners,
+// approvals required) is shown for context only.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::ValueEnum;
+use nc_sdk::accounts;
+use nc_sdk::governance as gov;
+use nc_sdk::{pda, GOVERNANCE_PROGRAM_ID};
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+
+use crate::display;
+use crate::rpc::RpcClient;
+use crate::Result;
+
+// Nonce account layout: Versions tag (u32), State tag (u32), authority, durable nonce
+const NONCE_INITIALIZED: u32 = 1;
+const NONCE_AUTHORITY: std::ops::Range<usize> = 8..40;
+const NONCE_BLOCKHASH: std::ops::Range<usize> = 40..72;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Approve,
+    Execute,
+}
+
+// What travels between the online and offline machines
+#[derive(Serialize, Deserialize)]
+pub struct OfflineTransaction {
+    pub action: Action,
+    pub tx_id: u64,
+    // Owner approving / executing
+    pub signer: String,
+    pub nonce_account: String,
+    // Cluster time when the snapshot was taken
+    pub snapshot_time: i64,
+    // Base64 account data as fetched by `build`
+    pub governance: String,
+    pub proposal: String,
+    // Base64 bincode legacy message
+    pub message: String,
+    // Signer pubkey -> signature
+    #[serde(default)]
+    pub signatures: BTreeMap<String, String>,
+}
+
+pub struct BuildArgs {
+    pub action: Action,
+    pub tx_id: u64,
+    pub signer: Pubkey,
+    pub nonce_account: Pubkey,
+    pub nonce_authority: Option<Pubkey>,
+    pub fee_payer: Option<Pubkey>,
+    // Approval PDAs and freeze accounts the instruction passes on
+    pub remaining: Vec<AccountMeta>,
+}
+
+// Account data `build` fetched
+pub struct Snapshot {
+    pub nonce: Vec<u8>,
+    pub governance: Vec<u8>,
+    pub proposal: Vec<u8>,
+    // Cluster time
+    pub time: i64,
+}
+
+impl OfflineTransaction {
+    // Compiles the unsigned transaction from a snapshot
+    pub fn new(args: BuildArgs, snapshot: &Snapshot) -> Result<Self> {
+        let nonce_authority = args.nonce_authority.unwrap_or(args.signer);
+        let fee_payer = args.fee_payer.unwrap_or(args.signer);
+
+        let (authority, durable_nonce) = parse_nonce(&snapshot.nonce)?;
+        if authority != nonce_authority {
+            return Err(format!(
+                "nonce account {} is controlled by {}, not {}",
+                args.nonce_account, authority, nonce_authority
+            )
+            .into());
+        }
+
+        let governance = accounts::governance(&snapshot.governance)?;
+        if !governance.owners.contains(&args.signer) {
+            return Err(format!("{} is not a governance owner", args.signer).into());
+        }
+        let proposal = accounts::pending_transaction(&snapshot.proposal)?;
+        if proposal.id != args.tx_id {
+            return Err(format!("snapshot holds proposal #{}, not #{}", proposal.id, args.tx_id).into());
+        }
+
+        let instruction = governance_instruction(
+            args.action,
+            args.signer,
+            args.tx_id,
+            proposal.content_hash(),
+            args.remaining,
+        );
+        let mut message = Message::new_with_nonce(
+            vec![instruction],
+            Some(&fee_payer),
+            &args.nonce_account,
+            &nonce_authority,
+        );
+        message.recent_blockhash = durable_nonce;
+
+        Ok(OfflineTransaction {
+            action: args.action,
+            tx_id: args.tx_id,
+            signer: args.signer.to_string(),
+            nonce_account: args.nonce_account.to_string(),
+            snapshot_time: snapshot.time,
+            governance: STANDARD.encode(&snapshot.governance),
+            proposal: STANDARD.encode(&snapshot.proposal),
+            message: STANDARD.encode(bincode::serialize(&message)?),
+            signatures: BTreeMap::new(),
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn message(&self) -> Result<Message> {
+        Ok(bincode::deserialize(&STANDARD.decode(&self.message)?)?)
+    }
+
+    fn signer(&self) -> Result<Pubkey> {
+        Ok(self.signer.parse()?)
+    }
+
+    fn governance(&self) -> Result<accounts::Governance> {
+        Ok(accounts::governance(&STANDARD.decode(&self.governance)?)?)
+    }
+
+    fn proposal(&self) -> Result<accounts::PendingTransaction> {
+        Ok(accounts::pending_transaction(&STANDARD.decode(&self.proposal)?)?)
+    }
+
+    // Signatures placed at their signer positions, checked against the message
+    pub fn transaction(&self) -> Result<Transaction> {
+        let message = self.message()?;
+        let mut transaction = Transaction::new_unsigned(message);
+        for (key, signature) in &self.signatures {
+            let key: Pubkey = key.parse()?;
+            let signature: Signature = signature.parse()?;
+            let position = transaction.message.account_keys
+                [..transaction.message.header.num_required_signatures as usize]
+                .iter()
+                .position(|k| *k == key)
+                .ok_or_else(|| format!("{} is not a signer of this transaction", key))?;
+            if !signature.verify(key.as_ref(), &transaction.message_data()) {
+                return Err(format!("signature for {} does not match the message", key).into());
+            }
+            transaction.signatures[position] = signature;
+        }
+        Ok(transaction)
+    }
+
+    pub fn missing_signers(&self) -> Result<Vec<Pubkey>> {
+        let message = self.message()?;
+        Ok(message
+            .signer_keys()
+            .into_iter()
+            .filter(|key| !self.signatures.contains_key(&key.to_string()))
+            .copied()
+            .collect())
+    }
+}
+
+pub fn build(rpc: &RpcClient, args: BuildArgs, out: &Path) -> Result<()> {
+    let snapshot = Snapshot {
+        nonce: rpc
+            .get_account_data(&args.nonce_account)?
+            .ok_or_else(|| format!("nonce account {} not found", args.nonce_account))?,
+        governance: rpc
+            .get_account_data(&pda::governance().0)?
+            .ok_or("governance is not initialized")?,
+        proposal: rpc
+            .get_account_data(&pda::proposal(args.tx_id).0)?
+            .ok_or_else(|| format!("proposal #{} not found", args.tx_id))?,
+        time: rpc.unix_timestamp()?,
+    };
+    let offline = OfflineTransaction::new(args, &snapshot)?;
+    offline.write(out)?;
+
+    println!("wrote {}", out.display());
+    println!("signers required:");
+    for key in offline.message()?.signer_keys() {
+        println!("  {}", key);
+    }
+    Ok(())
+}
+
+// Runs without network access
+pub fn sign(keypair: &Keypair, path: &Path, out: Option<&Path>, yes: bool) -> Result<()> {
+    let mut offline = OfflineTransaction::read(path)?;
+    let message = offline.message()?;
+    verify_message(&offline, &message)?;
+    let governance = offline.governance()?;
+    let proposal = offline.proposal()?;
+
+    println!("action:         {:?} proposal #{}", offline.action, offline.tx_id);
+    println!("owner:          {}", offline.signer);
+    println!("fee payer:      {}", message.account_keys[0]);
+    println!("nonce account:  {}", offline.nonce_account);
+    println!("durable nonce:  {}", message.recent_blockhash);
+    println!(
+        "snapshot taken at cluster time {} by the machine that built this file",
+        offline.snapshot_time
+    );
+    println!("proposal hash:  {}", display::hex(&proposal.content_hash()));
+    println!("  (checked on-chain - the transaction fails unless the proposal still reads as below;");
+    println!("  owners and approvals are as the building machine saw them)");
+    println!();
+    display::print_proposal(&proposal, &governance, offline.snapshot_time);
+    println!();
+
+    let key = keypair.pubkey();
+    if !message.signer_keys().contains(&&key) {
+        return Err(format!("{} is not a signer of this transaction", key).into());
+    }
+    if !yes && !confirm(&format!("Sign as {}?", key))? {
+        return Err("aborted".into());
+    }
+
+    let signature = keypair.sign_message(&message.serialize());
+    offline.signatures.insert(key.to_string(), signature.to_string());
+    let out = out.unwrap_or(path);
+    offline.write(out)?;
+    println!("signed as {}, wrote {}", key, out.display());
+    Ok(())
+}
+
+pub fn merge(paths: &[impl AsRef<Path>], out: &Path) -> Result<()> {
+    let mut paths = paths.iter().map(AsRef::as_ref);
+    let first = paths.next().ok_or("nothing to merge")?;
+    let mut merged = OfflineTransaction::read(first)?;
+    verify_message(&merged, &merged.message()?)?;
+    for path in paths {
+        let other = OfflineTransaction::read(path)?;
+        if other.message != merged.message || other.proposal != merged.proposal {
+            return Err(format!("{} signs a different message than {}", path.display(), first.display()).into());
+        }
+        for (key, signature) in other.signatures {
+            match merged.signatures.get(&key) {
+                Some(existing) if *existing != signature => {
+                    return Err(format!("conflicting signatures for {}", key).into())
+                }
+                _ => {
+                    merged.signatures.insert(key, signature);
+                }
+            }
+        }
+    }
+    // Rejects signatures that do not verify
+    merged.transaction()?;
+    merged.write(out)?;
+
+    println!("wrote {}", out.display());
+    for key in merged.missing_signers()? {
+        println!("  still missing: {}", key);
+    }
+    Ok(())
+}
+
+pub fn send(rpc: &RpcClient, path: &Path) -> Result<()> {
+    let offline = OfflineTransaction::read(path)?;
+    let missing = offline.missing_signers()?;
+    if !missing.is_empty() {
+        let missing: Vec<String> = missing.iter().map(Pubkey::to_string).collect();
+        return Err(format!("missing signatures from {}", missing.join(", ")).into());
+    }
+    let signature = rpc.send_and_confirm(&offline.transaction()?)?;
+    println!("{}", signature);
+    Ok(())
+}
+
+fn governance_instruction(
+    action: Action,
+    signer: Pubkey,
+    tx_id: u64,
+    content_hash: [u8; 32],
+    remaining: Vec<AccountMeta>,
+) -> Instruction {
+    match action {
+        Action::Approve => gov::approve_transaction(signer, tx_id, Some(content_hash), remaining),
+        Action::Execute => gov::execute_transaction(signer, tx_id, Some(content_hash), remaining),
+    }
+}
+
+// The message may only advance the nonce and approve / execute the
+// snapshotted proposal, as it reads in the snapshot, as the named owner;
+// extra remaining accounts are validated on-chain
+pub fn verify_message(offline: &OfflineTransaction, message: &Message) -> Result<()> {
+    let proposal = offline.proposal()?;
+    if proposal.id != offline.tx_id {
+        return Err(format!(
+            "snapshot holds proposal #{}, transaction targets #{}",
+            proposal.id, offline.tx_id
+        )
+        .into());
+    }
+
+    if message.instructions.len() != 2 {
+        return Err(format!("expected 2 instructions, found {}", message.instructions.len()).into());
+    }
+    let decompiled: Vec<(Pubkey, Vec<Pubkey>, &[u8])> = message
+        .instructions
+        .iter()
+        .map(|ix| {
+            (
+                message.account_keys[ix.program_id_index as usize],
+                ix.accounts.iter().map(|i| message.account_keys[*i as usize]).collect(),
+                ix.data.as_slice(),
+            )
+        })
+        .collect();
+
+    let nonce_account: Pubkey = offline.nonce_account.parse()?;
+    let (program_id, keys, data) = &decompiled[0];
+    let authority = keys.get(2).ok_or("malformed nonce instruction")?;
+    let expected = system_instruction::advance_nonce_account(&nonce_account, authority);
+    if !matches_instruction(&expected, program_id, keys, data, false) {
+        return Err("first instruction is not an advance of the named nonce account".into());
+    }
+
+    let (program_id, keys, data) = &decompiled[1];
+    let expected = governance_instruction(
+        offline.action,
+        offline.signer()?,
+        offline.tx_id,
+        proposal.content_hash(),
+        Vec::new(),
+    );
+    if *program_id != GOVERNANCE_PROGRAM_ID
+        || !matches_instruction(&expected, program_id, keys, data, true)
+    {
+        return Err(format!(
+            "second instruction is not {:?} of proposal #{} as snapshotted, by {}",
+            offline.action, offline.tx_id, offline.signer
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// `prefix` allows trailing remaining accounts
+fn matches_instruction(
+    expected: &Instruction,
+    program_id: &Pubkey,
+    keys: &[Pubkey],
+    data: &[u8],
+    prefix: bool,
+) -> bool {
+    let expected_keys: Vec<Pubkey> = expected.accounts.iter().map(|meta| meta.pubkey).collect();
+    expected.program_id == *program_id
+        && expected.data == data
+        && keys.starts_with(&expected_keys)
+        && (prefix || keys.len() == expected_keys.len())
+}
+
+fn parse_nonce(data: &[u8]) -> Result<(Pubkey, Hash)> {
+    let state = data.get(4..8).ok_or("malformed nonce account")?;
+    if u32::from_le_bytes(state.try_into()?) != NONCE_INITIALIZED {
+        return Err("nonce account is not initialized".into());
+    }
+    let authority = Pubkey::try_from(data.get(NONCE_AUTHORITY).ok_or("malformed nonce account")?)?;
+    let blockhash: [u8; 32] = data.get(NONCE_BLOCKHASH).ok_or("malformed nonce account")?.try_into()?;
+    Ok((authority, Hash::new_from_array(blockhash)))
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N] ", prompt);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
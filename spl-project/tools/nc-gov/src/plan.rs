@@ -0,0 +1,70 @@
+// What nc-gov sends for a proposal beyond its own arguments: list batches
+// split across queue and append_list_batch, and the remaining accounts
+// approve / execute / crank need
+
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+use nc_sdk::accounts::{Governance, PendingTransaction};
+use nc_sdk::governance::{self as gov, ProposalData};
+use nc_sdk::nc_token::ListUpdate;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::Result;
+
+// Entries per list-batch instruction (queue, then append the rest)
+pub const BATCH_ENTRIES_PER_TX: usize = 20;
+
+// The entries queued with the proposal and the append_list_batch chunks
+// that follow them
+pub fn split_batch(mut updates: Vec<ListUpdate>) -> (Vec<ListUpdate>, Vec<Vec<ListUpdate>>) {
+    let rest = updates.split_off(updates.len().min(BATCH_ENTRIES_PER_TX));
+    let appends = rest.chunks(BATCH_ENTRIES_PER_TX).map(<[_]>::to_vec).collect();
+    (updates, appends)
+}
+
+// Remaining accounts for approve / execute / crank: existing approval PDAs
+// (minus the approver's own, which approve creates) and whatever a freeze
+// or thaw needs. `fetch` returns each key's account data, None for
+// accounts that do not exist.
+pub fn execution_accounts<F>(
+    governance: &Governance,
+    proposal: &PendingTransaction,
+    approver: Option<&Pubkey>,
+    fetch: F,
+) -> Result<Vec<AccountMeta>>
+where
+    F: Fn(&[Pubkey]) -> Result<Vec<Option<Vec<u8>>>>,
+{
+    let owners: Vec<Pubkey> = governance
+        .owners
+        .iter()
+        .filter(|owner| Some(*owner) != approver)
+        .copied()
+        .collect();
+    let approvals = gov::approval_accounts(proposal.id, &owners);
+    let keys: Vec<Pubkey> = approvals.iter().map(|meta| meta.pubkey).collect();
+    let mut remaining: Vec<AccountMeta> = approvals
+        .into_iter()
+        .zip(fetch(&keys)?)
+        .filter(|(_, data)| data.is_some())
+        .map(|(meta, _)| meta)
+        .collect();
+
+    let token_accounts = ProposalData::decode(proposal.tx_type, &proposal.data)?
+        .freeze_token_accounts();
+    if !token_accounts.is_empty() {
+        let mut mints = Vec::new();
+        for (token_account, data) in token_accounts.iter().zip(fetch(&token_accounts)?) {
+            let data = data.ok_or_else(|| format!("token account {} not found", token_account))?;
+            // SPL token account layout starts with the mint
+            let mint = Pubkey::try_from(&data[..32])?;
+            if !mints.contains(&mint) {
+                mints.push(mint);
+            }
+            remaining.push(AccountMeta::new(*token_account, false));
+        }
+        remaining.extend(mints.into_iter().map(|mint| AccountMeta::new_readonly(mint, false)));
+        remaining.push(AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false));
+    }
+    Ok(remaining)
+}
@@ -0,0 +1,149 @@
+// Minimal JSON-RPC client (only the calls nc-gov needs)
+
+use std::thread;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+
+use crate::Result;
+
+// getMultipleAccounts limit
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+const CONFIRM_ATTEMPTS: usize = 60;
+
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: Value = ureq::post(&self.url)
+            .send_json(request)
+            .map_err(|e| format!("{} {}: {}", method, self.url, e))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            return Err(format!("{} failed: {}", method, error).into());
+        }
+        Ok(response["result"].clone())
+    }
+
+    pub fn get_account_data(&self, key: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([key.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        decode_account(&result["value"])
+    }
+
+    // None for accounts that do not exist
+    pub fn get_multiple_accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let mut accounts = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let keys: Vec<String> = chunk.iter().map(|k| k.to_string()).collect();
+            let result = self.call(
+                "getMultipleAccounts",
+                json!([keys, { "encoding": "base64", "commitment": "confirmed" }]),
+            )?;
+            for value in result["value"].as_array().ok_or("malformed getMultipleAccounts")? {
+                accounts.push(decode_account(value)?);
+            }
+        }
+        Ok(accounts)
+    }
+
+    // Accounts owned by `program` that match `filters` (getProgramAccounts
+    // dataSize / memcmp filters)
+    pub fn get_program_accounts(&self, program: &Pubkey, filters: Value) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getProgramAccounts",
+            json!([
+                program.to_string(),
+                { "encoding": "base64", "commitment": "confirmed", "filters": filters }
+            ]),
+        )?;
+        let mut accounts = Vec::new();
+        for value in result.as_array().ok_or("malformed getProgramAccounts")? {
+            let key: Pubkey = value["pubkey"]
+                .as_str()
+                .ok_or("malformed getProgramAccounts")?
+                .parse()?;
+            if let Some(data) = decode_account(&value["account"])? {
+                accounts.push((key, data));
+            }
+        }
+        Ok(accounts)
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or("malformed getLatestBlockhash")?;
+        Ok(blockhash.parse()?)
+    }
+
+    // Cluster time from the Clock sysvar (what execute_after is checked against)
+    pub fn unix_timestamp(&self) -> Result<i64> {
+        let data = self
+            .get_account_data(&sysvar::clock::ID)?
+            .ok_or("clock sysvar missing")?;
+        // slot, epoch_start_timestamp, epoch, leader_schedule_epoch, unix_timestamp
+        let bytes: [u8; 8] = data.get(32..40).ok_or("malformed clock sysvar")?.try_into()?;
+        Ok(i64::from_le_bytes(bytes))
+    }
+
+    // Sends and waits for confirmation
+    pub fn send_and_confirm(&self, transaction: &Transaction) -> Result<Signature> {
+        let encoded = STANDARD.encode(bincode::serialize(transaction)?);
+        let result = self.call(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature: Signature = result.as_str().ok_or("malformed sendTransaction")?.parse()?;
+
+        for _ in 0..CONFIRM_ATTEMPTS {
+            let result = self.call("getSignatureStatuses", json!([[signature.to_string()]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(format!("transaction {} failed: {}", signature, status["err"]).into());
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed") | Some("finalized")
+                ) {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        Err(format!("transaction {} not confirmed", signature).into())
+    }
+}
+
+fn decode_account(value: &Value) -> Result<Option<Vec<u8>>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let data = value["data"][0].as_str().ok_or("malformed account data")?;
+    Ok(Some(STANDARD.decode(data)?))
+}
@@ -0,0 +1,314 @@
+// Offline signing: building against a snapshot, what `sign` and `merge`
+// accept, and the on-chain check that pins the snapshotted proposal
+
+use std::fs;
+use std::path::PathBuf;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use nc_gov::offline::{self, verify_message, Action, BuildArgs, OfflineTransaction, Snapshot};
+use nc_harness::fixture::{assert_error, Protocol};
+use nc_sdk::governance::{self as gov, Proposal};
+use nc_sdk::multisig_governance::ErrorCode;
+use nc_sdk::nc_token::{AddressList, ListUpdate};
+use nc_sdk::pda;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+struct Setup {
+    protocol: Protocol,
+    tx_id: u64,
+    owner: Pubkey,
+    nonce_account: Pubkey,
+    nonce_authority: Keypair,
+    fee_payer: Keypair,
+    dir: PathBuf,
+}
+
+fn setup(name: &str) -> Setup {
+    let protocol = Protocol::new(3, 2);
+    let tx_id = protocol.queue(list_batch(3));
+    let owner = protocol.owners[0];
+    let dir = std::env::temp_dir().join(format!("nc-gov-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    Setup {
+        protocol,
+        tx_id,
+        owner,
+        nonce_account: Pubkey::new_unique(),
+        nonce_authority: Keypair::new(),
+        fee_payer: Keypair::new(),
+        dir,
+    }
+}
+
+fn list_batch(count: usize) -> Proposal {
+    Proposal::ListBatch {
+        list: AddressList::Restricted,
+        updates: (0..count)
+            .map(|_| ListUpdate {
+                account: Pubkey::new_unique(),
+                value: true,
+            })
+            .collect(),
+        reason_code: 0,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+    }
+}
+
+// Initialized nonce account data: Versions and State tags, authority,
+// durable nonce, fee calculator
+fn nonce_data(authority: &Pubkey) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(1u32.to_le_bytes());
+    data.extend(1u32.to_le_bytes());
+    data.extend(authority.to_bytes());
+    data.extend(Hash::new_unique().to_bytes());
+    data.extend(5_000u64.to_le_bytes());
+    data
+}
+
+impl Setup {
+    fn snapshot(&self) -> Snapshot {
+        let data = |key: &Pubkey| self.protocol.svm.account(key).unwrap().data;
+        Snapshot {
+            nonce: nonce_data(&self.nonce_authority.pubkey()),
+            governance: data(&pda::governance().0),
+            proposal: data(&pda::proposal(self.tx_id).0),
+            time: self.protocol.svm.now(),
+        }
+    }
+
+    fn build(&self, action: Action, snapshot: &Snapshot) -> OfflineTransaction {
+        let approver = match action {
+            Action::Approve => Some(&self.owner),
+            Action::Execute => None,
+        };
+        let args = BuildArgs {
+            action,
+            tx_id: self.tx_id,
+            signer: self.owner,
+            nonce_account: self.nonce_account,
+            nonce_authority: Some(self.nonce_authority.pubkey()),
+            fee_payer: Some(self.fee_payer.pubkey()),
+            remaining: self.protocol.execution_accounts(self.tx_id, approver),
+        };
+        OfflineTransaction::new(args, snapshot).unwrap()
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+}
+
+impl Drop for Setup {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn verify(offline: &OfflineTransaction) -> Result<(), String> {
+    verify_message(offline, &offline.message().unwrap()).map_err(|e| e.to_string())
+}
+
+#[test]
+fn built_transactions_pin_the_snapshotted_proposal() {
+    let setup = setup("pin");
+    let protocol = &setup.protocol;
+    let offline = setup.build(Action::Approve, &setup.snapshot());
+    verify(&offline).unwrap();
+
+    let message = offline.message().unwrap();
+    assert_eq!(message.account_keys[0], setup.fee_payer.pubkey());
+    let content_hash = protocol.proposal(setup.tx_id).unwrap().content_hash();
+    let expected = gov::approve_transaction(
+        setup.owner,
+        setup.tx_id,
+        Some(content_hash),
+        protocol.execution_accounts(setup.tx_id, Some(&setup.owner)),
+    );
+    let compiled = &message.instructions[1];
+    assert_eq!(compiled.data, expected.data);
+    let keys: Vec<Pubkey> = compiled
+        .accounts
+        .iter()
+        .map(|i| message.account_keys[*i as usize])
+        .collect();
+    let expected_keys: Vec<Pubkey> = expected.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(keys, expected_keys);
+
+    // The proposal grows after the snapshot - the signed approval no longer
+    // applies to it
+    protocol
+        .process(
+            &[gov::append_list_batch(setup.owner, setup.tx_id, vec![ListUpdate {
+                account: Pubkey::new_unique(),
+                value: true,
+            }])],
+            &[setup.owner],
+        )
+        .unwrap();
+    assert_error(
+        protocol.process(&[expected], &[setup.owner]),
+        ErrorCode::ProposalMismatch,
+    );
+
+    // A snapshot of the proposal as it now reads goes through
+    let offline = setup.build(Action::Approve, &setup.snapshot());
+    verify(&offline).unwrap();
+    let content_hash = protocol.proposal(setup.tx_id).unwrap().content_hash();
+    let ix = gov::approve_transaction(
+        setup.owner,
+        setup.tx_id,
+        Some(content_hash),
+        protocol.execution_accounts(setup.tx_id, Some(&setup.owner)),
+    );
+    assert_eq!(offline.message().unwrap().instructions[1].data, ix.data);
+    protocol.process(&[ix], &[setup.owner]).unwrap();
+}
+
+#[test]
+fn build_checks_the_nonce_owner_and_proposal() {
+    let setup = setup("build");
+    let snapshot = setup.snapshot();
+    let args = |signer: Pubkey, tx_id: u64| BuildArgs {
+        action: Action::Execute,
+        tx_id,
+        signer,
+        nonce_account: setup.nonce_account,
+        nonce_authority: Some(setup.nonce_authority.pubkey()),
+        fee_payer: None,
+        remaining: Vec::new(),
+    };
+
+    // The fee payer defaults to the owner
+    let offline = OfflineTransaction::new(args(setup.owner, setup.tx_id), &snapshot).unwrap();
+    assert_eq!(offline.message().unwrap().account_keys[0], setup.owner);
+
+    let stranger = Pubkey::new_unique();
+    assert!(OfflineTransaction::new(args(stranger, setup.tx_id), &snapshot).is_err());
+    assert!(OfflineTransaction::new(args(setup.owner, setup.tx_id + 1), &snapshot).is_err());
+
+    // Nonce controlled by someone else, or not initialized
+    let mut other_authority = args(setup.owner, setup.tx_id);
+    other_authority.nonce_authority = None;
+    assert!(OfflineTransaction::new(other_authority, &snapshot).is_err());
+    let mut uninitialized = setup.snapshot();
+    uninitialized.nonce[4..8].copy_from_slice(&0u32.to_le_bytes());
+    assert!(OfflineTransaction::new(args(setup.owner, setup.tx_id), &uninitialized).is_err());
+}
+
+#[test]
+fn tampered_files_fail_verification() {
+    let setup = setup("tamper");
+    let protocol = &setup.protocol;
+    let snapshot = setup.snapshot();
+    let offline = setup.build(Action::Approve, &snapshot);
+
+    // A snapshot showing different content than the message commits to
+    let other_id = protocol.queue(list_batch(3));
+    let other = protocol.svm.account(&pda::proposal(other_id).0).unwrap().data;
+    let mut forged = setup.build(Action::Approve, &snapshot);
+    forged.proposal = STANDARD.encode(&other);
+    assert!(verify(&forged).unwrap_err().contains("snapshot holds proposal"));
+    forged.tx_id = other_id;
+    assert!(verify(&forged).is_err());
+
+    protocol
+        .process(
+            &[gov::append_list_batch(setup.owner, setup.tx_id, vec![ListUpdate {
+                account: Pubkey::new_unique(),
+                value: true,
+            }])],
+            &[setup.owner],
+        )
+        .unwrap();
+    let mut changed = setup.build(Action::Approve, &snapshot);
+    changed.proposal = STANDARD.encode(protocol.svm.account(&pda::proposal(setup.tx_id).0).unwrap().data);
+    assert!(verify(&changed).unwrap_err().contains("as snapshotted"));
+
+    // Message for another action, owner or nonce account
+    let mut action = setup.build(Action::Approve, &snapshot);
+    action.action = Action::Execute;
+    assert!(verify(&action).is_err());
+    let mut owner = setup.build(Action::Approve, &snapshot);
+    owner.signer = protocol.owners[1].to_string();
+    assert!(verify(&owner).is_err());
+    let mut nonce = setup.build(Action::Approve, &snapshot);
+    nonce.nonce_account = Pubkey::new_unique().to_string();
+    assert!(verify(&nonce).unwrap_err().contains("nonce"));
+
+    // Extra instructions
+    let mut message = offline.message().unwrap();
+    message.instructions.push(message.instructions[1].clone());
+    let mut extra = setup.build(Action::Approve, &snapshot);
+    extra.message = STANDARD.encode(bincode::serialize(&message).unwrap());
+    assert!(verify(&extra).unwrap_err().contains("expected 2 instructions"));
+
+    // `sign` refuses all of them
+    for (name, file) in [("forged", &forged), ("changed", &changed), ("extra", &extra)] {
+        let path = setup.path(name);
+        file.write(&path).unwrap();
+        assert!(offline::sign(&setup.fee_payer, &path, None, true).is_err());
+        assert!(OfflineTransaction::read(&path).unwrap().signatures.is_empty());
+    }
+}
+
+#[test]
+fn merge_combines_signatures_and_rejects_forgeries() {
+    let setup = setup("merge");
+    let built = setup.path("built.json");
+    setup.build(Action::Approve, &setup.snapshot()).write(&built).unwrap();
+
+    let by_payer = setup.path("payer.json");
+    let by_authority = setup.path("authority.json");
+    offline::sign(&setup.fee_payer, &built, Some(&by_payer), true).unwrap();
+    offline::sign(&setup.nonce_authority, &built, Some(&by_authority), true).unwrap();
+    // Not a signer of this transaction
+    assert!(offline::sign(&Keypair::new(), &built, None, true).is_err());
+
+    let merged = setup.path("merged.json");
+    offline::merge(&[&by_payer, &by_authority], &merged).unwrap();
+    let offline = OfflineTransaction::read(&merged).unwrap();
+    assert_eq!(offline.signatures.len(), 2);
+    assert_eq!(offline.missing_signers().unwrap(), vec![setup.owner]);
+    // Signatures land at their signer positions and verify
+    let transaction = offline.transaction().unwrap();
+    let message = transaction.message_data();
+    for (key, signature) in transaction.message.account_keys.iter().zip(&transaction.signatures) {
+        if offline.signatures.contains_key(&key.to_string()) {
+            assert!(signature.verify(key.as_ref(), &message));
+        }
+    }
+
+    // Another build of the same proposal is a different message
+    let rebuilt = setup.path("rebuilt.json");
+    setup.build(Action::Approve, &setup.snapshot()).write(&rebuilt).unwrap();
+    assert!(offline::merge(&[&by_payer, &rebuilt], &merged).is_err());
+
+    // A signature that doesn't verify, and two signatures for one key
+    let mut forged = OfflineTransaction::read(&by_payer).unwrap();
+    let authority_signature = OfflineTransaction::read(&by_authority)
+        .unwrap()
+        .signatures
+        .remove(&setup.nonce_authority.pubkey().to_string())
+        .unwrap();
+    forged
+        .signatures
+        .insert(setup.fee_payer.pubkey().to_string(), authority_signature);
+    let forged_path = setup.path("forged.json");
+    forged.write(&forged_path).unwrap();
+    assert!(offline::merge(&[&forged_path, &by_authority], &merged).is_err());
+    assert!(offline::merge(&[&by_payer, &forged_path], &merged).is_err());
+
+    // A signature from a key that doesn't sign this transaction
+    let mut stranger = OfflineTransaction::read(&by_payer).unwrap();
+    let key = Keypair::new();
+    let signature = key.sign_message(&offline.message().unwrap().serialize());
+    stranger.signatures.insert(key.pubkey().to_string(), signature.to_string());
+    let stranger_path = setup.path("stranger.json");
+    stranger.write(&stranger_path).unwrap();
+    assert!(offline::merge(&[&stranger_path, &by_authority], &merged).is_err());
+}
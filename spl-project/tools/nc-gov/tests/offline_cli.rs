@@ -0,0 +1,228 @@
+// `nc-gov offline sign` / `merge` on the files `offline build` writes - both
+// run without a network, so the binary is driven directly
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use anchor_lang::{AccountSerialize, AnchorSerialize};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use nc_sdk::accounts::{Governance, PendingTransaction};
+use nc_sdk::governance as gov;
+use nc_sdk::multisig_governance::{TransactionStatus, TransactionType};
+use serde_json::{json, Value};
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signature, Signer};
+
+const TX_ID: u64 = 7;
+
+struct Setup {
+    owner: Keypair,
+    fee_payer: Keypair,
+    nonce_account: Pubkey,
+    dir: PathBuf,
+}
+
+fn setup(name: &str) -> Setup {
+    let dir = std::env::temp_dir().join(format!("nc-gov-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let setup = Setup {
+        owner: Keypair::new(),
+        fee_payer: Keypair::new(),
+        nonce_account: Pubkey::new_unique(),
+        dir,
+    };
+    write_keypair_file(&setup.owner, setup.path("owner.json")).unwrap();
+    write_keypair_file(&setup.fee_payer, setup.path("payer.json")).unwrap();
+    setup
+}
+
+fn account_data<T: AccountSerialize>(account: &T) -> String {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    STANDARD.encode(data)
+}
+
+impl Setup {
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    // What `offline build` writes for an approval of `approve_id`, signed by
+    // no one yet
+    fn write_unsigned(&self, name: &str, approve_id: u64) -> PathBuf {
+        let owner = self.owner.pubkey();
+        let governance = Governance {
+            cooldown_period: 90 * 60,
+            required_approvals: 2,
+            nc_token: Pubkey::new_unique(),
+            nc_token_state: Pubkey::new_unique(),
+            token_set: true,
+            next_transaction_id: TX_ID + 1,
+            admin: Pubkey::new_unique(),
+            owners: vec![owner, Pubkey::new_unique()],
+            config_nonce: 1,
+            require_reapproval: false,
+            guardians: Vec::new(),
+            guardian_quorum: 0,
+            max_pause_duration: 24 * 60 * 60,
+            guardian_pause_started_at: 0,
+            guardian_pause_cooldown: 0,
+            bump: 255,
+        };
+        let proposal = PendingTransaction {
+            id: TX_ID,
+            tx_type: TransactionType::Restrict,
+            status: TransactionStatus::Pending,
+            initiator: owner,
+            target: Pubkey::new_unique(),
+            amount: 0,
+            data: (Pubkey::new_unique(), true).try_to_vec().unwrap(),
+            timestamp: 1_700_000_000,
+            execute_after: 1_700_005_400,
+            approval_count: 0,
+            rejection_reason: String::new(),
+            rejector: Pubkey::default(),
+            rejection_rent: 0,
+            config_nonce: 1,
+            title: "Restrict the old bridge".to_string(),
+            description_uri: String::new(),
+            description_hash: [0; 32],
+            executed_count: 0,
+            exists: true,
+            bump: 255,
+        };
+        let instruction =
+            gov::approve_transaction(owner, approve_id, Some(proposal.content_hash()), Vec::new());
+        let mut message = Message::new_with_nonce(
+            vec![instruction],
+            Some(&self.fee_payer.pubkey()),
+            &self.nonce_account,
+            &owner,
+        );
+        message.recent_blockhash = Hash::new_unique();
+
+        let path = self.path(name);
+        let file = json!({
+            "action": "approve",
+            "tx_id": TX_ID,
+            "signer": owner.to_string(),
+            "nonce_account": self.nonce_account.to_string(),
+            "snapshot_time": 1_700_000_100,
+            "governance": account_data(&governance),
+            "proposal": account_data(&proposal),
+            "message": STANDARD.encode(bincode::serialize(&message).unwrap()),
+        });
+        fs::write(&path, serde_json::to_string_pretty(&file).unwrap()).unwrap();
+        path
+    }
+
+    fn sign(&self, keypair: &str, file: &Path, out: &Path) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_nc-gov"))
+            .arg("-k")
+            .arg(self.path(keypair))
+            .args(["offline", "sign", "--yes", "-o"])
+            .arg(out)
+            .arg(file)
+            .output()
+            .unwrap()
+    }
+
+    fn merge(&self, files: &[&Path], out: &Path) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_nc-gov"))
+            .args(["offline", "merge", "-o"])
+            .arg(out)
+            .args(files)
+            .output()
+            .unwrap()
+    }
+}
+
+fn read(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn signs_and_merges_an_approval_built_against_a_nonce() {
+    let setup = setup("sign");
+    let owner = setup.owner.pubkey();
+    let unsigned = setup.write_unsigned("approve.json", TX_ID);
+
+    let by_owner = setup.path("approve.owner.json");
+    let output = setup.sign("owner.json", &unsigned, &by_owner);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let signed = read(&by_owner);
+    let message: Message = bincode::deserialize(
+        &STANDARD
+            .decode(signed["message"].as_str().unwrap())
+            .unwrap(),
+    )
+    .unwrap();
+    let signature: Signature = signed["signatures"][owner.to_string()]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(signature.verify(owner.as_ref(), &message.serialize()));
+
+    let by_payer = setup.path("approve.payer.json");
+    assert!(setup
+        .sign("payer.json", &unsigned, &by_payer)
+        .status
+        .success());
+
+    let merged = setup.path("approve.signed.json");
+    let output = setup.merge(&[&by_owner, &by_payer], &merged);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("still missing"));
+    assert_eq!(read(&merged)["signatures"].as_object().unwrap().len(), 2);
+}
+
+#[test]
+fn refuses_to_sign_a_message_for_another_proposal() {
+    let setup = setup("tampered");
+    // The file names #7 but the message approves #8
+    let tampered = setup.write_unsigned("approve.json", TX_ID + 1);
+
+    let out = setup.path("approve.owner.json");
+    let output = setup.sign("owner.json", &tampered, &out);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("second instruction is not"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!out.exists());
+}
+
+#[test]
+fn merge_rejects_copies_of_different_messages() {
+    let setup = setup("merge");
+    let first = setup.write_unsigned("first.json", TX_ID);
+    // Same instruction against another durable nonce value
+    let second = setup.write_unsigned("second.json", TX_ID);
+    let (by_owner, by_payer) = (
+        setup.path("first.owner.json"),
+        setup.path("second.payer.json"),
+    );
+    assert!(setup.sign("owner.json", &first, &by_owner).status.success());
+    assert!(setup
+        .sign("payer.json", &second, &by_payer)
+        .status
+        .success());
+
+    let output = setup.merge(&[&by_owner, &by_payer], &setup.path("merged.json"));
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("signs a different message"),
+        "{}",
+        stderr(&output)
+    );
+}
@@ -0,0 +1,164 @@
+// Execution accounts, list batch chunking and hash arguments
+
+use nc_gov::parse_hash32;
+use nc_gov::plan::{self, split_batch, BATCH_ENTRIES_PER_TX};
+use nc_harness::fixture::{Protocol, DEFAULT_COOLDOWN};
+use nc_sdk::governance::{self as gov, Proposal};
+use nc_sdk::multisig_governance::TransactionStatus;
+use nc_sdk::nc_token::{AddressList, ListUpdate};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+fn execution_accounts(protocol: &Protocol, tx_id: u64, approver: Option<&Pubkey>) -> Vec<AccountMeta> {
+    plan::execution_accounts(
+        &protocol.governance(),
+        &protocol.proposal(tx_id).unwrap(),
+        approver,
+        |keys| Ok(keys.iter().map(|key| protocol.svm.account(key).map(|a| a.data)).collect()),
+    )
+    .unwrap()
+}
+
+fn updates(count: usize) -> Vec<ListUpdate> {
+    (0..count)
+        .map(|_| ListUpdate {
+            account: Pubkey::new_unique(),
+            value: true,
+        })
+        .collect()
+}
+
+#[test]
+fn execution_accounts_list_other_approvals_and_freeze_accounts() {
+    let protocol = Protocol::new(3, 3);
+    let holder = protocol.wallet();
+    let accounts = [protocol.token_account(&holder, 1), protocol.token_account(&holder, 2)];
+    let tx_id = protocol.queue(Proposal::Blacklist {
+        account: holder,
+        value: true,
+        reason_code: 1,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+        freeze_token_accounts: accounts.to_vec(),
+    });
+    let [first, second, last] = [protocol.owners[0], protocol.owners[1], protocol.owners[2]];
+
+    // Nothing approved yet - only what the freeze needs, the mint once
+    let freeze = vec![
+        AccountMeta::new(accounts[0], false),
+        AccountMeta::new(accounts[1], false),
+        AccountMeta::new_readonly(protocol.mint, false),
+        AccountMeta::new_readonly(anchor_spl::token::ID, false),
+    ];
+    assert_eq!(execution_accounts(&protocol, tx_id, Some(&first)), freeze);
+
+    protocol.approve(&first, tx_id).unwrap();
+    protocol.approve(&second, tx_id).unwrap();
+    // Approvals that exist, minus the approver's own
+    let mut expected = gov::approval_accounts(tx_id, &[first, second]);
+    expected.extend(freeze.clone());
+    assert_eq!(execution_accounts(&protocol, tx_id, Some(&last)), expected);
+    // What the harness approves with in every other test
+    assert_eq!(expected, protocol.execution_accounts(tx_id, Some(&last)));
+    let mut without_second = gov::approval_accounts(tx_id, &[first]);
+    without_second.extend(freeze);
+    assert_eq!(execution_accounts(&protocol, tx_id, Some(&second)), without_second);
+
+    // The last approval executes with them and freezes both accounts
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    let remaining = execution_accounts(&protocol, tx_id, Some(&last));
+    protocol
+        .process(&[gov::approve_transaction(last, tx_id, None, remaining)], &[last])
+        .unwrap();
+    assert_eq!(protocol.proposal(tx_id).unwrap().status, TransactionStatus::AutoExecuted);
+    assert!(accounts.iter().all(|account| protocol.is_frozen(account)));
+}
+
+#[test]
+fn execution_accounts_need_the_freeze_token_accounts() {
+    let protocol = Protocol::new(1, 1);
+    let missing = Pubkey::new_unique();
+    let tx_id = protocol.queue(Proposal::Blacklist {
+        account: protocol.wallet(),
+        value: true,
+        reason_code: 1,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+        freeze_token_accounts: vec![missing],
+    });
+    let result = plan::execution_accounts(
+        &protocol.governance(),
+        &protocol.proposal(tx_id).unwrap(),
+        None,
+        |keys| Ok(keys.iter().map(|key| protocol.svm.account(key).map(|a| a.data)).collect()),
+    );
+    assert!(result.unwrap_err().to_string().contains(&missing.to_string()));
+}
+
+#[test]
+fn list_batches_split_into_a_queued_chunk_and_appends() {
+    let sizes = |count: usize| {
+        let (queued, appends) = split_batch(updates(count));
+        (queued.len(), appends.iter().map(Vec::len).collect::<Vec<_>>())
+    };
+    assert_eq!(sizes(1), (1, vec![]));
+    assert_eq!(sizes(BATCH_ENTRIES_PER_TX), (BATCH_ENTRIES_PER_TX, vec![]));
+    assert_eq!(sizes(BATCH_ENTRIES_PER_TX + 1), (BATCH_ENTRIES_PER_TX, vec![1]));
+    assert_eq!(sizes(2 * BATCH_ENTRIES_PER_TX + 5), (BATCH_ENTRIES_PER_TX, vec![BATCH_ENTRIES_PER_TX, 5]));
+
+    // Order is kept across the chunks
+    let all = updates(2 * BATCH_ENTRIES_PER_TX + 5);
+    let (queued, appends) = split_batch(all.clone());
+    let rejoined: Vec<Pubkey> = queued
+        .iter()
+        .chain(appends.iter().flatten())
+        .map(|update| update.account)
+        .collect();
+    assert_eq!(rejoined, all.iter().map(|update| update.account).collect::<Vec<_>>());
+}
+
+#[test]
+fn split_list_batches_queue_append_and_execute() {
+    let protocol = Protocol::new(2, 2);
+    let owner = protocol.owners[0];
+    let all = updates(2 * BATCH_ENTRIES_PER_TX + 5);
+    let (queued, appends) = split_batch(all.clone());
+
+    let tx_id = protocol.queue(Proposal::ListBatch {
+        list: AddressList::Restricted,
+        updates: queued,
+        reason_code: 0,
+        evidence_hash: [0; 32],
+        expires_at: 0,
+    });
+    // Each chunk fits one transaction
+    for chunk in appends {
+        protocol
+            .process(&[gov::append_list_batch(owner, tx_id, chunk)], &[owner])
+            .unwrap();
+    }
+
+    protocol.svm.advance(DEFAULT_COOLDOWN);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    while protocol.proposal(tx_id).unwrap().status == TransactionStatus::Pending {
+        protocol.crank(tx_id).unwrap();
+    }
+    let restricted = protocol.state().restricted_list;
+    assert!(all.iter().all(|update| restricted.contains(&update.account)));
+}
+
+#[test]
+fn hash_arguments_are_64_hex_digits() {
+    let hash = [0xab; 32];
+    let hex: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+    assert_eq!(parse_hash32(&hex), Ok(hash));
+    assert_eq!(parse_hash32(&format!("0x{}", hex.to_uppercase())), Ok(hash));
+
+    assert!(parse_hash32(&hex[..62]).is_err());
+    assert!(parse_hash32(&format!("{}zz", &hex[..62])).is_err());
+    // 64 bytes, but not 64 ASCII digits - rejected rather than split
+    // inside a character
+    assert!(parse_hash32(&"é".repeat(32)).is_err());
+    assert!(parse_hash32(&format!("aé{}", &hex[..61])).is_err());
+}
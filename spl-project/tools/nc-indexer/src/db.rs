@@ -0,0 +1,235 @@
+// SQLite store: one row per transaction and per event
+//
+// `events.data` is the decoded event (Debug form) for reading, and
+// `events.payload` the raw discriminator + borsh bytes, so rows can be
+// re-decoded and searched for any 32-byte address they contain.
+
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use nc_sdk::events::{self, Event, GovernanceEvent};
+use nc_sdk::{GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+
+use crate::{Result, Transaction};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature  TEXT PRIMARY KEY,
+    slot       INTEGER NOT NULL,
+    block_time INTEGER,
+    failed     INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    signature  TEXT NOT NULL REFERENCES transactions (signature),
+    position   INTEGER NOT NULL,
+    slot       INTEGER NOT NULL,
+    block_time INTEGER,
+    program    TEXT NOT NULL,
+    name       TEXT NOT NULL,
+    tx_id      INTEGER,
+    data       TEXT NOT NULL,
+    payload    BLOB NOT NULL,
+    PRIMARY KEY (signature, position)
+);
+CREATE INDEX IF NOT EXISTS events_slot ON events (slot);
+CREATE INDEX IF NOT EXISTS events_name ON events (name);
+CREATE INDEX IF NOT EXISTS events_tx_id ON events (tx_id);
+CREATE TABLE IF NOT EXISTS cursors (
+    address   TEXT PRIMARY KEY,
+    signature TEXT NOT NULL
+);
+";
+
+pub struct Db {
+    conn: Connection,
+}
+
+// Query filters (all optional, combined with AND)
+#[derive(Default)]
+pub struct Filter {
+    pub name: Option<String>,
+    pub program: Option<Pubkey>,
+    pub account: Option<Pubkey>,
+    pub tx_id: Option<u64>,
+    pub signature: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: u32,
+}
+
+pub struct Row {
+    pub signature: String,
+    pub slot: u64,
+    pub time: Option<String>, // UTC, from block_time
+    pub program: String,
+    pub name: String,
+    pub tx_id: Option<u64>,
+    pub data: String,
+}
+
+impl Db {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    // Stores a transaction and its events, returning the event count (None
+    // if it was already indexed). Failed transactions are recorded without
+    // events, since nothing they emitted took effect
+    pub fn insert(&mut self, tx: &Transaction) -> Result<Option<usize>> {
+        let db = self.conn.transaction()?;
+        let inserted = db.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time, failed)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![tx.signature, tx.slot, tx.block_time, tx.failed],
+        )?;
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        let logged = if tx.failed {
+            Vec::new()
+        } else {
+            // Event CPIs, or log lines from before the switch to event CPI
+            let inner = tx
+                .inner_instructions
+                .iter()
+                .map(|(program_id, data)| (program_id, &data[..]));
+            let mut logged = events::parse_inner_instructions(inner);
+            logged.extend(events::parse_logged(&tx.logs));
+            logged
+        };
+        for (position, logged) in logged.iter().enumerate() {
+            db.execute(
+                "INSERT INTO events
+                 (signature, position, slot, block_time, program, name, tx_id, data, payload)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    tx.signature,
+                    position,
+                    tx.slot,
+                    tx.block_time,
+                    program_name(&logged.program_id),
+                    logged.event.name(),
+                    tx_id(&logged.event),
+                    format!("{:?}", logged.event.fields()),
+                    logged.data,
+                ],
+            )?;
+        }
+        db.commit()?;
+        Ok(Some(logged.len()))
+    }
+
+    // Newest signature synced for a program address
+    pub fn cursor(&self, address: &Pubkey) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT signature FROM cursors WHERE address = ?1",
+                [address.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_cursor(&self, address: &Pubkey, signature: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO cursors (address, signature) VALUES (?1, ?2)
+             ON CONFLICT (address) DO UPDATE SET signature = excluded.signature",
+            params![address.to_string(), signature],
+        )?;
+        Ok(())
+    }
+
+    // Matching events, oldest first. `tx_id` selects every event of the
+    // transactions that touched the proposal, token-side effects included
+    pub fn query(&self, filter: &Filter) -> Result<Vec<Row>> {
+        let mut sql = String::from(
+            "SELECT signature, slot, datetime(block_time, 'unixepoch'), program, name, tx_id, data
+             FROM events WHERE 1 = 1",
+        );
+        let mut values: Vec<Value> = Vec::new();
+        let mut condition = |clause: &str, value: Value| {
+            sql.push_str(" AND ");
+            sql.push_str(clause);
+            values.push(value);
+        };
+        if let Some(name) = &filter.name {
+            condition("name = ?", Value::Text(name.clone()));
+        }
+        if let Some(program) = &filter.program {
+            condition(
+                "program = ?",
+                Value::Text(program_name(program).to_string()),
+            );
+        }
+        if let Some(account) = &filter.account {
+            condition(
+                "instr(payload, ?) > 0",
+                Value::Blob(account.to_bytes().to_vec()),
+            );
+        }
+        if let Some(tx_id) = filter.tx_id {
+            condition(
+                "signature IN (SELECT signature FROM events WHERE tx_id = ?)",
+                Value::Integer(tx_id as i64),
+            );
+        }
+        if let Some(signature) = &filter.signature {
+            condition("signature = ?", Value::Text(signature.clone()));
+        }
+        if let Some(since) = filter.since {
+            condition("block_time >= ?", Value::Integer(since));
+        }
+        if let Some(until) = filter.until {
+            condition("block_time < ?", Value::Integer(until));
+        }
+        sql.push_str(" ORDER BY slot, signature, position LIMIT ?");
+        values.push(Value::Integer(filter.limit as i64));
+
+        let mut statement = self.conn.prepare(&sql)?;
+        let rows = statement.query_map(params_from_iter(values), |row| {
+            Ok(Row {
+                signature: row.get(0)?,
+                slot: row.get(1)?,
+                time: row.get(2)?,
+                program: row.get(3)?,
+                name: row.get(4)?,
+                tx_id: row.get(5)?,
+                data: row.get(6)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+fn program_name(program_id: &Pubkey) -> &'static str {
+    if *program_id == NC_TOKEN_PROGRAM_ID {
+        "nc_token"
+    } else if *program_id == GOVERNANCE_PROGRAM_ID {
+        "governance"
+    } else {
+        "unknown"
+    }
+}
+
+// Governance proposal an event belongs to
+fn tx_id(event: &Event) -> Option<u64> {
+    let Event::Governance(event) = event else {
+        return None;
+    };
+    match event {
+        GovernanceEvent::TransactionQueued(e) => Some(e.tx_id),
+        GovernanceEvent::TransactionApproved(e) => Some(e.tx_id),
+        GovernanceEvent::TransactionRejected(e) => Some(e.tx_id),
+        GovernanceEvent::ProposalClosed(e) => Some(e.tx_id),
+        GovernanceEvent::TransactionExecuted(e) => Some(e.tx_id),
+        GovernanceEvent::ListBatchAppended(e) => Some(e.tx_id),
+        GovernanceEvent::ListBatchChunkExecuted(e) => Some(e.tx_id),
+        _ => None,
+    }
+}
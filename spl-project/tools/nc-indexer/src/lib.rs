@@ -0,0 +1,99 @@
+// nc-indexer library - transaction decoding, the SQLite store and the RPC
+// client behind the nc-indexer command line
+
+pub mod db;
+pub mod rpc;
+
+use std::error::Error;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::Value;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+// A transaction as far as the indexer cares
+pub struct Transaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub logs: Vec<String>,
+    pub inner_instructions: Vec<(Pubkey, Vec<u8>)>, // program id, data
+}
+
+impl Transaction {
+    // From a getTransaction result or the flat saved form
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let meta = &value["meta"];
+        let signature = value["signature"]
+            .as_str()
+            .or_else(|| value["transaction"]["signatures"][0].as_str())
+            .ok_or("transaction without a signature")?;
+        let (logs, err, inner_instructions) = if meta.is_object() {
+            (&meta["logMessages"], &meta["err"], inner_instructions(value)?)
+        } else {
+            (&value["logs"], &value["err"], flat_inner_instructions(value)?)
+        };
+        Ok(Self {
+            signature: signature.to_string(),
+            slot: value["slot"].as_u64().ok_or("transaction without a slot")?,
+            block_time: value["blockTime"].as_i64(),
+            failed: !err.is_null(),
+            logs: logs
+                .as_array()
+                .map(|lines| {
+                    lines
+                        .iter()
+                        .filter_map(|line| line.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            inner_instructions,
+        })
+    }
+}
+
+// meta.innerInstructions of a getTransaction result, program ids resolved
+// through the message's account keys followed by any addresses loaded from
+// lookup tables
+fn inner_instructions(value: &Value) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let meta = &value["meta"];
+    let mut keys: Vec<&Value> = Vec::new();
+    for list in [
+        &value["transaction"]["message"]["accountKeys"],
+        &meta["loadedAddresses"]["writable"],
+        &meta["loadedAddresses"]["readonly"],
+    ] {
+        keys.extend(list.as_array().into_iter().flatten());
+    }
+
+    let mut instructions = Vec::new();
+    for group in meta["innerInstructions"].as_array().into_iter().flatten() {
+        for ix in group["instructions"].as_array().into_iter().flatten() {
+            let program_id = ix["programIdIndex"]
+                .as_u64()
+                .and_then(|index| keys.get(index as usize))
+                .and_then(|key| key.as_str())
+                .ok_or("inner instruction with an unknown program")?;
+            instructions.push((Pubkey::from_str(program_id)?, decode_data(&ix["data"])?));
+        }
+    }
+    Ok(instructions)
+}
+
+fn flat_inner_instructions(value: &Value) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let mut instructions = Vec::new();
+    for ix in value["innerInstructions"].as_array().into_iter().flatten() {
+        let program_id = ix["programId"]
+            .as_str()
+            .ok_or("inner instruction without a programId")?;
+        instructions.push((Pubkey::from_str(program_id)?, decode_data(&ix["data"])?));
+    }
+    Ok(instructions)
+}
+
+fn decode_data(data: &Value) -> Result<Vec<u8>> {
+    let data = data.as_str().ok_or("inner instruction without data")?;
+    Ok(bs58::decode(data).into_vec()?)
+}
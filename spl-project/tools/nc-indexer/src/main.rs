@@ -0,0 +1,221 @@
+// nc-indexer - NC Token and MultiSig Governance events in SQLite
+//
+// `sync` walks both programs' transaction history on an RPC node (a local
+// validator by default), `import` reads transactions saved to a file. Both
+// decode events with nc_sdk::events - event CPIs from the inner
+// instructions, and events logged before the programs switched to event
+// CPI - and store one row per event; `query` filters them for audits.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use nc_sdk::{GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+use serde_json::{json, Value};
+
+use nc_indexer::db::{Db, Filter};
+use nc_indexer::rpc::{self, RpcClient};
+use nc_indexer::{Result, Transaction};
+
+#[derive(Parser)]
+#[command(
+    name = "nc-indexer",
+    about = "NC Token and MultiSig Governance event indexer"
+)]
+struct Cli {
+    /// SQLite database file
+    #[arg(long, short = 'd', global = true, default_value = "nc-events.db")]
+    db: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index new transactions of both programs from an RPC node
+    Sync {
+        /// JSON-RPC endpoint
+        #[arg(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// Keep syncing, polling every SECONDS
+        #[arg(long, value_name = "SECONDS")]
+        follow: Option<u64>,
+    },
+    /// Index transactions saved to a file: JSON lines (or one JSON array) of
+    /// getTransaction results (json encoding) or {"signature", "slot",
+    /// "blockTime", "logs", "innerInstructions", "err"}, inner instructions
+    /// as {"programId", "data" (base58)} (all but signature and slot
+    /// optional)
+    Import { file: PathBuf },
+    /// Print stored events, oldest first
+    Query(QueryArgs),
+}
+
+#[derive(Args)]
+struct QueryArgs {
+    /// Event name, e.g. TransferEvent or TransactionQueued
+    #[arg(long)]
+    event: Option<String>,
+    #[arg(long, value_enum)]
+    program: Option<Program>,
+    /// Events that contain this address in any field
+    #[arg(long)]
+    account: Option<Pubkey>,
+    /// Everything in the transactions that queued, approved, executed or
+    /// closed this proposal
+    #[arg(long)]
+    tx_id: Option<u64>,
+    #[arg(long)]
+    signature: Option<String>,
+    /// Unix time, inclusive
+    #[arg(long)]
+    since: Option<i64>,
+    /// Unix time, exclusive
+    #[arg(long)]
+    until: Option<i64>,
+    #[arg(long, default_value_t = 100)]
+    limit: u32,
+    /// One JSON object per line
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Program {
+    NcToken,
+    Governance,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let mut db = Db::open(&cli.db)?;
+    match cli.command {
+        Command::Sync { url, follow } => {
+            let rpc = RpcClient::new(&url);
+            loop {
+                sync(&rpc, &mut db)?;
+                let Some(seconds) = follow else {
+                    return Ok(());
+                };
+                thread::sleep(Duration::from_secs(seconds));
+            }
+        }
+        Command::Import { file } => import(&mut db, &fs::read_to_string(&file)?),
+        Command::Query(args) => query(&db, args),
+    }
+}
+
+fn sync(rpc: &RpcClient, db: &mut Db) -> Result<()> {
+    let (mut transactions, mut events) = (0, 0);
+    for program in [NC_TOKEN_PROGRAM_ID, GOVERNANCE_PROGRAM_ID] {
+        // Newest first, paged back to the last synced signature
+        let until = db.cursor(&program)?;
+        let mut signatures: Vec<String> = Vec::new();
+        loop {
+            let page = rpc.get_signatures_for_address(
+                &program,
+                signatures.last().map(String::as_str),
+                until.as_deref(),
+            )?;
+            let done = page.len() < rpc::MAX_SIGNATURES;
+            signatures.extend(page);
+            if done {
+                break;
+            }
+        }
+
+        // Oldest first, so the cursor only moves past indexed transactions
+        for signature in signatures.iter().rev() {
+            let Some(tx) = rpc.get_transaction(signature)? else {
+                break;
+            };
+            if let Some(count) = db.insert(&tx)? {
+                transactions += 1;
+                events += count;
+            }
+            db.set_cursor(&program, signature)?;
+        }
+    }
+    println!("indexed {} transactions, {} events", transactions, events);
+    Ok(())
+}
+
+fn import(db: &mut Db, contents: &str) -> Result<()> {
+    let values: Vec<Value> = if contents.trim_start().starts_with('[') {
+        serde_json::from_str(contents)?
+    } else {
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?
+    };
+    let (mut transactions, mut events, mut skipped) = (0, 0, 0);
+    for value in &values {
+        match db.insert(&Transaction::from_json(value)?)? {
+            Some(count) => {
+                transactions += 1;
+                events += count;
+            }
+            None => skipped += 1,
+        }
+    }
+    println!(
+        "indexed {} transactions, {} events ({} already indexed)",
+        transactions, events, skipped
+    );
+    Ok(())
+}
+
+fn query(db: &Db, args: QueryArgs) -> Result<()> {
+    let rows = db.query(&Filter {
+        name: args.event,
+        program: args.program.map(|program| match program {
+            Program::NcToken => NC_TOKEN_PROGRAM_ID,
+            Program::Governance => GOVERNANCE_PROGRAM_ID,
+        }),
+        account: args.account,
+        tx_id: args.tx_id,
+        signature: args.signature,
+        since: args.since,
+        until: args.until,
+        limit: args.limit,
+    })?;
+    for row in rows {
+        if args.json {
+            let object = json!({
+                "signature": row.signature,
+                "slot": row.slot,
+                "time": row.time,
+                "program": row.program,
+                "event": row.name,
+                "tx_id": row.tx_id,
+                "data": row.data,
+            });
+            println!("{}", object);
+        } else {
+            println!(
+                "{} slot {} {} {} {}",
+                row.time.as_deref().unwrap_or("-"),
+                row.slot,
+                row.signature,
+                row.program,
+                row.name
+            );
+            println!("    {}", row.data);
+        }
+    }
+    Ok(())
+}
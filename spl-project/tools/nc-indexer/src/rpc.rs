@@ -0,0 +1,84 @@
+// Minimal JSON-RPC client (transaction history only)
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::{json, Value};
+
+use crate::{Result, Transaction};
+
+// getSignaturesForAddress page size limit
+pub const MAX_SIGNATURES: usize = 1000;
+
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: Value = ureq::post(&self.url)
+            .send_json(request)
+            .map_err(|e| format!("{} {}: {}", method, self.url, e))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            return Err(format!("{} failed: {}", method, error).into());
+        }
+        Ok(response["result"].clone())
+    }
+
+    // One page of signatures for `address`, newest first, older than
+    // `before` and newer than `until`
+    pub fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let result = self.call(
+            "getSignaturesForAddress",
+            json!([address.to_string(), {
+                "limit": MAX_SIGNATURES,
+                "before": before,
+                "until": until,
+                "commitment": "confirmed",
+            }]),
+        )?;
+        result
+            .as_array()
+            .ok_or("malformed getSignaturesForAddress")?
+            .iter()
+            .map(|entry| {
+                entry["signature"]
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| "malformed getSignaturesForAddress".into())
+            })
+            .collect()
+    }
+
+    // None until the node has the transaction at confirmed commitment
+    pub fn get_transaction(&self, signature: &str) -> Result<Option<Transaction>> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        Transaction::from_json(&result).map(Some)
+    }
+}
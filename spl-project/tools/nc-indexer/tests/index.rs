@@ -0,0 +1,370 @@
+// Indexing: decoding getTransaction results and the flat saved form,
+// storing transactions once, and every query filter
+
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use nc_harness::fixture::{metadata, Protocol};
+use nc_harness::Outcome;
+use nc_indexer::db::{Db, Filter, Row};
+use nc_indexer::Transaction;
+use nc_sdk::governance::{self as gov, Proposal};
+use nc_sdk::{GOVERNANCE_PROGRAM_ID, NC_TOKEN_PROGRAM_ID};
+use serde_json::{json, Value};
+
+// getTransaction result (json encoding) for a harness outcome. Inner
+// instructions index the message's account keys, or past them the
+// addresses loaded from lookup tables - here every program but the first.
+fn get_transaction(signature: &str, slot: u64, block_time: i64, outcome: &Outcome) -> Value {
+    let mut programs: Vec<Pubkey> = Vec::new();
+    for ix in &outcome.inner_instructions {
+        if !programs.contains(&ix.program_id) {
+            programs.push(ix.program_id);
+        }
+    }
+    let (fee_payer, loaded_writable) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let account_keys = [fee_payer, programs[0]];
+    let mut keys = account_keys.to_vec();
+    keys.push(loaded_writable);
+    keys.extend(&programs[1..]);
+
+    let instructions: Vec<Value> = outcome
+        .inner_instructions
+        .iter()
+        .map(|ix| {
+            json!({
+                "programIdIndex": keys.iter().position(|key| *key == ix.program_id),
+                "accounts": [],
+                "data": bs58::encode(&ix.data).into_string(),
+                "stackHeight": ix.stack_height,
+            })
+        })
+        .collect();
+    json!({
+        "slot": slot,
+        "blockTime": block_time,
+        "transaction": {
+            "signatures": [signature],
+            "message": {
+                "accountKeys": account_keys.map(|key| key.to_string()),
+                "instructions": [],
+            },
+        },
+        "meta": {
+            "err": null,
+            "logMessages": outcome.logs,
+            "innerInstructions": [{ "index": 0, "instructions": instructions }],
+            "loadedAddresses": {
+                "writable": [loaded_writable.to_string()],
+                "readonly": programs[1..].iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+            },
+        },
+    })
+}
+
+// The flat saved form
+fn flat(signature: &str, slot: u64, block_time: i64, outcome: &Outcome) -> Value {
+    let inner: Vec<Value> = outcome
+        .inner_instructions
+        .iter()
+        .map(|ix| {
+            json!({
+                "programId": ix.program_id.to_string(),
+                "data": bs58::encode(&ix.data).into_string(),
+            })
+        })
+        .collect();
+    json!({
+        "signature": signature,
+        "slot": slot,
+        "blockTime": block_time,
+        "logs": outcome.logs,
+        "innerInstructions": inner,
+        "err": null,
+    })
+}
+
+fn inner(outcome: &Outcome) -> Vec<(Pubkey, Vec<u8>)> {
+    outcome
+        .inner_instructions
+        .iter()
+        .map(|ix| (ix.program_id, ix.data.clone()))
+        .collect()
+}
+
+fn open() -> Db {
+    Db::open(Path::new(":memory:")).unwrap()
+}
+
+fn names(rows: &[Row]) -> Vec<&str> {
+    rows.iter().map(|row| row.name.as_str()).collect()
+}
+
+// Wallet with a funded token account, and a transfer out of it
+fn transfer(protocol: &Protocol) -> (Pubkey, Outcome) {
+    let wallet = protocol.wallet();
+    let from = protocol.token_account(&wallet, 1_000);
+    let to = protocol.token_account(&protocol.wallet(), 0);
+    let outcome = protocol.transfer(&wallet, &from, &to, 10).unwrap();
+    (wallet, outcome)
+}
+
+#[test]
+fn decodes_get_transaction_results_through_lookup_tables() {
+    let protocol = Protocol::new(3, 2);
+    let tx_id = protocol.queue(Proposal::Restrict {
+        account: Pubkey::new_unique(),
+        value: true,
+    });
+    protocol.svm.advance(protocol.governance().cooldown_period);
+    protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    // Approval that executes: events of both programs, plus the CPI into
+    // nc_token
+    let outcome = protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    let programs: Vec<Pubkey> = outcome.inner_instructions.iter().map(|ix| ix.program_id).collect();
+    assert!(programs.contains(&GOVERNANCE_PROGRAM_ID) && programs.contains(&NC_TOKEN_PROGRAM_ID));
+
+    let value = get_transaction("sig", 42, 1_700_000_000, &outcome);
+    let tx = Transaction::from_json(&value).unwrap();
+    assert_eq!(tx.signature, "sig");
+    assert_eq!(tx.slot, 42);
+    assert_eq!(tx.block_time, Some(1_700_000_000));
+    assert!(!tx.failed);
+    assert_eq!(tx.logs, outcome.logs);
+    assert_eq!(tx.inner_instructions, inner(&outcome));
+
+    // An index past the loaded addresses
+    let mut broken = value.clone();
+    broken["meta"]["loadedAddresses"]["readonly"] = json!([]);
+    assert!(Transaction::from_json(&broken).is_err());
+
+    let mut failed = value.clone();
+    failed["meta"]["err"] = json!({ "InstructionError": [0, { "Custom": 6000 }] });
+    assert!(Transaction::from_json(&failed).unwrap().failed);
+    let mut unsigned = value;
+    unsigned["transaction"]["signatures"] = json!([]);
+    assert!(Transaction::from_json(&unsigned).is_err());
+}
+
+#[test]
+fn decodes_the_flat_form() {
+    let protocol = Protocol::new(3, 2);
+    let (_, outcome) = transfer(&protocol);
+    let tx = Transaction::from_json(&flat("sig", 7, 1_000, &outcome)).unwrap();
+    assert_eq!(tx.signature, "sig");
+    assert_eq!((tx.slot, tx.block_time, tx.failed), (7, Some(1_000), false));
+    assert_eq!(tx.inner_instructions, inner(&outcome));
+
+    // Only signature and slot are required
+    let tx = Transaction::from_json(&json!({ "signature": "bare", "slot": 8 })).unwrap();
+    assert_eq!(tx.block_time, None);
+    assert!(!tx.failed && tx.logs.is_empty() && tx.inner_instructions.is_empty());
+    assert!(Transaction::from_json(&json!({ "signature": "no-slot" })).is_err());
+    assert!(Transaction::from_json(&json!({ "slot": 8 })).is_err());
+
+    let bad_program = json!({
+        "signature": "bad",
+        "slot": 9,
+        "innerInstructions": [{ "programId": "not-a-key", "data": "" }],
+    });
+    assert!(Transaction::from_json(&bad_program).is_err());
+    let bad_data = json!({
+        "signature": "bad",
+        "slot": 9,
+        "innerInstructions": [{ "programId": NC_TOKEN_PROGRAM_ID.to_string(), "data": "0OIl" }],
+    });
+    assert!(Transaction::from_json(&bad_data).is_err());
+}
+
+#[test]
+fn inserts_each_transaction_once_and_skips_failed_ones() {
+    let protocol = Protocol::new(3, 2);
+    let (_, outcome) = transfer(&protocol);
+    let mut db = open();
+    let all = Filter {
+        limit: 100,
+        ..Filter::default()
+    };
+
+    let tx = Transaction::from_json(&flat("sig", 7, 1_000, &outcome)).unwrap();
+    assert_eq!(db.insert(&tx).unwrap(), Some(1));
+    assert_eq!(db.insert(&tx).unwrap(), None);
+    // The same signature from another source is still a duplicate
+    let again = Transaction::from_json(&get_transaction("sig", 7, 1_000, &outcome)).unwrap();
+    assert_eq!(db.insert(&again).unwrap(), None);
+    assert_eq!(names(&db.query(&all).unwrap()), ["TransferEvent"]);
+
+    // A failed transaction is recorded, but nothing it emitted
+    let mut value = flat("failed", 8, 1_001, &outcome);
+    value["err"] = json!({ "InstructionError": [0, { "Custom": 6000 }] });
+    let failed = Transaction::from_json(&value).unwrap();
+    assert!(failed.failed);
+    assert_eq!(db.insert(&failed).unwrap(), Some(0));
+    assert_eq!(db.insert(&failed).unwrap(), None);
+    assert_eq!(db.query(&all).unwrap().len(), 1);
+
+    // Events logged before the switch to event CPI
+    let payload = outcome
+        .inner_instructions
+        .iter()
+        .find(|ix| ix.program_id == NC_TOKEN_PROGRAM_ID && ix.data.len() > 8)
+        .map(|ix| STANDARD.encode(&ix.data[8..]))
+        .unwrap();
+    let logged = json!({
+        "signature": "logged",
+        "slot": 9,
+        "logs": [
+            format!("Program {} invoke [1]", NC_TOKEN_PROGRAM_ID),
+            format!("Program data: {}", payload),
+            format!("Program {} success", NC_TOKEN_PROGRAM_ID),
+        ],
+    });
+    assert_eq!(db.insert(&Transaction::from_json(&logged).unwrap()).unwrap(), Some(1));
+    let rows = db.query(&all).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[1].signature.as_str(), rows[1].program.as_str()), ("logged", "nc_token"));
+    assert_eq!(rows[1].time, None);
+}
+
+#[test]
+fn query_filters() {
+    let protocol = Protocol::new(3, 2);
+    let mut db = open();
+    let mut index = |signature: &str, slot: u64, block_time: i64, outcome: &Outcome| {
+        let tx = Transaction::from_json(&get_transaction(signature, slot, block_time, outcome)).unwrap();
+        db.insert(&tx).unwrap().unwrap()
+    };
+
+    let (sender, outcome) = transfer(&protocol);
+    index("transfer", 10, 1_000, &outcome);
+
+    let module = Pubkey::new_unique();
+    let tx_id = protocol.governance().next_transaction_id;
+    let owner = protocol.owners[0];
+    let restrict = Proposal::Restrict {
+        account: module,
+        value: true,
+    };
+    let outcome = protocol
+        .process(&[gov::queue(owner, tx_id, restrict, metadata("module"))], &[owner])
+        .unwrap();
+    index("queue", 11, 2_000, &outcome);
+    protocol.svm.advance(protocol.governance().cooldown_period);
+    let outcome = protocol.approve(&protocol.owners[0], tx_id).unwrap();
+    index("approve", 12, 3_000, &outcome);
+    let outcome = protocol.approve(&protocol.owners[1], tx_id).unwrap();
+    assert_eq!(index("execute", 13, 4_000, &outcome), 4);
+
+    // Another proposal, queued but never approved
+    let other = protocol.queue(Proposal::Restrict {
+        account: Pubkey::new_unique(),
+        value: true,
+    });
+    let outcome = protocol.approve(&protocol.owners[0], other).unwrap();
+    index("other", 14, 5_000, &outcome);
+
+    let query = |filter: Filter| db.query(&filter).unwrap();
+    let all = || Filter {
+        limit: 100,
+        ..Filter::default()
+    };
+
+    // Oldest first, CPI events in execution order
+    assert_eq!(
+        names(&query(all())),
+        [
+            "TransferEvent",
+            "TransactionQueued",
+            "TransactionApproved",
+            "TransactionApproved",
+            "RestrictedSet",
+            "AddressRestricted",
+            "TransactionExecuted",
+            "TransactionApproved",
+        ]
+    );
+
+    let rows = query(Filter {
+        name: Some("TransactionApproved".to_string()),
+        ..all()
+    });
+    assert_eq!(rows.len(), 3);
+    assert!(rows.iter().all(|row| row.program == "governance"));
+
+    let rows = query(Filter {
+        program: Some(NC_TOKEN_PROGRAM_ID),
+        ..all()
+    });
+    assert_eq!(names(&rows), ["TransferEvent", "RestrictedSet"]);
+
+    // Any field holding the address
+    let rows = query(Filter {
+        account: Some(module),
+        ..all()
+    });
+    assert_eq!(names(&rows), ["RestrictedSet", "AddressRestricted"]);
+    let rows = query(Filter {
+        account: Some(sender),
+        ..all()
+    });
+    assert_eq!(names(&rows), ["TransferEvent"]);
+    let rows = query(Filter {
+        account: Some(protocol.owners[1]),
+        ..all()
+    });
+    assert_eq!(names(&rows), ["TransactionApproved", "TransactionExecuted"]);
+
+    // Whole transactions that touched the proposal, nc_token side included
+    let rows = query(Filter {
+        tx_id: Some(tx_id),
+        ..all()
+    });
+    assert_eq!(rows.len(), 6);
+    assert!(rows.iter().all(|row| ["queue", "approve", "execute"].contains(&row.signature.as_str())));
+    assert_eq!(rows[3].name, "RestrictedSet");
+    assert_eq!(rows[3].tx_id, None);
+    assert_eq!(rows[5].tx_id, Some(tx_id));
+
+    let rows = query(Filter {
+        signature: Some("execute".to_string()),
+        ..all()
+    });
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0].slot, 13);
+    assert_eq!(rows[0].time.as_deref(), Some("1970-01-01 01:06:40"));
+
+    // since is inclusive, until exclusive
+    let rows = query(Filter {
+        since: Some(3_000),
+        until: Some(5_000),
+        ..all()
+    });
+    assert_eq!(rows.len(), 5);
+    assert!(rows.iter().all(|row| ["approve", "execute"].contains(&row.signature.as_str())));
+    assert_eq!(
+        query(Filter {
+            since: Some(5_001),
+            ..all()
+        })
+        .len(),
+        0
+    );
+
+    let rows = query(Filter {
+        limit: 2,
+        ..Filter::default()
+    });
+    assert_eq!(names(&rows), ["TransferEvent", "TransactionQueued"]);
+
+    // Filters combine
+    let rows = query(Filter {
+        name: Some("TransactionApproved".to_string()),
+        tx_id: Some(tx_id),
+        since: Some(4_000),
+        ..all()
+    });
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].signature, "execute");
+}
@@ -0,0 +1,154 @@
+// nc-merkle - builds the Merkle root and proofs for NC Token root-mode lists
+//
+// Usage:
+//   nc-merkle root <list.csv>              print the root to set via governance
+//   nc-merkle proofs <list.csv>            membership proofs for every entry (JSON)
+//   nc-merkle prove <list.csv> <address>   membership or non-membership proof (JSON)
+//
+// The CSV holds one address per line in the first column; a header line and
+// lines starting with '#' are skipped.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::process;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use nc_token::merkle::{MerkleProof, MerkleTree, NonMembershipProof};
+use serde::Serialize;
+
+const USAGE: &str = "usage: nc-merkle <root|proofs|prove> <list.csv> [address]";
+
+#[derive(Serialize)]
+struct ProofJson {
+    index: u32,
+    siblings: Vec<String>,
+}
+
+impl From<&MerkleProof> for ProofJson {
+    fn from(proof: &MerkleProof) -> Self {
+        Self {
+            index: proof.index,
+            siblings: proof.siblings.iter().map(to_hex).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct NonMembershipJson {
+    low: String,
+    low_proof: ProofJson,
+    high: String,
+    high_proof: ProofJson,
+}
+
+impl From<&NonMembershipProof> for NonMembershipJson {
+    fn from(proof: &NonMembershipProof) -> Self {
+        Self {
+            low: proof.low.to_string(),
+            low_proof: (&proof.low_proof).into(),
+            high: proof.high.to_string(),
+            high_proof: (&proof.high_proof).into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ProofsJson {
+    root: String,
+    depth: usize,
+    proofs: BTreeMap<String, ProofJson>,
+}
+
+#[derive(Serialize)]
+struct ProveJson {
+    root: String,
+    address: String,
+    member: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof: Option<ProofJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    non_membership: Option<NonMembershipJson>,
+}
+
+fn main() {
+    if let Err(err) = run(std::env::args().skip(1).collect()) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let (command, csv_path) = match args.as_slice() {
+        [command, csv_path, ..] => (command.as_str(), csv_path),
+        _ => return Err(USAGE.into()),
+    };
+    let tree = MerkleTree::new(read_addresses(csv_path)?);
+    let root = to_hex(&tree.root());
+
+    match (command, args.get(2)) {
+        ("root", None) => {
+            println!("{}", root);
+            eprintln!("{} entries, depth {}", tree.members().count(), tree.depth());
+        }
+        ("proofs", None) => {
+            let proofs = tree
+                .members()
+                .map(|account| {
+                    let proof = tree.prove(account).expect("member has a proof");
+                    (account.to_string(), ProofJson::from(&proof))
+                })
+                .collect();
+            let out = ProofsJson {
+                root,
+                depth: tree.depth(),
+                proofs,
+            };
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        ("prove", Some(address)) => {
+            let account = Pubkey::from_str(address)
+                .map_err(|_| format!("invalid address: {}", address))?;
+            let proof = tree.prove(&account);
+            let non_membership = match proof {
+                Some(_) => None,
+                None => tree.prove_absent(&account),
+            };
+            let out = ProveJson {
+                root,
+                address: account.to_string(),
+                member: proof.is_some(),
+                proof: proof.as_ref().map(ProofJson::from),
+                non_membership: non_membership.as_ref().map(NonMembershipJson::from),
+            };
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+fn read_addresses(path: &str) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut addresses = Vec::new();
+    for (line_no, line) in contents.lines().enumerate() {
+        let field = line.split(',').next().unwrap_or("").trim().trim_matches('"');
+        if field.is_empty() || field.starts_with('#') {
+            continue;
+        }
+        match Pubkey::from_str(field) {
+            Ok(account) => addresses.push(account),
+            // First line may be a header
+            Err(_) if line_no == 0 => continue,
+            Err(_) => {
+                return Err(format!("{}:{}: invalid address '{}'", path, line_no + 1, field).into())
+            }
+        }
+    }
+    Ok(addresses)
+}
+
+fn to_hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}